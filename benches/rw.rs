@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use movetex::Movetex;
+use movetex::{Backoff, Movetex, MovetexCopy};
 use rand::{distributions::Alphanumeric, Rng};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -59,6 +59,30 @@ fn bench_movetex_with_write_small(c: &mut Criterion) {
     });
 }
 
+fn bench_movetex_copy_with_write_small(c: &mut Criterion) {
+    let movetex = Arc::new(MovetexCopy::new(0u128));
+
+    c.bench_function("MovetexCopy read/write small", |b| {
+        b.iter(|| {
+            let m = Arc::clone(&movetex);
+            thread::scope(|s| {
+                for _ in 0..10 {
+                    let m = Arc::clone(&m);
+                    s.spawn(move || {
+                        let _ = m.read();
+                    });
+                }
+                let m = Arc::clone(&m);
+                s.spawn(move || {
+                    m.write(|data| {
+                        *data = 1;
+                    });
+                });
+            });
+        })
+    });
+}
+
 fn bench_movetex_with_write(c: &mut Criterion) {
     let movetex = Arc::new(Movetex::new(generate_large_string()));
 
@@ -130,6 +154,34 @@ fn bench_movetex_with_multiple_writes(c: &mut Criterion) {
     });
 }
 
+fn bench_movetex_with_multiple_writes_backoff(c: &mut Criterion) {
+    let movetex = Arc::new(Movetex::new(generate_large_string()));
+
+    c.bench_function("Movetex multiple writes with backoff", |b| {
+        b.iter(|| {
+            let m = Arc::clone(&movetex);
+            thread::scope(|s| {
+                for _ in 0..10 {
+                    let m = Arc::clone(&m);
+                    s.spawn(move || {
+                        m.write_with_backoff(
+                            |data| {
+                                *data = generate_large_string();
+                            },
+                            Backoff::new(),
+                        )
+                        .unwrap();
+                    });
+                }
+                let m = Arc::clone(&m);
+                s.spawn(move || {
+                    let _ = m.read();
+                });
+            });
+        })
+    });
+}
+
 fn bench_mutex_with_multiple_writes(c: &mut Criterion) {
     let mutex = Arc::new(Mutex::new(generate_large_string()));
 
@@ -157,7 +209,9 @@ criterion_group! {
     name = benches;
     config = Criterion::default().measurement_time(std::time::Duration::from_secs(6));
     targets = bench_mutex_with_write_small, bench_movetex_with_write_small,
-    bench_mutex_with_write, bench_movetex_with_write, bench_mutex_with_multiple_writes, bench_movetex_with_multiple_writes
+    bench_movetex_copy_with_write_small,
+    bench_mutex_with_write, bench_movetex_with_write, bench_mutex_with_multiple_writes, bench_movetex_with_multiple_writes,
+    bench_movetex_with_multiple_writes_backoff
 }
 
 criterion_main!(benches);
@@ -0,0 +1,125 @@
+//! Hot-reloadable configuration served over HTTP.
+//!
+//! A minimal HTTP server answers `GET /config` from a `Movetex` and applies `PATCH /config`
+//! bodies as JSON merge patches. Request handlers only ever read; the patch handler is the single
+//! writer. The example drives itself with a scripted client and exits, so it can run in CI:
+//!
+//! ```text
+//! cargo run --example hot_config_server --features demo
+//! ```
+
+use movetex::{HealthCheck, HealthPolicy, Movetex};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Config {
+    greeting: String,
+    max_connections: u32,
+    features: Vec<String>,
+}
+
+fn handle(stream: TcpStream, config: &Movetex<Config>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let (status, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["GET", "/config"] => ("200 OK", serde_json::to_string(&*config.read())?),
+        ["PATCH", "/config"] => {
+            let patch = RawValue::from_string(String::from_utf8_lossy(&body).into_owned())?;
+            match config.write_patch(&patch) {
+                Ok(true) => ("200 OK", serde_json::to_string(&*config.read())?),
+                Ok(false) => ("409 Conflict", String::from("write in progress")),
+                Err(e) => ("422 Unprocessable Entity", e.to_string()),
+            }
+        }
+        ["GET", "/health"] => {
+            let health = config.health(&HealthPolicy::default());
+            (
+                if health.is_healthy() {
+                    "200 OK"
+                } else {
+                    "503 Service Unavailable"
+                },
+                format!("{health:?}"),
+            )
+        }
+        _ => ("404 Not Found", String::new()),
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn request(addr: &str, method: &str, path: &str, body: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\ncontent-length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.lines().last().unwrap_or_default().to_string()
+}
+
+fn main() {
+    let config = Arc::new(Movetex::new(Config {
+        greeting: String::from("hello"),
+        max_connections: 64,
+        features: vec![String::from("basic")],
+    }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let requests = 5;
+
+    let server = {
+        let config = Arc::clone(&config);
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let config = Arc::clone(&config);
+                thread::spawn(move || handle(stream.unwrap(), &config).unwrap());
+            }
+        })
+    };
+
+    println!("GET    /config -> {}", request(&addr, "GET", "/config", ""));
+    println!(
+        "PATCH  /config -> {}",
+        request(&addr, "PATCH", "/config", r#"{"max_connections": 256}"#)
+    );
+    println!(
+        "PATCH  /config -> {}",
+        request(&addr, "PATCH", "/config", r#"{"max_connections": "many"}"#)
+    );
+    println!("GET    /config -> {}", request(&addr, "GET", "/config", ""));
+    println!("GET    /health -> {}", request(&addr, "GET", "/health", ""));
+
+    server.join().unwrap();
+    assert_eq!(config.read().max_connections, 256);
+}
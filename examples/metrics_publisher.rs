@@ -0,0 +1,85 @@
+//! Periodic metrics snapshots published to many readers.
+//!
+//! A collector thread aggregates samples and publishes a fresh snapshot every tick, labelling
+//! each publish with its own name. Reporter threads read the latest snapshot without ever
+//! blocking the collector, and a watchdog checks the instance health:
+//!
+//! ```text
+//! cargo run --example metrics_publisher --features demo
+//! ```
+
+use movetex::{HealthCheck, HealthPolicy, Movetex};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default)]
+struct Metrics {
+    tick: u64,
+    counters: BTreeMap<&'static str, u64>,
+}
+
+fn main() {
+    let metrics = Arc::new(
+        Movetex::builder(Metrics::default())
+            .track_writers(true)
+            .build(),
+    );
+    let ticks = 20;
+
+    thread::scope(|s| {
+        let m = Arc::clone(&metrics);
+        thread::Builder::new()
+            .name(String::from("collector"))
+            .spawn_scoped(s, move || {
+                for tick in 1..=ticks {
+                    while !m.write_as("collector", |metrics| {
+                        metrics.tick = tick;
+                        *metrics.counters.entry("requests").or_default() += tick * 3;
+                        *metrics.counters.entry("errors").or_default() += tick % 2;
+                    }) {
+                        thread::yield_now();
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                }
+            })
+            .unwrap();
+
+        for reporter in 0..3 {
+            let m = Arc::clone(&metrics);
+            s.spawn(move || {
+                let mut last = 0;
+                while last < ticks {
+                    let snapshot = m.read();
+                    if snapshot.tick != last {
+                        last = snapshot.tick;
+                        if reporter == 0 {
+                            println!("tick {:>2}: {:?}", snapshot.tick, snapshot.counters);
+                        }
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                }
+            });
+        }
+
+        let m = Arc::clone(&metrics);
+        s.spawn(move || {
+            let policy = HealthPolicy {
+                max_publish_age: Some(Duration::from_secs(1)),
+                ..HealthPolicy::default()
+            };
+            for _ in 0..4 {
+                println!("watchdog: healthy = {}", m.health(&policy).is_healthy());
+                thread::sleep(Duration::from_millis(25));
+            }
+        });
+    });
+
+    let writer = metrics.last_writer().unwrap();
+    println!(
+        "last publish by {:?} ({:?})",
+        writer.label, writer.thread_name
+    );
+    assert_eq!(metrics.read().tick, ticks);
+}
@@ -0,0 +1,103 @@
+//! Hot-swapping a plugin while workers keep calling it.
+//!
+//! Workers wait for the first plugin to be loaded (`first_publish`), then call whatever plugin
+//! is currently published. A loader replaces the plugin at runtime; in-flight calls finish on
+//! the plugin they started with:
+//!
+//! ```text
+//! cargo run --example plugin_hot_swap --features demo
+//! ```
+
+use movetex::Movetex;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+trait Plugin: Send + Sync {
+    fn name(&self) -> &str;
+    fn transform(&self, input: u64) -> u64;
+}
+
+struct Doubler;
+
+impl Plugin for Doubler {
+    fn name(&self) -> &str {
+        "doubler"
+    }
+
+    fn transform(&self, input: u64) -> u64 {
+        input * 2
+    }
+}
+
+struct Squarer;
+
+impl Plugin for Squarer {
+    fn name(&self) -> &str {
+        "squarer"
+    }
+
+    fn transform(&self, input: u64) -> u64 {
+        input * input
+    }
+}
+
+/// Wakes the thread blocked in `block_on`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn main() {
+    let plugin = Arc::new(Movetex::<Option<Arc<dyn Plugin>>>::empty());
+    let calls = Arc::new(AtomicU64::new(0));
+
+    thread::scope(|s| {
+        for worker in 0..4u64 {
+            let plugin = Arc::clone(&plugin);
+            let calls = Arc::clone(&calls);
+            s.spawn(move || {
+                block_on(plugin.first_publish());
+                for input in 0..50 {
+                    let current = plugin.read().clone().expect("plugin is published");
+                    let output = current.transform(input);
+                    if input % 25 == 0 {
+                        println!("worker {worker}: {}({input}) = {output}", current.name());
+                    }
+                    calls.fetch_add(1, Ordering::Relaxed);
+                    thread::sleep(Duration::from_millis(1));
+                }
+            });
+        }
+
+        thread::sleep(Duration::from_millis(10));
+        println!("loading doubler");
+        assert!(plugin.write(|p| *p = Some(Arc::new(Doubler))));
+
+        thread::sleep(Duration::from_millis(20));
+        println!("hot-swapping to squarer");
+        assert!(plugin.write(|p| *p = Some(Arc::new(Squarer))));
+    });
+
+    println!("{} calls served", calls.load(Ordering::Relaxed));
+    assert_eq!(calls.load(Ordering::Relaxed), 200);
+}
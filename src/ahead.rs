@@ -0,0 +1,107 @@
+//! Clone-ahead pipelining of the writer copy.
+//!
+//! Normally a write clones the new value into the next writer copy before it returns. With
+//! clone-ahead enabled, the write publishes the value it modified and hands the clone to a
+//! worker thread owned by the instance; the next operation that needs the writer copy picks up
+//! the result, waiting only if the clone has not finished yet.
+//!
+//! While a clone is pending, `ptr_w` is null and the published snapshot being cloned is kept
+//! alive: replacing it requires the writer copy, and every path that takes the writer copy, as
+//! well as `Drop`, first collects the pending clone.
+//!
+//! A panic in the clone is caught on the worker and resumed by the operation collecting the
+//! clone, which holds the writer copy so that the unwind poisons the instance, like a clone
+//! panicking in a regular write. `Drop` discards it instead.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::spill::Slot;
+use crate::sync::Mutex;
+
+/// Starts the worker thread of an instance, if a thread can be started.
+pub(crate) type Spawn<T> = fn() -> Option<Worker<T>>;
+
+pub(crate) struct CloneAhead<T> {
+    spawn: Spawn<T>,
+    // Started by the first publish
+    worker: Mutex<Option<Worker<T>>>,
+}
+
+impl<T> CloneAhead<T> {
+    pub(crate) fn new(spawn: Spawn<T>) -> Self {
+        Self {
+            spawn,
+            worker: Mutex::new(None),
+        }
+    }
+
+    /// Starts preparing the next writer copy from `published`; returns `false` if the worker
+    /// thread could not be started, in which case the caller must prepare it.
+    ///
+    /// `published` must stay alive until [`CloneAhead::finish`] has returned the clone.
+    pub(crate) fn start(&self, published: &Slot<T>) -> bool {
+        let mut worker = self.worker.lock();
+        if worker.is_none() {
+            *worker = (self.spawn)();
+        }
+        let Some(worker) = worker.as_mut() else {
+            return false;
+        };
+        debug_assert!(!worker.pending, "previous clone was not collected");
+        worker.pending = worker.jobs.send(Published(published)).is_ok();
+        worker.pending
+    }
+
+    /// Waits for the pending clone, if any, and returns it, or the panic it raised.
+    pub(crate) fn finish(&self) -> Option<thread::Result<Slot<T>>> {
+        let mut worker = self.worker.lock();
+        let worker = worker.as_mut().filter(|worker| worker.pending)?;
+        worker.pending = false;
+        worker.clones.recv().ok()
+    }
+}
+
+/// A thread cloning published slots for one instance; it exits once the instance drops the
+/// queue.
+pub(crate) struct Worker<T> {
+    jobs: Sender<Published<T>>,
+    clones: Receiver<thread::Result<Slot<T>>>,
+    // Set while a clone has been started and not collected yet
+    pending: bool,
+}
+
+/// The [`Spawn`] function for payloads that can be cloned on another thread.
+pub(crate) fn spawn<T: Clone + Send + Sync + 'static>() -> Option<Worker<T>> {
+    let (jobs, queue) = mpsc::channel::<Published<T>>();
+    let (done, clones) = mpsc::channel();
+    thread::Builder::new()
+        .name("movetex-clone-ahead".into())
+        .spawn(move || {
+            for published in queue {
+                let clone = panic::catch_unwind(AssertUnwindSafe(|| published.get().clone()));
+                if done.send(clone).is_err() {
+                    break;
+                }
+            }
+        })
+        .ok()?;
+    Some(Worker {
+        jobs,
+        clones,
+        pending: false,
+    })
+}
+
+/// A published slot sent to the worker thread.
+struct Published<T>(*const Slot<T>);
+
+// The slot is only read, and outlives the clone as documented on `CloneAhead::start`.
+unsafe impl<T: Sync> Send for Published<T> {}
+
+impl<T> Published<T> {
+    fn get(&self) -> &Slot<T> {
+        unsafe { &*self.0 }
+    }
+}
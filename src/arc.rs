@@ -0,0 +1,91 @@
+//! A `Movetex` for values that are not `Clone`, published behind an `Arc`.
+
+use std::sync::Arc;
+
+use crate::{Movetex, ReadGuard, WriteError};
+
+/// A `Movetex` over `Arc<T>`, for values that cannot be cloned or are too expensive to clone.
+///
+/// The writer copy of the wrapped instance is a second `Arc` to the published value, so
+/// publishing never deep-clones `T`: every write builds a new value from the published one and
+/// swaps the pointer. The trade-off against [`Movetex`] is that writes cannot modify the value
+/// in place, and that [`MovetexArc::load`] hands out reference-counted pointers that keep a
+/// value alive beyond the next publish.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexArc;
+/// use std::sync::Mutex;
+///
+/// // `Mutex` is not `Clone`, so it could not be published by a plain `Movetex`.
+/// let state = MovetexArc::new(Mutex::new(vec![1]));
+///
+/// state.store(Mutex::new(vec![1, 2])).unwrap();
+/// assert_eq!(*state.read().lock().unwrap(), [1, 2]);
+/// ```
+pub struct MovetexArc<T> {
+    movetex: Movetex<Arc<T>>,
+}
+
+impl<T> MovetexArc<T> {
+    /// Creates an instance publishing `value`.
+    pub fn new(value: T) -> Self {
+        Self::from_arc(Arc::new(value))
+    }
+
+    /// Creates an instance publishing the value behind `value` without moving it.
+    pub fn from_arc(value: Arc<T>) -> Self {
+        Self {
+            movetex: Movetex::new(value),
+        }
+    }
+
+    /// Returns the published value, see [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        ReadGuard::map(self.movetex.read(), |value| &**value)
+    }
+
+    /// Returns a pointer to the published value that stays valid after later publishes.
+    pub fn load(&self) -> Arc<T> {
+        Arc::clone(&self.movetex.read())
+    }
+
+    /// Publishes `value`. Fails like [`Movetex::try_write`].
+    pub fn store(&self, value: T) -> Result<(), WriteError> {
+        self.store_arc(Arc::new(value))
+    }
+
+    /// Publishes the value behind `value` without moving it. Fails like [`Movetex::try_write`].
+    pub fn store_arc(&self, value: Arc<T>) -> Result<(), WriteError> {
+        self.movetex.try_write(|current| *current = value)
+    }
+
+    /// Publishes the value `f` builds from the published one. Fails like
+    /// [`Movetex::try_write`], without calling `f` if the write cannot start.
+    pub fn update(&self, f: impl FnOnce(&T) -> T) -> Result<(), WriteError> {
+        self.movetex
+            .try_write(|current| *current = Arc::new(f(current)))
+    }
+
+    /// Same as [`MovetexArc::update`], but recomputes the value if another write publishes in
+    /// the meantime, see [`Movetex::rcu`].
+    pub fn rcu(&self, f: impl Fn(&T) -> T) -> Result<(), WriteError> {
+        self.movetex.rcu(|current| Arc::new(f(current)))
+    }
+
+    /// The wrapped instance.
+    pub fn movetex(&self) -> &Movetex<Arc<T>> {
+        &self.movetex
+    }
+
+    /// Returns the published value.
+    pub fn into_inner(self) -> Arc<T> {
+        self.movetex.into_inner()
+    }
+}
+
+impl<T: Default> Default for MovetexArc<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
@@ -0,0 +1,111 @@
+//! Interoperability with the `arc-swap` crate (feature `arc-swap`).
+//!
+//! Projects moving between `ArcSwap` and `Movetex` rarely switch every call site at once. The
+//! conversions below share the underlying `Arc` wherever possible, and [`ArcSwapMirror`] keeps
+//! both primitives in step while the migration is in progress.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::sync::Mutex;
+use crate::Movetex;
+
+impl<T: Clone> From<ArcSwap<T>> for Movetex<T> {
+    /// Creates a `Movetex` whose reader and writer copies share the `Arc` held by the `ArcSwap`.
+    ///
+    /// The result uses [`SpillPolicy::always`](crate::SpillPolicy::always), so later snapshots
+    /// stay `Arc`-backed and can be handed back to `arc-swap` without cloning.
+    fn from(value: ArcSwap<T>) -> Self {
+        Movetex::from_arc(value.into_inner())
+    }
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Converts the `Movetex` into an `ArcSwap` holding the published value.
+    ///
+    /// If the value is spilled (see [`SpillPolicy`](crate::SpillPolicy)), the existing `Arc` is
+    /// handed over without cloning the payload.
+    pub fn into_arc_swap(self) -> ArcSwap<T> {
+        ArcSwap::new(self.published_arc())
+    }
+}
+
+/// Keeps a `Movetex` and an `ArcSwap` in step during a migration between the two crates.
+///
+/// Every write goes through the `Movetex` and the published result is then stored into the
+/// `ArcSwap`, so code that still reads from the `ArcSwap` observes the same snapshots. When the
+/// `Movetex` has spilled its payload, both primitives share a single allocation.
+/// [`ArcSwapMirror::is_consistent`] compares the two views, which is useful as an assertion while
+/// call sites are ported one at a time.
+///
+/// ### Usage Example:
+/// ```rust
+/// use arc_swap::ArcSwap;
+/// use movetex::ArcSwapMirror;
+/// use std::sync::Arc;
+///
+/// let legacy = Arc::new(ArcSwap::from_pointee(1));
+/// let mirror = ArcSwapMirror::new(Arc::clone(&legacy));
+///
+/// assert!(mirror.write(|v| *v = 2));
+/// assert_eq!(**legacy.load(), 2);
+/// assert_eq!(*mirror.movetex().read(), 2);
+/// assert!(mirror.is_consistent());
+/// ```
+pub struct ArcSwapMirror<T: Clone> {
+    movetex: Movetex<T>,
+    arc_swap: Arc<ArcSwap<T>>,
+    // Keeps the publish and the store into `arc_swap` in the same order across writers
+    gate: Mutex<()>,
+}
+
+impl<T: Clone> ArcSwapMirror<T> {
+    /// Creates a mirror seeded from the current value of `arc_swap`.
+    pub fn new(arc_swap: Arc<ArcSwap<T>>) -> Self {
+        Self {
+            movetex: Movetex::from_arc(arc_swap.load_full()),
+            arc_swap,
+            gate: Mutex::new(()),
+        }
+    }
+
+    /// Applies `f` through [`Movetex::write`] and stores the published result into the `ArcSwap`.
+    ///
+    /// Returns `false` without touching either primitive if the write could not be acquired.
+    pub fn write(&self, f: impl FnOnce(&mut T)) -> bool {
+        let Some(_gate) = self.gate.try_lock() else {
+            return false;
+        };
+        if !self.movetex.write(f) {
+            return false;
+        }
+        self.arc_swap.store(self.movetex.published_arc());
+        true
+    }
+
+    /// The `Movetex` side of the mirror.
+    pub fn movetex(&self) -> &Movetex<T> {
+        &self.movetex
+    }
+
+    /// The `ArcSwap` side of the mirror.
+    pub fn arc_swap(&self) -> &Arc<ArcSwap<T>> {
+        &self.arc_swap
+    }
+
+    /// Returns `true` if both primitives currently publish equal values.
+    ///
+    /// Writes made directly to the `ArcSwap`, bypassing the mirror, show up here as divergence.
+    pub fn is_consistent(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        *self.movetex.read() == **self.arc_swap.load()
+    }
+
+    /// Consumes the mirror, keeping only the `Movetex`.
+    pub fn into_movetex(self) -> Movetex<T> {
+        self.movetex
+    }
+}
@@ -0,0 +1,316 @@
+//! A stress and benchmark runner for comparing `Movetex` with `Mutex` and `RwLock` on your own
+//! payload types and hardware (feature `bench`).
+//!
+//! The crate's criterion benches measure fixed payloads on the machine they run on. Whether
+//! `Movetex` pays off depends on the payload's clone cost and the read/write mix, so this module
+//! runs the same workload against all three primitives and reports throughput and latency
+//! distributions for each.
+//!
+//! ### Usage Example:
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use movetex::bench::{self, Profile};
+//!
+//! let profile = Profile::new(vec![0u64; 1024], |v| v[0] += 1)
+//!     .readers(2)
+//!     .writers(0)
+//!     .duration(Duration::from_millis(20));
+//!
+//! let report = bench::run(&profile);
+//! println!(
+//!     "reads/s: movetex {:.0}, mutex {:.0}, rwlock {:.0}",
+//!     report.movetex.read_throughput(),
+//!     report.mutex.read_throughput(),
+//!     report.rw_lock.read_throughput(),
+//! );
+//! assert!(report.movetex.reads > 0);
+//! ```
+
+use std::fmt;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::Movetex;
+
+/// The workload run against every primitive.
+pub struct Profile<T> {
+    payload: T,
+    mutate: fn(&mut T),
+    readers: usize,
+    writers: usize,
+    duration: Duration,
+}
+
+impl<T> Profile<T> {
+    /// A workload that reads `payload` and writes it by applying `mutate`, with four reader
+    /// threads and one writer thread for one second per primitive.
+    pub fn new(payload: T, mutate: fn(&mut T)) -> Self {
+        Self {
+            payload,
+            mutate,
+            readers: 4,
+            writers: 1,
+            duration: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the number of threads that read in a loop.
+    pub fn readers(mut self, readers: usize) -> Self {
+        self.readers = readers;
+        self
+    }
+
+    /// Sets the number of threads that write in a loop.
+    pub fn writers(mut self, writers: usize) -> Self {
+        self.writers = writers;
+        self
+    }
+
+    /// Sets how long the workload runs against each primitive.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// The measurements of one primitive.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    /// Completed reads.
+    pub reads: u64,
+    /// Completed writes.
+    pub writes: u64,
+    /// Writes that were not performed because another write was in progress; only `Movetex`
+    /// reports these.
+    pub failed_writes: u64,
+    /// How long the workload ran.
+    pub elapsed: Duration,
+    /// Latencies of completed reads.
+    pub read_latency: LatencyHistogram,
+    /// Latencies of completed writes.
+    pub write_latency: LatencyHistogram,
+}
+
+impl Measurement {
+    /// Completed reads per second.
+    pub fn read_throughput(&self) -> f64 {
+        self.reads as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Completed writes per second.
+    pub fn write_throughput(&self) -> f64 {
+        self.writes as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// The result of [`run`].
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The workload run against `Movetex`.
+    pub movetex: Measurement,
+    /// The workload run against `std::sync::Mutex`.
+    pub mutex: Measurement,
+    /// The workload run against `std::sync::RwLock`.
+    pub rw_lock: Measurement,
+}
+
+/// A latency distribution with power-of-two nanosecond buckets.
+///
+/// Percentiles are reported as the upper bound of their bucket, so they overestimate by less
+/// than a factor of two.
+#[derive(Clone)]
+pub struct LatencyHistogram {
+    buckets: [u64; 64],
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: [0; 64],
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    fn record(&mut self, latency: Duration) {
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.buckets[(u64::BITS - nanos.leading_zeros()) as usize % 64] += 1;
+        self.count += 1;
+        self.total += latency;
+        self.max = self.max.max(latency);
+    }
+
+    fn merge(&mut self, other: &LatencyHistogram) {
+        for (bucket, other) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += other;
+        }
+        self.count += other.count;
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+
+    /// The number of recorded operations.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean latency, or zero if nothing was recorded.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => self.total / u32::try_from(count).unwrap_or(u32::MAX),
+        }
+    }
+
+    /// The highest recorded latency.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The latency below which the fraction `quantile` (between 0 and 1) of operations
+    /// completed.
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        let target = (self.count as f64 * quantile.clamp(0.0, 1.0)).ceil() as u64;
+        let mut seen = 0;
+        for (bits, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target.max(1) {
+                let upper = 1u64.checked_shl(bits as u32).unwrap_or(u64::MAX);
+                return Duration::from_nanos(upper).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+impl fmt::Debug for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyHistogram")
+            .field("count", &self.count)
+            .field("mean", &self.mean())
+            .field("p50", &self.percentile(0.5))
+            .field("p99", &self.percentile(0.99))
+            .field("max", &self.max)
+            .finish()
+    }
+}
+
+/// Runs `profile` against `Movetex`, `Mutex`, and `RwLock`, one after another.
+///
+/// Blocks for three times the profile's duration.
+pub fn run<T: Clone + Send + Sync>(profile: &Profile<T>) -> Report {
+    let mutate = profile.mutate;
+
+    let movetex = Movetex::new(profile.payload.clone());
+    let movetex = measure(
+        profile,
+        || {
+            black_box(movetex.read());
+        },
+        || movetex.write(mutate),
+    );
+
+    let mutex = Mutex::new(profile.payload.clone());
+    let mutex = measure(
+        profile,
+        || drop(black_box(mutex.lock().unwrap_or_else(|e| e.into_inner()))),
+        || {
+            mutate(&mut mutex.lock().unwrap_or_else(|e| e.into_inner()));
+            true
+        },
+    );
+
+    let rw_lock = RwLock::new(profile.payload.clone());
+    let rw_lock = measure(
+        profile,
+        || drop(black_box(rw_lock.read().unwrap_or_else(|e| e.into_inner()))),
+        || {
+            mutate(&mut rw_lock.write().unwrap_or_else(|e| e.into_inner()));
+            true
+        },
+    );
+
+    Report {
+        movetex,
+        mutex,
+        rw_lock,
+    }
+}
+
+fn measure<T>(
+    profile: &Profile<T>,
+    read: impl Fn() + Sync,
+    write: impl Fn() -> bool + Sync,
+) -> Measurement {
+    let stop = AtomicBool::new(false);
+    let start = Instant::now();
+    let (readers, writers) = thread::scope(|s| {
+        let readers: Vec<_> = (0..profile.readers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut latency = LatencyHistogram::new();
+                    while !stop.load(Ordering::Relaxed) {
+                        let started = Instant::now();
+                        read();
+                        latency.record(started.elapsed());
+                    }
+                    latency
+                })
+            })
+            .collect();
+        let writers: Vec<_> = (0..profile.writers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut latency = LatencyHistogram::new();
+                    let mut failed = 0;
+                    while !stop.load(Ordering::Relaxed) {
+                        let started = Instant::now();
+                        if write() {
+                            latency.record(started.elapsed());
+                        } else {
+                            failed += 1;
+                        }
+                    }
+                    (latency, failed)
+                })
+            })
+            .collect();
+        thread::sleep(profile.duration);
+        stop.store(true, Ordering::Relaxed);
+        let readers: Vec<_> = readers.into_iter().map(join).collect();
+        let writers: Vec<_> = writers.into_iter().map(join).collect();
+        (readers, writers)
+    });
+    let elapsed = start.elapsed();
+
+    let mut read_latency = LatencyHistogram::new();
+    readers.iter().for_each(|h| read_latency.merge(h));
+    let mut write_latency = LatencyHistogram::new();
+    let mut failed_writes = 0;
+    for (h, failed) in &writers {
+        write_latency.merge(h);
+        failed_writes += failed;
+    }
+    Measurement {
+        reads: read_latency.count(),
+        writes: write_latency.count(),
+        failed_writes,
+        elapsed,
+        read_latency,
+        write_latency,
+    }
+}
+
+fn join<R>(handle: thread::ScopedJoinHandle<'_, R>) -> R {
+    handle
+        .join()
+        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+}
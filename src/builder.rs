@@ -0,0 +1,318 @@
+//! Builder for configuring a `Movetex` before it is shared.
+
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ahead::{self, Spawn};
+use crate::checksum::{Checksum, Repair};
+use crate::clock::{self, Clock};
+use crate::dropper;
+use crate::intern::{InternPool, Interner};
+use crate::ordering::MemoryOrdering;
+use crate::snapshot::Snapshot;
+use crate::spill::{Slot, SpillPolicy};
+use crate::validate::Validator;
+use crate::Movetex;
+
+/// Configures optional behaviour of a [`Movetex`] before construction.
+///
+/// `Movetex::new` covers the common case; the builder is used when one of the optional
+/// policies has to be changed from its default.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, SpillPolicy};
+///
+/// let movetex = Movetex::builder(vec![0u8; 16])
+///     .spill(SpillPolicy::new(64 * 1024, |v: &Vec<u8>| v.len()))
+///     .build();
+///
+/// assert_eq!(movetex.read().len(), 16);
+/// ```
+pub struct MovetexBuilder<T: Clone> {
+    // `None` for instances created with `Movetex::uninit`
+    pub(crate) value: Option<Slot<T>>,
+    pub(crate) spill: SpillPolicy<T>,
+    pub(crate) validator: Option<Box<dyn Validator<T>>>,
+    // `false` for placeholder values that do not count as published yet
+    pub(crate) published: bool,
+    pub(crate) track_writers: bool,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) fallback: Option<T>,
+    pub(crate) write_once: bool,
+    pub(crate) checksum: Option<Checksum<T>>,
+    pub(crate) clone_ahead: Option<Spawn<T>>,
+    pub(crate) drop_later: Option<fn(Box<Snapshot<T>>)>,
+    pub(crate) repair: Option<Repair<T>>,
+    pub(crate) on_retire: Option<fn(T)>,
+    pub(crate) intern: Option<Interner<T>>,
+    pub(crate) max_write_duration: Option<Duration>,
+    pub(crate) memory_ordering: MemoryOrdering,
+    #[cfg(feature = "hdrhistogram")]
+    pub(crate) record_latencies: bool,
+}
+
+impl<T: Clone> MovetexBuilder<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self::from_slot(Slot::Inline(value))
+    }
+
+    pub(crate) fn from_slot(value: Slot<T>) -> Self {
+        Self::with_value(Some(value))
+    }
+
+    pub(crate) fn uninit() -> Self {
+        Self::with_value(None)
+    }
+
+    fn with_value(value: Option<Slot<T>>) -> Self {
+        Self {
+            published: value.is_some(),
+            value,
+            spill: SpillPolicy::default(),
+            validator: None,
+            track_writers: false,
+            clock: clock::system(),
+            fallback: None,
+            write_once: false,
+            checksum: None,
+            clone_ahead: None,
+            drop_later: None,
+            repair: None,
+            on_retire: None,
+            intern: None,
+            max_write_duration: None,
+            memory_ordering: MemoryOrdering::default(),
+            #[cfg(feature = "hdrhistogram")]
+            record_latencies: false,
+        }
+    }
+
+    /// Stores a checksum computed by `checksum` with every published snapshot and verifies it on
+    /// every read.
+    ///
+    /// This is a paranoid mode for debug builds and test environments: it catches memory
+    /// corruption or unsound `unsafe` code in downstream crates that modified a snapshot after it
+    /// was published, before the damage propagates. Mismatches are reported through
+    /// [`set_invariant_handler`](crate::set_invariant_handler). Each read pays for a full
+    /// checksum computation.
+    ///
+    /// ```rust
+    /// use std::hash::{BuildHasher, RandomState};
+    /// use std::sync::OnceLock;
+    ///
+    /// use movetex::Movetex;
+    ///
+    /// fn checksum(value: &Vec<u32>) -> u64 {
+    ///     static STATE: OnceLock<RandomState> = OnceLock::new();
+    ///     STATE.get_or_init(RandomState::new).hash_one(value)
+    /// }
+    ///
+    /// let mut builder = Movetex::builder(vec![1, 2, 3]);
+    /// if cfg!(debug_assertions) {
+    ///     builder = builder.checksum(checksum);
+    /// }
+    /// assert_eq!(builder.build().read().len(), 3);
+    /// ```
+    pub fn checksum(mut self, checksum: fn(&T) -> u64) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// Sets a hook that repairs a published snapshot found to be bad, so readers self-heal
+    /// without a restart.
+    ///
+    /// It receives the bad value and returns the value to publish in its place, or `None` to
+    /// keep serving the bad one. Readers never run it: a read that finds a
+    /// [checksum](MovetexBuilder::checksum) mismatch serves the snapshot as it is and leaves the
+    /// repair to the next write, which starts from the repaired value instead of the bad one.
+    /// [`Movetex::verify_published`](crate::Movetex::verify_published) repairs right away, also
+    /// for a snapshot the [validator](MovetexBuilder::validator) rejects, and publishes the
+    /// repaired value like a regular write, so it passes the validator first.
+    pub fn repair(mut self, repair: fn(&T) -> Option<T>) -> Self {
+        self.repair = Some(repair);
+        self
+    }
+
+    /// Hands every retired value to `sink` instead of dropping it.
+    ///
+    /// A value is retired when a publish replaces it and no reader can see it anymore. The sink
+    /// can recycle it into an object pool, log it, or send it to a low-priority thread so its
+    /// destruction does not run on the writer's path. It runs on a publishing thread, never on
+    /// a reader: before the write returns, or, if a [`ReadGuard`](crate::ReadGuard) still holds
+    /// the value, in the first publish after the last guard is dropped. A spilled value is only
+    /// handed over if no other copy shares its allocation; the values held when the instance
+    /// itself is dropped are dropped normally.
+    pub fn on_retire(mut self, sink: fn(T)) -> Self {
+        self.on_retire = Some(sink);
+        self
+    }
+
+    /// Shares published values with other instances configured with the same `pool`.
+    ///
+    /// Every published value is looked up in the pool, and stored behind the `Arc` of an equal
+    /// value another instance already publishes, regardless of the
+    /// [spill policy](MovetexBuilder::spill). The writer copy stays private to the instance.
+    pub fn intern(mut self, pool: &Arc<InternPool<T>>) -> Self
+    where
+        T: Hash + Eq + Send + Sync + 'static,
+    {
+        let pool = Arc::clone(pool);
+        self.intern = Some(Box::new(move |value| pool.intern(value)));
+        self
+    }
+
+    /// Discards writes whose closure held the writer copy for longer than `limit`.
+    ///
+    /// A write whose closure returns after `limit` has elapsed is not published: the writer
+    /// copy is restored from the published value like after a rejected write, and the write
+    /// fails with [`WriteError::TimedOut`](crate::WriteError::TimedOut). This keeps slow
+    /// updates from publishing values computed from a stale view and bounds how long waiting
+    /// writers see the instance busy per write. A closure cannot be interrupted while it runs,
+    /// so the limit is enforced when it returns; a closure that never returns still holds the
+    /// writer copy forever.
+    pub fn max_write_duration(mut self, limit: Duration) -> Self {
+        self.max_write_duration = Some(limit);
+        self
+    }
+
+    /// Sets the memory orderings the published value is stored and loaded with, and the writer
+    /// copy is handed between writers with.
+    ///
+    /// The default, [`MemoryOrdering::AcquireRelease`], is all a single instance needs; see
+    /// [`MemoryOrdering`] for when [`MemoryOrdering::Mixed`] or [`MemoryOrdering::SeqCst`] is
+    /// worth its cost.
+    ///
+    /// ```rust
+    /// use movetex::{MemoryOrdering, Movetex};
+    ///
+    /// let movetex = Movetex::builder(1u32)
+    ///     .memory_ordering(MemoryOrdering::SeqCst)
+    ///     .build();
+    /// assert!(movetex.write(|v| *v += 1));
+    /// assert_eq!(*movetex.read(), 2);
+    /// ```
+    pub fn memory_ordering(mut self, ordering: MemoryOrdering) -> Self {
+        self.memory_ordering = ordering;
+        self
+    }
+
+    /// Sets the policy deciding when the payload is moved behind an `Arc`.
+    ///
+    /// See [`SpillPolicy`] for details. The default policy never spills.
+    pub fn spill(mut self, policy: SpillPolicy<T>) -> Self {
+        self.spill = policy;
+        self
+    }
+
+    /// Attaches a [`Validator`] that every written value must pass before it is published.
+    ///
+    /// Rejected writes leave the published value untouched and are reported as
+    /// [`WriteError::Rejected`](crate::WriteError::Rejected) by [`Movetex::try_write`].
+    pub fn validator(mut self, validator: impl Validator<T> + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Records the thread and optional label of every publish, see
+    /// [`Movetex::last_writer`](crate::Movetex::last_writer).
+    ///
+    /// Tracking is disabled by default because it takes a short lock on every publish.
+    pub fn track_writers(mut self, enabled: bool) -> Self {
+        self.track_writers = enabled;
+        self
+    }
+
+    /// Makes the instance immutable as soon as it holds a value.
+    ///
+    /// An instance built with a value is sealed from the start; one built with
+    /// [`Movetex::uninit_builder`](crate::Movetex::uninit_builder) is sealed by its first
+    /// [`Movetex::initialize`](crate::Movetex::initialize) or
+    /// [`Movetex::publish_once`](crate::Movetex::publish_once). Afterwards every write path fails
+    /// with [`WriteError::Sealed`](crate::WriteError::Sealed), while reads stay lock-free.
+    pub fn write_once(mut self) -> Self {
+        self.write_once = true;
+        self
+    }
+
+    /// Records read and write latencies into HDR histograms, see
+    /// [`Movetex::latency_report`](crate::Movetex::latency_report).
+    ///
+    /// Every write takes short locks to record its samples. Reads only record when no other
+    /// reader is recording at the same moment, so they never wait.
+    #[cfg(feature = "hdrhistogram")]
+    pub fn record_latencies(mut self) -> Self {
+        self.record_latencies = true;
+        self
+    }
+
+    /// Sets the time source for time-based features such as health reports.
+    ///
+    /// Defaults to [`SystemClock`](crate::SystemClock); tests can pass a
+    /// [`MockClock`](crate::MockClock) instead of sleeping.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Sets the value readers see through [`Movetex::read`](crate::Movetex::read) while the
+    /// instance has no published value to serve, instead of panicking.
+    ///
+    /// This keeps request paths free of branches on primitive-internal states: an instance
+    /// created with [`Movetex::uninit`](crate::Movetex::uninit) serves the fallback until it is
+    /// initialized. [`Movetex::try_read`](crate::Movetex::try_read) still reports the real state.
+    pub fn fallback(mut self, value: T) -> Self {
+        self.fallback = Some(value);
+        self
+    }
+
+    /// Creates the configured `Movetex`.
+    pub fn build(self) -> Movetex<T> {
+        Movetex::from_builder(self)
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> MovetexBuilder<T> {
+    /// Prepares the next writer copy on a background thread after every publish.
+    ///
+    /// A write normally clones the new value into the next writer copy before it returns. With
+    /// clone-ahead, the write returns right after publishing and the clone runs on a worker
+    /// thread owned by the instance; the next `write` or `swap` starts from the prepared copy,
+    /// waiting only if it is not ready yet. This hides the clone latency from writers that
+    /// publish at a predictable cadence, at the cost of a thread per instance and a hand-off
+    /// per publish, so it pays off for payloads whose clone takes longer than that. If the clone
+    /// panics, the operation picking it up panics and poisons the instance, like a write whose
+    /// clone panics.
+    ///
+    /// Has no effect with the `single-copy` feature, which keeps no writer copy between writes.
+    pub fn clone_ahead(mut self) -> Self {
+        self.clone_ahead = Some(ahead::spawn::<T>);
+        self
+    }
+
+    /// Drops retired values on a background thread instead of on the publishing path.
+    ///
+    /// A publish retires the value it replaces, and dropping it can take as long as cloning it
+    /// for large payloads, which the writer would otherwise pay for before the write returns.
+    /// With this option the value is sent to a thread shared by all instances and dropped
+    /// there, so publish latency does not depend on the size of the replaced value. The value a
+    /// publish recycles (see [`Movetex::write`]) is not dropped at all and stays on the
+    /// writer's path. A hook set with [`MovetexBuilder::on_retire`] takes precedence, and the
+    /// values held when the instance itself is dropped are dropped normally.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, SpillPolicy};
+    ///
+    /// let movetex = Movetex::builder(vec![0u8; 1 << 20])
+    ///     .spill(SpillPolicy::always())
+    ///     .drop_in_background()
+    ///     .build();
+    /// assert!(movetex.write(|v| v.truncate(16)));
+    /// assert_eq!(movetex.read().len(), 16);
+    /// ```
+    pub fn drop_in_background(mut self) -> Self {
+        self.drop_later = Some(dropper::drop_later::<Box<Snapshot<T>>>);
+        self
+    }
+}
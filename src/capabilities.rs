@@ -0,0 +1,126 @@
+//! Runtime report of how the crate was compiled.
+
+use std::fmt;
+
+/// How snapshots replaced by a publish are freed.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Reclamation {
+    /// Readers pin the current epoch while loading a snapshot and hold it through a per-snapshot
+    /// guard count; replaced snapshots wait on a retired list until the epoch has advanced past
+    /// them and their last guard is gone; publishes reclaim them, readers only release their
+    /// counts.
+    EpochPins,
+}
+
+/// The features and engines a build of the crate was compiled with, returned by
+/// [`capabilities`].
+///
+/// Libraries building on `Movetex` can use it to adapt to optional features at runtime, and
+/// its `Display` output is meant to be pasted into bug reports.
+///
+/// ```rust
+/// let caps = movetex::capabilities();
+/// assert_eq!(caps.version, env!("CARGO_PKG_VERSION"));
+/// println!("{caps}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// The version of the crate.
+    pub version: &'static str,
+    /// The enabled Cargo features, in alphabetical order.
+    pub features: &'static [&'static str],
+    /// The async runtime offloading is built for, if any.
+    pub async_runtime: Option<&'static str>,
+    /// The widest lock-free atomic of the target, in bits.
+    pub atomic_width: u32,
+    /// Whether the shared counters are padded to separate cache lines.
+    pub cache_padded: bool,
+    /// Whether instances keep a standing writer copy, see the `single-copy` feature.
+    pub writer_copy: bool,
+    /// How replaced snapshots are freed.
+    pub reclamation: Reclamation,
+}
+
+/// Reports the features and engines this build of the crate was compiled with.
+pub const fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES,
+        async_runtime: if cfg!(feature = "tokio") {
+            Some("tokio")
+        } else {
+            None
+        },
+        atomic_width: ATOMIC_WIDTH,
+        cache_padded: false,
+        writer_copy: !cfg!(feature = "single-copy"),
+        reclamation: Reclamation::EpochPins,
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "movetex {} (features: ", self.version)?;
+        match self.features {
+            [] => f.write_str("none")?,
+            features => f.write_str(&features.join(", "))?,
+        }
+        write!(
+            f,
+            "; async runtime: {}; atomics: {} bit; cache padding: {}; writer copy: {}; \
+             reclamation: {:?})",
+            self.async_runtime.unwrap_or("none"),
+            self.atomic_width,
+            on_off(self.cache_padded),
+            on_off(self.writer_copy),
+            self.reclamation,
+        )
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+macro_rules! enabled_features {
+    ($($feature:literal),+ $(,)?) => {
+        &[$(#[cfg(feature = $feature)] $feature,)+]
+    };
+}
+
+const FEATURES: &[&str] = enabled_features!(
+    "arc-swap",
+    "bench",
+    "defmt",
+    "demo",
+    "follower",
+    "hdrhistogram",
+    "im",
+    "json",
+    "jsonschema",
+    "parking_lot",
+    "serde",
+    "single-copy",
+    "stats",
+    "tokio",
+    "tracing",
+);
+
+const ATOMIC_WIDTH: u32 = if cfg!(target_has_atomic = "128") {
+    128
+} else if cfg!(target_has_atomic = "64") {
+    64
+} else if cfg!(target_has_atomic = "32") {
+    32
+} else if cfg!(target_has_atomic = "16") {
+    16
+} else {
+    8
+};
@@ -0,0 +1,15 @@
+//! Integrity checking of published snapshots.
+//!
+//! When a checksum function is configured through [`MovetexBuilder::checksum`], every published
+//! snapshot carries the checksum computed at publish time, and every read recomputes it. A
+//! mismatch means the snapshot was modified after publishing, which only memory corruption or
+//! unsound `unsafe` code can do, and is reported as
+//! [`InvariantViolation::ChecksumMismatch`](crate::InvariantViolation::ChecksumMismatch).
+//!
+//! [`MovetexBuilder::checksum`]: crate::MovetexBuilder::checksum
+
+/// Computes the checksum of a published value.
+pub(crate) type Checksum<T> = fn(&T) -> u64;
+
+/// Produces a replacement for a published value found to be bad.
+pub(crate) type Repair<T> = fn(&T) -> Option<T>;
@@ -0,0 +1,134 @@
+//! Time source used by every time-based feature.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A monotonic time source.
+///
+/// Time-based policies (publish age in health reports, idle eviction,
+/// [`MovetexBuilder::max_write_duration`](crate::MovetexBuilder::max_write_duration) and the
+/// timeout of [`Movetex::write_retrying`](crate::Movetex::write_retrying)) read the time
+/// through a `Clock` rather than calling `Instant::now` directly. Production code uses
+/// [`SystemClock`]; tests inject a [`MockClock`] and advance it explicitly, so time-dependent
+/// behaviour is tested without sleeping.
+///
+/// Calls that block the thread for up to a timeout, like
+/// [`Movetex::write_timeout`](crate::Movetex::write_timeout),
+/// [`Movetex::read_timeout`](crate::Movetex::read_timeout) or
+/// [`Subscription::wait_timeout`](crate::Subscription::wait_timeout), check their deadline on
+/// the clock as well, waking up as often as [`Clock::recheck_after`] asks. Only latency
+/// histograms measure real time.
+pub trait Clock: Send + Sync {
+    /// The current instant.
+    fn now(&self) -> Instant;
+
+    /// How long a thread blocked until a deadline may sleep before reading the clock again,
+    /// given the `remaining` time until the deadline.
+    ///
+    /// The default sleeps for all of it, which suits clocks that advance with real time.
+    fn recheck_after(&self, remaining: Duration) -> Duration {
+        remaining
+    }
+}
+
+/// The real monotonic clock, backed by `Instant::now`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A manually advanced clock for tests.
+///
+/// Clones share the same time, so a test keeps one handle and passes another to the code under
+/// test. Threads blocked until a deadline on a `MockClock` check it every millisecond, so they
+/// time out shortly after the clock is advanced past it, and never before.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{HealthCheck, HealthPolicy, MockClock, Movetex};
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new();
+/// let movetex = Movetex::builder(1).clock(clock.clone()).build();
+/// let policy = HealthPolicy {
+///     max_publish_age: Some(Duration::from_secs(60)),
+///     ..HealthPolicy::default()
+/// };
+///
+/// clock.advance(Duration::from_secs(61));
+/// assert!(movetex.health(&policy).stale);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    origin: Instant,
+    elapsed: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    /// Creates a clock that stands still until advanced.
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.elapsed
+            .fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.origin + Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+
+    fn recheck_after(&self, remaining: Duration) -> Duration {
+        remaining.min(MOCK_RECHECK)
+    }
+}
+
+const MOCK_RECHECK: Duration = Duration::from_millis(1);
+
+/// A deadline on a [`Clock`], for calls that block the thread until it passes.
+#[derive(Clone, Copy)]
+pub(crate) struct Deadline<'a> {
+    clock: &'a dyn Clock,
+    // `None` if the timeout is too long to be represented
+    at: Option<Instant>,
+}
+
+impl<'a> Deadline<'a> {
+    pub(crate) fn after(clock: &'a dyn Clock, timeout: Duration) -> Self {
+        Self {
+            clock,
+            at: clock.now().checked_add(timeout),
+        }
+    }
+
+    /// How long to sleep before checking again, or `None` once the deadline has passed.
+    pub(crate) fn sleep(&self) -> Option<Duration> {
+        let Some(at) = self.at else {
+            return Some(self.clock.recheck_after(Duration::MAX));
+        };
+        let remaining = at.saturating_duration_since(self.clock.now());
+        (!remaining.is_zero()).then(|| self.clock.recheck_after(remaining))
+    }
+}
+
+pub(crate) fn system() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
@@ -0,0 +1,375 @@
+//! A heap-free instance for small `Copy` values, stored in atomic words.
+
+use std::fmt;
+use std::hint;
+use std::marker::PhantomData;
+use std::sync::atomic::{self, AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+
+/// A `Copy` value of at most 16 bytes that [`MovetexCopy`] can store as plain bits.
+///
+/// Implemented for the integer and floating point primitives, `bool` and `char`. Each type is
+/// stored in an atomic integer of its own size; `u128` and `i128`, for which stable Rust has no
+/// atomic type, take two 64-bit words and a sequence counter.
+///
+/// The trait is sealed: user-defined types cannot implement it, even if they are plain bytes.
+/// Pack such a value into one of the primitives, for example two `u32` fields into a `u64`, or
+/// use [`Movetex`](crate::Movetex).
+pub trait AtomicCopy: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    type Storage: Storage;
+    #[doc(hidden)]
+    fn to_bits(self) -> u128;
+    #[doc(hidden)]
+    fn from_bits(bits: u128) -> Self;
+}
+
+/// The atomic storage of an [`AtomicCopy`] type, holding its bits.
+#[doc(hidden)]
+pub trait Storage: sealed::Sealed {
+    fn with_bits(bits: u128) -> Self;
+
+    /// Returns the published bits.
+    fn load_bits(&self) -> u128;
+
+    /// Publishes the bits `f` makes of the published ones, unless other bits were published or
+    /// a write was in progress meanwhile.
+    fn write_bits(&self, f: impl FnOnce(u128) -> u128) -> bool;
+
+    /// Publishes `bits` and returns the replaced ones, waiting for a write in progress.
+    fn swap_bits(&self, bits: u128) -> u128;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! atomic_copy_int {
+    ($($ty:ty: $storage:ty),+) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl AtomicCopy for $ty {
+                type Storage = $storage;
+
+                fn to_bits(self) -> u128 {
+                    self as u128
+                }
+
+                fn from_bits(bits: u128) -> Self {
+                    bits as $ty
+                }
+            }
+        )+
+    };
+}
+
+atomic_copy_int!(
+    u8: AtomicU8,
+    u16: AtomicU16,
+    u32: AtomicU32,
+    u64: AtomicU64,
+    u128: Words,
+    usize: AtomicUsize,
+    i8: AtomicU8,
+    i16: AtomicU16,
+    i32: AtomicU32,
+    i64: AtomicU64,
+    i128: Words,
+    isize: AtomicUsize
+);
+
+impl sealed::Sealed for bool {}
+
+impl AtomicCopy for bool {
+    type Storage = AtomicU8;
+
+    fn to_bits(self) -> u128 {
+        self as u128
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        bits != 0
+    }
+}
+
+impl sealed::Sealed for char {}
+
+impl AtomicCopy for char {
+    type Storage = AtomicU32;
+
+    fn to_bits(self) -> u128 {
+        self as u128
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        char::from_u32(bits as u32).expect("only valid chars are stored")
+    }
+}
+
+impl sealed::Sealed for f32 {}
+
+impl AtomicCopy for f32 {
+    type Storage = AtomicU32;
+
+    fn to_bits(self) -> u128 {
+        f32::to_bits(self) as u128
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        f32::from_bits(bits as u32)
+    }
+}
+
+impl sealed::Sealed for f64 {}
+
+impl AtomicCopy for f64 {
+    type Storage = AtomicU64;
+
+    fn to_bits(self) -> u128 {
+        f64::to_bits(self) as u128
+    }
+
+    fn from_bits(bits: u128) -> Self {
+        f64::from_bits(bits as u64)
+    }
+}
+
+macro_rules! one_word_storage {
+    ($($atomic:ty: $bits:ty),+) => {
+        $(
+            impl sealed::Sealed for $atomic {}
+
+            impl Storage for $atomic {
+                fn with_bits(bits: u128) -> Self {
+                    <$atomic>::new(bits as $bits)
+                }
+
+                fn load_bits(&self) -> u128 {
+                    self.load(Ordering::Acquire) as u128
+                }
+
+                fn write_bits(&self, f: impl FnOnce(u128) -> u128) -> bool {
+                    let current = self.load(Ordering::Acquire);
+                    let next = f(current as u128) as $bits;
+                    self.compare_exchange(current, next, Ordering::AcqRel, Ordering::Relaxed)
+                        .is_ok()
+                }
+
+                fn swap_bits(&self, bits: u128) -> u128 {
+                    self.swap(bits as $bits, Ordering::AcqRel) as u128
+                }
+            }
+        )+
+    };
+}
+
+one_word_storage!(
+    AtomicU8: u8,
+    AtomicU16: u16,
+    AtomicU32: u32,
+    AtomicU64: u64,
+    AtomicUsize: usize
+);
+
+/// Two 64-bit words guarded by a sequence counter, for 16-byte values.
+#[doc(hidden)]
+pub struct Words {
+    // Low and high 64 bits
+    words: [AtomicU64; 2],
+    // Odd while a write is in progress
+    seq: AtomicUsize,
+}
+
+impl sealed::Sealed for Words {}
+
+impl Storage for Words {
+    fn with_bits(bits: u128) -> Self {
+        Self {
+            words: [
+                AtomicU64::new(bits as u64),
+                AtomicU64::new((bits >> 64) as u64),
+            ],
+            seq: AtomicUsize::new(0),
+        }
+    }
+
+    fn load_bits(&self) -> u128 {
+        self.load_consistent().1
+    }
+
+    fn write_bits(&self, f: impl FnOnce(u128) -> u128) -> bool {
+        // `f` runs before the counter is taken, so readers only wait for the two stores.
+        let (seq, current) = self.load_consistent();
+        let next = f(current);
+        let Some(_write) = self.begin_write(seq) else {
+            return false;
+        };
+        self.store_words(next);
+        true
+    }
+
+    fn swap_bits(&self, bits: u128) -> u128 {
+        let _write = loop {
+            match self.begin_write(self.seq.load(Ordering::Relaxed)) {
+                Some(write) => break write,
+                None => hint::spin_loop(),
+            }
+        };
+        let previous = self.load_words();
+        self.store_words(bits);
+        previous
+    }
+}
+
+impl Words {
+    /// Reads both words without a write overlapping, along with the sequence counter they were
+    /// read at.
+    fn load_consistent(&self) -> (usize, u128) {
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before.is_multiple_of(2) {
+                let bits = self.load_words();
+                // Orders the word loads before the second counter load.
+                atomic::fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) == before {
+                    return (before, bits);
+                }
+            }
+            hint::spin_loop();
+        }
+    }
+
+    /// Makes the sequence counter odd if it is still at `seq` and no write is in progress; it
+    /// becomes even again when the returned guard is dropped, also if the write panics.
+    fn begin_write(&self, seq: usize) -> Option<SeqWrite<'_>> {
+        if !seq.is_multiple_of(2) {
+            return None;
+        }
+        self.seq
+            .compare_exchange(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        // Orders the counter update before the word stores, for readers checking it.
+        atomic::fence(Ordering::Release);
+        Some(SeqWrite {
+            seq: &self.seq,
+            end: seq + 2,
+        })
+    }
+
+    /// Reads both words; readers must check the sequence counter around it.
+    fn load_words(&self) -> u128 {
+        let low = self.words[0].load(Ordering::Relaxed);
+        let high = self.words[1].load(Ordering::Relaxed);
+        ((high as u128) << 64) | low as u128
+    }
+
+    /// Writes both words; the caller must own the sequence counter.
+    fn store_words(&self, bits: u128) {
+        self.words[0].store(bits as u64, Ordering::Relaxed);
+        self.words[1].store((bits >> 64) as u64, Ordering::Relaxed);
+    }
+}
+
+/// Ends a two-word write when dropped, see `Words::begin_write`.
+struct SeqWrite<'a> {
+    seq: &'a AtomicUsize,
+    end: usize,
+}
+
+impl Drop for SeqWrite<'_> {
+    fn drop(&mut self) {
+        self.seq.store(self.end, Ordering::Release);
+    }
+}
+
+/// A counterpart of [`Movetex`](crate::Movetex) for small `Copy` values, which never
+/// allocates.
+///
+/// A `Movetex<u64>` boxes every published value, although the hardware can publish it with a
+/// single store. `MovetexCopy` keeps the value in an atomic integer of its own size instead,
+/// such as an `AtomicU8` for a `u8` or an `AtomicU64` for a `u64`, so reads are one load and
+/// writes one compare-and-exchange. 16-byte values such as `u128`, for which stable Rust
+/// offers no atomic type, are kept in two words guarded by a sequence counter: a write
+/// computes its value first and only holds the counter for its two stores, and readers retry
+/// the rare read that overlaps them.
+///
+/// Only the primitives implementing [`AtomicCopy`] can be stored; the trait is sealed, so a
+/// user-defined `Copy` type, even a plain 16-byte struct, cannot implement it. Such a type can
+/// be packed into an integer by the caller, or published through a [`Movetex`](crate::Movetex)
+/// instead.
+///
+/// Like [`Movetex::write`](crate::Movetex::write), a write that finds another write in the
+/// way fails instead of waiting.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexCopy;
+///
+/// let offset = MovetexCopy::new(0u128);
+/// assert!(offset.write(|v| *v += 1 << 100));
+/// assert_eq!(offset.read(), 1 << 100);
+/// assert_eq!(offset.swap(7), 1 << 100);
+/// ```
+pub struct MovetexCopy<T: AtomicCopy> {
+    storage: T::Storage,
+    value: PhantomData<T>,
+}
+
+impl<T: AtomicCopy> MovetexCopy<T> {
+    /// Creates an instance publishing `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            storage: T::Storage::with_bits(value.to_bits()),
+            value: PhantomData,
+        }
+    }
+
+    /// Returns the published value.
+    pub fn read(&self) -> T {
+        T::from_bits(self.storage.load_bits())
+    }
+
+    /// Publishes the value `f` makes of the published one.
+    ///
+    /// Returns `false` without publishing if the published value changed while `f` ran, or
+    /// if another 16-byte write started meanwhile; `f` then ran on a value that is no longer
+    /// current.
+    /// Values of up to 8 bytes are published with a compare-and-exchange of their bits, so a
+    /// write that published the value `f` started from again goes unnoticed, as `f`'s result
+    /// still follows from the current value.
+    pub fn write(&self, f: impl FnOnce(&mut T)) -> bool {
+        self.storage.write_bits(|bits| {
+            let mut value = T::from_bits(bits);
+            f(&mut value);
+            value.to_bits()
+        })
+    }
+
+    /// Publishes `value`, waiting for a 16-byte write in progress.
+    pub fn store(&self, value: T) {
+        self.swap(value);
+    }
+
+    /// Publishes `value` and returns the value it replaced, waiting for a 16-byte write in
+    /// progress.
+    pub fn swap(&self, value: T) -> T {
+        T::from_bits(self.storage.swap_bits(value.to_bits()))
+    }
+
+    /// Consumes the instance and returns the published value.
+    pub fn into_inner(self) -> T {
+        self.read()
+    }
+}
+
+impl<T: AtomicCopy + Default> Default for MovetexCopy<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: AtomicCopy + fmt::Debug> fmt::Debug for MovetexCopy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MovetexCopy").field(&self.read()).finish()
+    }
+}
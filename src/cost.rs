@@ -0,0 +1,203 @@
+//! Clone cost estimation for choosing a storage strategy.
+//!
+//! [`SpillPolicy`](crate::SpillPolicy) needs to know how expensive a payload is to clone, which
+//! `size_of` cannot tell for anything that owns heap data. [`CloneCost`] provides that estimate
+//! per type, so [`SpillPolicy::by_clone_cost`](crate::SpillPolicy::by_clone_cost) can decide
+//! between the inline and the `Arc` layout from the value itself instead of a hand-written
+//! estimator.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::mem::size_of;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Estimates the number of bytes copied by cloning a value, including owned heap data.
+///
+/// Estimates only need to be proportionate, not exact: they are compared against a threshold.
+/// Implementations are provided for primitives, strings, the standard collections, and
+/// smart pointers, where `Arc` and `Rc` only count the pointer since cloning them shares the
+/// allocation. Structs can implement it by summing their fields with
+/// [`impl_clone_cost!`](crate::impl_clone_cost), which takes the place of a derive: the crate
+/// ships no procedural macros, so the fields to count are listed by hand.
+pub trait CloneCost {
+    /// The estimated number of bytes copied by `clone`.
+    fn clone_cost(&self) -> usize;
+}
+
+/// Implements [`CloneCost`] for a struct as the sum of the costs of the listed fields.
+///
+/// Fields that are not listed are not counted, which suits fields whose clone is negligible.
+///
+/// ```rust
+/// use movetex::{impl_clone_cost, CloneCost};
+///
+/// #[derive(Clone)]
+/// struct Route {
+///     path: String,
+///     upstreams: Vec<String>,
+///     weight: u32,
+/// }
+///
+/// impl_clone_cost!(Route { path, upstreams, weight });
+///
+/// let route = Route {
+///     path: "/api".to_string(),
+///     upstreams: vec!["a:80".to_string(), "b:80".to_string()],
+///     weight: 1,
+/// };
+/// assert!(route.clone_cost() > "/api".len() + 2 * "a:80".len());
+/// ```
+#[macro_export]
+macro_rules! impl_clone_cost {
+    ($type:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::CloneCost for $type {
+            fn clone_cost(&self) -> usize {
+                0 $(+ $crate::CloneCost::clone_cost(&self.$field))*
+            }
+        }
+    };
+}
+
+macro_rules! stack_only {
+    ($($type:ty),+) => {
+        $(impl CloneCost for $type {
+            fn clone_cost(&self) -> usize {
+                size_of::<$type>()
+            }
+        })+
+    };
+}
+
+stack_only!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl CloneCost for String {
+    fn clone_cost(&self) -> usize {
+        size_of::<String>() + self.len()
+    }
+}
+
+impl<T: CloneCost> CloneCost for Option<T> {
+    fn clone_cost(&self) -> usize {
+        match self {
+            Some(value) => size_of::<Option<T>>() - size_of::<T>() + value.clone_cost(),
+            None => size_of::<Option<T>>(),
+        }
+    }
+}
+
+impl<T: CloneCost + ?Sized> CloneCost for Box<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<Box<T>>() + (**self).clone_cost()
+    }
+}
+
+impl<T: ?Sized> CloneCost for Arc<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<Arc<T>>()
+    }
+}
+
+impl<T: ?Sized> CloneCost for Rc<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<Rc<T>>()
+    }
+}
+
+impl<T: CloneCost> CloneCost for [T] {
+    fn clone_cost(&self) -> usize {
+        self.iter().map(CloneCost::clone_cost).sum()
+    }
+}
+
+impl<T: CloneCost, const N: usize> CloneCost for [T; N] {
+    fn clone_cost(&self) -> usize {
+        self.as_slice().clone_cost()
+    }
+}
+
+impl<T: CloneCost> CloneCost for Vec<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<Vec<T>>() + self.as_slice().clone_cost()
+    }
+}
+
+impl<T: CloneCost> CloneCost for VecDeque<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<VecDeque<T>>() + self.iter().map(CloneCost::clone_cost).sum::<usize>()
+    }
+}
+
+impl<K: CloneCost, V: CloneCost, S> CloneCost for HashMap<K, V, S> {
+    fn clone_cost(&self) -> usize {
+        let entries: usize = self
+            .iter()
+            .map(|(k, v)| k.clone_cost() + v.clone_cost())
+            .sum();
+        size_of::<HashMap<K, V, S>>() + entries
+    }
+}
+
+impl<T: CloneCost, S> CloneCost for HashSet<T, S> {
+    fn clone_cost(&self) -> usize {
+        size_of::<HashSet<T, S>>() + self.iter().map(CloneCost::clone_cost).sum::<usize>()
+    }
+}
+
+impl<K: CloneCost, V: CloneCost> CloneCost for BTreeMap<K, V> {
+    fn clone_cost(&self) -> usize {
+        let entries: usize = self
+            .iter()
+            .map(|(k, v)| k.clone_cost() + v.clone_cost())
+            .sum();
+        size_of::<BTreeMap<K, V>>() + entries
+    }
+}
+
+impl<T: CloneCost> CloneCost for BTreeSet<T> {
+    fn clone_cost(&self) -> usize {
+        size_of::<BTreeSet<T>>() + self.iter().map(CloneCost::clone_cost).sum::<usize>()
+    }
+}
+
+/// A clone of an `im` map shares its nodes, so only the handle is copied.
+#[cfg(feature = "im")]
+impl<K, V, S> CloneCost for im::HashMap<K, V, S> {
+    fn clone_cost(&self) -> usize {
+        size_of::<im::HashMap<K, V, S>>()
+    }
+}
+
+macro_rules! tuple {
+    ($($name:ident: $index:tt),+) => {
+        impl<$($name: CloneCost),+> CloneCost for ($($name,)+) {
+            fn clone_cost(&self) -> usize {
+                0 $(+ self.$index.clone_cost())+
+            }
+        }
+    };
+}
+
+tuple!(A: 0);
+tuple!(A: 0, B: 1);
+tuple!(A: 0, B: 1, C: 2);
+tuple!(A: 0, B: 1, C: 2, D: 3);
+tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
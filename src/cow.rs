@@ -0,0 +1,126 @@
+//! Reads that turn into a private copy on their first modification.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::state::State;
+use crate::{Movetex, ReadGuard, WriteError};
+
+/// The published value, or a modified copy of it, returned by [`Movetex::read_cow`].
+///
+/// It starts out as a read guard and clones the value the first time it is mutated, through
+/// [`MovetexCow::to_mut`] or `DerefMut`, so handlers that rarely modify the value pay for a
+/// clone only when they do. [`MovetexCow::commit`] publishes the copy, as long as no other
+/// write published in the meantime.
+pub struct MovetexCow<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+    // The version the value was read at
+    version: u64,
+    value: CowValue<'a, T>,
+}
+
+enum CowValue<'a, T: Clone> {
+    Borrowed(ReadGuard<'a, T>),
+    Owned(T),
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Returns the published value as a [`MovetexCow`], which clones it on the first
+    /// modification and can publish the modified copy.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let routes = Movetex::new(vec!["/", "/health"]);
+    ///
+    /// let mut view = routes.read_cow();
+    /// if !view.contains(&"/metrics") {
+    ///     view.push("/metrics");
+    /// }
+    /// assert_eq!(view.commit(), Ok(true));
+    ///
+    /// // Nothing to change, nothing published.
+    /// let view = routes.read_cow();
+    /// assert_eq!(view.commit(), Ok(false));
+    /// assert_eq!(routes.version(), 1);
+    /// ```
+    pub fn read_cow(&self) -> MovetexCow<'_, T> {
+        let (version, guard) = self.read_versioned();
+        MovetexCow {
+            movetex: self,
+            version,
+            value: CowValue::Borrowed(guard),
+        }
+    }
+}
+
+impl<T: Clone> MovetexCow<'_, T> {
+    /// Returns the modified copy, cloning the published value first if this is the first
+    /// modification.
+    pub fn to_mut(&mut self) -> &mut T {
+        if let CowValue::Borrowed(guard) = &self.value {
+            self.value = CowValue::Owned(T::clone(guard));
+        }
+        match &mut self.value {
+            CowValue::Owned(value) => value,
+            CowValue::Borrowed(_) => unreachable!("the value was just cloned"),
+        }
+    }
+
+    /// Returns `true` once the value has been cloned for modification.
+    pub fn is_owned(&self) -> bool {
+        matches!(self.value, CowValue::Owned(_))
+    }
+
+    /// The version of the published value this one was read from, see [`Movetex::version`].
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Publishes the modified copy, if there is one.
+    ///
+    /// Returns `Ok(false)` without publishing if the value was never modified, and `Ok(true)`
+    /// once the copy is published. Fails with [`WriteError::Conflict`] if another write
+    /// published since the value was read, so that its update is not overwritten; read the
+    /// value again and reapply the modification to retry. Otherwise fails like
+    /// [`Movetex::try_write`].
+    pub fn commit(self) -> Result<bool, WriteError> {
+        let CowValue::Owned(value) = self.value else {
+            return Ok(false);
+        };
+        let acquired = self
+            .movetex
+            .state
+            .acquire_resting(State::Writing)
+            .map_err(|state| self.movetex.refuse_write(state))?;
+        if self.movetex.version() != self.version {
+            self.movetex.stats.write_failed();
+            self.movetex.state.restore(acquired);
+            return Err(WriteError::Conflict);
+        }
+        self.movetex
+            .write_acquired(acquired, |current| *current = value, |_| Ok(()), None)?;
+        Ok(true)
+    }
+}
+
+impl<T: Clone> Deref for MovetexCow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.value {
+            CowValue::Borrowed(guard) => guard,
+            CowValue::Owned(value) => value,
+        }
+    }
+}
+
+impl<T: Clone> DerefMut for MovetexCow<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.to_mut()
+    }
+}
@@ -0,0 +1,138 @@
+//! Differences between published values, for subscribers that only want what changed.
+//!
+//! A [`DiffSubscription`](crate::DiffSubscription) keeps the value it saw last and hands out
+//! the [`Diff`] between it and the newly published one, so a subscriber mirroring a large map
+//! into a cache, an index or a remote replica handles a handful of entries per publish
+//! instead of the whole value.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+/// A value that can describe how it differs from another value of its type, and apply such a
+/// description to itself.
+///
+/// `old.diff(&new)` followed by `old.apply_diff(diff)` must leave `old` equal to `new`.
+/// Implemented for `HashMap` and `BTreeMap`, whose diffs are a [`MapDiff`].
+///
+/// ```rust
+/// use movetex::Diff;
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Log(Vec<String>);
+///
+/// impl Diff for Log {
+///     // The lines appended since.
+///     type Diff = Vec<String>;
+///
+///     fn diff(&self, new: &Self) -> Vec<String> {
+///         new.0[self.0.len()..].to_vec()
+///     }
+///
+///     fn apply_diff(&mut self, diff: Vec<String>) {
+///         self.0.extend(diff);
+///     }
+/// }
+///
+/// let mut old = Log(vec!["a".into()]);
+/// let new = Log(vec!["a".into(), "b".into()]);
+/// let diff = old.diff(&new);
+/// assert_eq!(diff, ["b"]);
+/// old.apply_diff(diff);
+/// assert_eq!(old, new);
+/// ```
+pub trait Diff {
+    /// The description of a change.
+    type Diff;
+
+    /// Returns what changed from `self` to `new`.
+    fn diff(&self, new: &Self) -> Self::Diff;
+
+    /// Applies a change returned by [`Diff::diff`].
+    fn apply_diff(&mut self, diff: Self::Diff);
+}
+
+/// The entries that changed between two maps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapDiff<K, V> {
+    /// Entries that were added or whose value changed, with their new value.
+    pub changed: Vec<(K, V)>,
+    /// Keys that were removed.
+    pub removed: Vec<K>,
+}
+
+impl<K, V> MapDiff<K, V> {
+    /// Returns `true` if the maps hold the same entries.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<K, V> Default for MapDiff<K, V> {
+    fn default() -> Self {
+        Self {
+            changed: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+impl<K, V, S> Diff for HashMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    V: PartialEq + Clone,
+    S: BuildHasher,
+{
+    type Diff = MapDiff<K, V>;
+
+    fn diff(&self, new: &Self) -> MapDiff<K, V> {
+        MapDiff {
+            changed: new
+                .iter()
+                .filter(|&(key, value)| self.get(key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            removed: self
+                .keys()
+                .filter(|key| !new.contains_key(*key))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn apply_diff(&mut self, diff: MapDiff<K, V>) {
+        for key in &diff.removed {
+            self.remove(key);
+        }
+        self.extend(diff.changed);
+    }
+}
+
+impl<K, V> Diff for BTreeMap<K, V>
+where
+    K: Ord + Clone,
+    V: PartialEq + Clone,
+{
+    type Diff = MapDiff<K, V>;
+
+    fn diff(&self, new: &Self) -> MapDiff<K, V> {
+        MapDiff {
+            changed: new
+                .iter()
+                .filter(|&(key, value)| self.get(key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            removed: self
+                .keys()
+                .filter(|key| !new.contains_key(*key))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn apply_diff(&mut self, diff: MapDiff<K, V>) {
+        for key in &diff.removed {
+            self.remove(key);
+        }
+        self.extend(diff.changed);
+    }
+}
@@ -0,0 +1,27 @@
+//! A background thread dropping retired snapshots, see `MovetexBuilder::drop_in_background`.
+//!
+//! All instances share one thread, started on first use. If it cannot be started, or has died
+//! because a destructor panicked on it, values are dropped on the calling thread instead.
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+use std::thread;
+
+type Garbage = Box<dyn Send>;
+
+/// Hands `value` to the dropper thread.
+pub(crate) fn drop_later<T: Send + 'static>(value: T) {
+    static QUEUE: OnceLock<Option<Sender<Garbage>>> = OnceLock::new();
+    let queue = QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Garbage>();
+        thread::Builder::new()
+            .name("movetex-dropper".into())
+            .spawn(move || receiver.into_iter().for_each(drop))
+            .ok()
+            .map(|_| sender)
+    });
+    if let Some(queue) = queue {
+        // A returned value is dropped here, on the calling thread.
+        let _ = queue.send(Box::new(value));
+    }
+}
@@ -0,0 +1,80 @@
+//! Epoch-based protection for loading published snapshots.
+//!
+//! A reader pins the current epoch while it loads the published pointer and takes a reference
+//! on the snapshot it found; the pin is released right after. A snapshot unlinked by a publish
+//! is tagged with the epoch current at that point, and may be reclaimed once the epoch has
+//! advanced twice past its tag: the epoch only advances when no reader is pinned in the
+//! previous one, so every reader that could have loaded the snapshot has taken its reference
+//! or gone by then.
+//!
+//! Pins are counted per epoch in two alternating slots. Readers that keep overlapping pin into
+//! the current slot while the previous one drains, so they cannot hold the epoch back the way
+//! a single shared counter would.
+
+use crate::sync::atomic::{self, AtomicUsize, Ordering};
+
+pub(crate) struct Epochs {
+    // Only advanced by `advance`, whose callers serialize through the retired list lock
+    epoch: AtomicUsize,
+    // Readers pinned in even and odd epochs
+    pins: [AtomicUsize; 2],
+}
+
+impl Epochs {
+    pub(crate) fn new() -> Self {
+        Self {
+            epoch: AtomicUsize::new(0),
+            pins: [AtomicUsize::new(0), AtomicUsize::new(0)],
+        }
+    }
+
+    /// Pins the current epoch and returns the slot to pass to [`Epochs::unpin`].
+    pub(crate) fn pin(&self) -> usize {
+        loop {
+            let epoch = self.epoch.load(Ordering::Acquire);
+            let slot = epoch & 1;
+            self.pins[slot].fetch_add(1, Ordering::Relaxed);
+            // Pairs with the fence in `advance`: either the pin is seen there, or the epoch
+            // loaded below shows that the slot now belongs to a later epoch.
+            atomic::fence(Ordering::SeqCst);
+            if self.epoch.load(Ordering::Acquire) == epoch {
+                return slot;
+            }
+            self.unpin(slot);
+        }
+    }
+
+    /// Releases a pin; the next publish advances the epoch past it.
+    pub(crate) fn unpin(&self, slot: usize) {
+        self.pins[slot].fetch_sub(1, Ordering::Release);
+    }
+
+    /// The current epoch; the caller must serialize with [`Epochs::advance`].
+    pub(crate) fn current(&self) -> usize {
+        self.epoch.load(Ordering::Relaxed)
+    }
+
+    /// Advances the epoch as far as drained slots allow, at most twice, and returns it; calls
+    /// must be serialized.
+    pub(crate) fn advance(&self) -> usize {
+        let mut epoch = self.epoch.load(Ordering::Relaxed);
+        for _ in 0..2 {
+            atomic::fence(Ordering::SeqCst);
+            // The slot of the previous epoch, which the next one reuses.
+            if self.pins[(epoch + 1) & 1].load(Ordering::Relaxed) != 0 {
+                break;
+            }
+            // Pairs with the release in `unpin`, so the readers of that epoch are done.
+            atomic::fence(Ordering::Acquire);
+            epoch = epoch.wrapping_add(1);
+            self.epoch.store(epoch, Ordering::Release);
+        }
+        epoch
+    }
+}
+
+/// Returns `true` if a snapshot unlinked in epoch `tag` can no longer be loaded by a reader
+/// once the epoch is `epoch`.
+pub(crate) fn expired(tag: usize, epoch: usize) -> bool {
+    epoch.wrapping_sub(tag) >= 2
+}
@@ -0,0 +1,153 @@
+//! Error types reported by `Movetex` operations.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::ValidationError;
+
+/// The reason a write was not published.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WriteError {
+    /// Another write was in progress, so the writer copy could not be acquired.
+    Busy,
+    /// The configured [`Validator`](crate::Validator) rejected the modified value.
+    Rejected(ValidationError),
+    /// The instance was created with [`Movetex::uninit`](crate::Movetex::uninit) and has not
+    /// been initialized yet.
+    NotInitialized,
+    /// The instance was sealed with [`Movetex::seal`](crate::Movetex::seal) and accepts no
+    /// further writes.
+    Sealed,
+    /// A previous writer panicked while owning the writer copy.
+    Poisoned,
+    /// A subscription had not seen the published value yet, see
+    /// [`Movetex::publish_with_backpressure`](crate::Movetex::publish_with_backpressure).
+    Lagging,
+    /// The write closure ran longer than the limit set with
+    /// [`MovetexBuilder::max_write_duration`](crate::MovetexBuilder::max_write_duration), so
+    /// its modification was discarded.
+    TimedOut,
+    /// Another write published since the modified value was read, see
+    /// [`MovetexCow::commit`](crate::MovetexCow::commit).
+    Conflict,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Busy => f.write_str("another write is in progress"),
+            WriteError::Rejected(e) => write!(f, "write rejected: {}", e.message()),
+            WriteError::NotInitialized => NotInitialized.fmt(f),
+            WriteError::Sealed => f.write_str("movetex is sealed"),
+            WriteError::Poisoned => f.write_str("a previous writer panicked"),
+            WriteError::Lagging => f.write_str("a subscriber has not seen the published value"),
+            WriteError::TimedOut => f.write_str("write closure exceeded its time limit"),
+            WriteError::Conflict => f.write_str("another write published in the meantime"),
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteError::Rejected(e) => Some(e),
+            WriteError::Busy
+            | WriteError::NotInitialized
+            | WriteError::Sealed
+            | WriteError::Poisoned
+            | WriteError::Lagging
+            | WriteError::TimedOut
+            | WriteError::Conflict => None,
+        }
+    }
+}
+
+/// Returned when reading an instance created with [`Movetex::uninit`](crate::Movetex::uninit)
+/// before it has been initialized.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotInitialized;
+
+impl fmt::Display for NotInitialized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("movetex has not been initialized")
+    }
+}
+
+impl Error for NotInitialized {}
+
+/// Returned by [`Movetex::initialize`](crate::Movetex::initialize) when the instance already
+/// holds a value; gives the rejected value back to the caller.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlreadyInitialized<T>(pub T);
+
+impl<T> fmt::Display for AlreadyInitialized<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("movetex is already initialized")
+    }
+}
+
+impl<T: fmt::Debug> Error for AlreadyInitialized<T> {}
+
+/// Returned by [`Movetex::publish_once`](crate::Movetex::publish_once) when the instance already
+/// holds a value; gives the rejected value back to the caller.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlreadyPublished<T>(pub T);
+
+impl<T> fmt::Display for AlreadyPublished<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("movetex has already been published")
+    }
+}
+
+impl<T: fmt::Debug> Error for AlreadyPublished<T> {}
+
+/// Returned by [`Movetex::compare_and_swap`](crate::Movetex::compare_and_swap) when the new
+/// value was not published; gives it back to the caller.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareAndSwapError<T> {
+    /// The published value differs from the expected one.
+    Mismatch(T),
+    /// A value swapped in with [`Movetex::swap`](crate::Movetex::swap) is waiting to be
+    /// published, and replacing it would discard it without readers ever seeing it.
+    Pending(T),
+    /// The value could not be published; see [`WriteError`].
+    Write(T, WriteError),
+}
+
+impl<T> CompareAndSwapError<T> {
+    /// Returns the value that was not published.
+    pub fn into_value(self) -> T {
+        match self {
+            CompareAndSwapError::Mismatch(value)
+            | CompareAndSwapError::Pending(value)
+            | CompareAndSwapError::Write(value, _) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for CompareAndSwapError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareAndSwapError::Mismatch(_) => {
+                f.write_str("the published value differs from the expected one")
+            }
+            CompareAndSwapError::Pending(_) => f.write_str("a swapped-in value is pending"),
+            CompareAndSwapError::Write(_, e) => e.fmt(f),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for CompareAndSwapError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CompareAndSwapError::Write(_, e) => Some(e),
+            CompareAndSwapError::Mismatch(_) | CompareAndSwapError::Pending(_) => None,
+        }
+    }
+}
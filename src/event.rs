@@ -0,0 +1,144 @@
+//! An event-sourced state cell built on `Movetex`.
+
+use std::convert::Infallible;
+
+use crate::sync::Mutex;
+use crate::{Movetex, ReadGuard, StateSource, ValidationError, WriteError, WriteThroughError};
+
+/// The log every event appended to an [`EventSourced`] is recorded in before it is published.
+///
+/// `Vec<E>` keeps the events in memory; implement the trait for a file or a message broker
+/// to make the log durable.
+pub trait Journal<E> {
+    /// The error reported when an event could not be recorded.
+    type Error;
+
+    /// Records `event`. Calls are serialized by the [`EventSourced`] that owns the journal.
+    fn append(&mut self, event: &E) -> Result<(), Self::Error>;
+}
+
+impl<E: Clone> Journal<E> for Vec<E> {
+    type Error = Infallible;
+
+    fn append(&mut self, event: &E) -> Result<(), Infallible> {
+        self.push(event.clone());
+        Ok(())
+    }
+}
+
+/// State derived from a log of events: every change is an event that is applied, recorded in
+/// a [`Journal`], and only then published.
+///
+/// Create it with [`Movetex::from_events`], which replays an existing log. Appends are
+/// serialized through the journal, so they never fail with [`WriteError::Busy`] against each
+/// other, and an event the journal fails to record is not published.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::Movetex;
+///
+/// #[derive(Clone)]
+/// enum Event {
+///     Deposit(u64),
+///     Withdraw(u64),
+/// }
+///
+/// fn apply(balance: &mut u64, event: Event) {
+///     match event {
+///         Event::Deposit(amount) => *balance += amount,
+///         Event::Withdraw(amount) => *balance -= amount,
+///     }
+/// }
+///
+/// let log = vec![Event::Deposit(100), Event::Withdraw(30)];
+/// let account = Movetex::from_events(0, log, apply);
+/// assert_eq!(*account.read(), 70);
+///
+/// account.append_event(Event::Deposit(5)).unwrap();
+/// assert_eq!(*account.read(), 75);
+/// assert_eq!(account.into_inner().1.len(), 1);
+/// ```
+pub struct EventSourced<T: Clone, E, J = Vec<E>> {
+    movetex: Movetex<T>,
+    apply: fn(&mut T, E),
+    journal: Mutex<J>,
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Rebuilds state by applying `events` to `initial` in order, and returns an
+    /// [`EventSourced`] cell publishing it.
+    ///
+    /// The replayed events are assumed to be recorded already; the cell starts with an empty
+    /// in-memory journal, which [`EventSourced::with_journal`] replaces.
+    pub fn from_events<E: Clone>(
+        initial: T,
+        events: impl IntoIterator<Item = E>,
+        apply: fn(&mut T, E),
+    ) -> EventSourced<T, E> {
+        let mut state = initial;
+        for event in events {
+            apply(&mut state, event);
+        }
+        EventSourced {
+            movetex: Movetex::new(state),
+            apply,
+            journal: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Clone, E: Clone, J: Journal<E>> EventSourced<T, E, J> {
+    /// Replaces the journal new events are recorded in.
+    pub fn with_journal<K: Journal<E>>(self, journal: K) -> EventSourced<T, E, K> {
+        EventSourced {
+            movetex: self.movetex,
+            apply: self.apply,
+            journal: Mutex::new(journal),
+        }
+    }
+
+    /// Applies `event` to the writer copy, records it in the journal, and publishes the result.
+    ///
+    /// If the journal fails to record the event, the new state is discarded and readers keep
+    /// the published one.
+    pub fn append_event(&self, event: E) -> Result<(), WriteThroughError<J::Error>> {
+        let mut journal = self.journal.lock();
+        let mut failed = None;
+        let written = self.movetex.write_checked(
+            |state| (self.apply)(state, event.clone()),
+            |_| {
+                journal.append(&event).map_err(|e| {
+                    failed = Some(e);
+                    WriteError::Rejected(ValidationError::new("failed to record event"))
+                })
+            },
+            None,
+        );
+        match (written, failed) {
+            (_, Some(e)) => Err(WriteThroughError::Persist(e)),
+            (Err(e), None) => Err(WriteThroughError::Write(e)),
+            (Ok(()), None) => Ok(()),
+        }
+    }
+
+    /// Returns the published state, see [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+
+    /// The wrapped instance. Values written to it directly bypass the journal.
+    pub fn movetex(&self) -> &Movetex<T> {
+        &self.movetex
+    }
+
+    /// Returns the wrapped instance and the journal.
+    pub fn into_inner(self) -> (Movetex<T>, J) {
+        (self.movetex, self.journal.into_inner())
+    }
+}
+
+impl<T: Clone, E, J> StateSource<T> for EventSourced<T, E, J> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+}
@@ -0,0 +1,132 @@
+//! Access to the value of an instance that is owned exclusively.
+//!
+//! With `&mut Movetex` or an owned `Movetex` no reader can hold a guard, so the published value
+//! can be modified in place or moved out without cloning, publishing, or reclamation. The
+//! pointers are accessed with relaxed atomics rather than `get_mut`, which loom's atomics lack.
+
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use crate::snapshot::Snapshot;
+use crate::state::State;
+use crate::sync::atomic::Ordering;
+use crate::Movetex;
+
+/// Mutable access to the published value of an exclusively borrowed instance, returned by
+/// [`Movetex::get_mut`].
+///
+/// Modifications are made in place and become the published value without a publish: the
+/// version and metadata stay unchanged. When the guard is dropped after a modification, the
+/// checksum is recomputed and the writer copy is replaced by a clone of the modified value,
+/// unless a [`swap`](Movetex::swap) left a pending writer copy, which is kept.
+pub struct MutGuard<'a, T: Clone> {
+    movetex: &'a mut Movetex<T>,
+    modified: bool,
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Returns mutable access to the published value, for setup and teardown code that owns
+    /// the instance exclusively.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let mut movetex = Movetex::new(vec![1, 2]);
+    /// movetex.get_mut().push(3);
+    /// assert_eq!(*movetex.read(), [1, 2, 3]);
+    /// assert_eq!(movetex.version(), 0);
+    /// ```
+    pub fn get_mut(&mut self) -> MutGuard<'_, T> {
+        assert!(
+            !self.ptr_r.load(Ordering::Relaxed).is_null(),
+            "movetex has not been initialized"
+        );
+        // A pending clone still reads the published snapshot.
+        self.settle_writer_copy();
+        MutGuard {
+            movetex: self,
+            modified: false,
+        }
+    }
+
+    /// Consumes the instance and returns the published value, or the fallback if nothing was
+    /// published.
+    ///
+    /// A value spilled behind an `Arc` is unwrapped without cloning. A writer copy left by a
+    /// [`swap`](Movetex::swap) that was not published yet is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized and no fallback is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(String::from("a"));
+    /// movetex.write(|v| v.push('b'));
+    /// assert_eq!(movetex.into_inner(), "ab");
+    /// ```
+    pub fn into_inner(mut self) -> T {
+        self.settle_writer_copy();
+        let ptr_w = self.ptr_w.swap(ptr::null_mut(), Ordering::Relaxed);
+        if !ptr_w.is_null() {
+            // Dropped first so that a spilled value is no longer shared with the writer copy.
+            drop(unsafe { Box::from_raw(ptr_w) });
+        }
+        let ptr_r = self.ptr_r.swap(ptr::null_mut(), Ordering::Relaxed);
+        if ptr_r.is_null() {
+            return self
+                .fallback
+                .take()
+                .expect("movetex has not been initialized");
+        }
+        unsafe { Box::from_raw(ptr_r) }.into_slot().into_inner()
+    }
+}
+
+impl<T: Clone> MutGuard<'_, T> {
+    fn snapshot(&self) -> &Snapshot<T> {
+        // `get_mut` checked that a snapshot is published, and the exclusive borrow keeps it.
+        unsafe { &*self.movetex.ptr_r.load(Ordering::Relaxed) }
+    }
+}
+
+impl<T: Clone> Deref for MutGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.snapshot().slot()
+    }
+}
+
+impl<T: Clone> DerefMut for MutGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.modified = true;
+        let snapshot = unsafe { &mut *self.movetex.ptr_r.load(Ordering::Relaxed) };
+        snapshot.slot_mut().make_mut()
+    }
+}
+
+impl<T: Clone> Drop for MutGuard<'_, T> {
+    fn drop(&mut self) {
+        if !self.modified {
+            return;
+        }
+        let movetex = &mut *self.movetex;
+        let snapshot = unsafe { &mut *movetex.ptr_r.load(Ordering::Relaxed) };
+        snapshot.reseal(movetex.checksum);
+        if movetex.state.get() == State::Dirty {
+            return;
+        }
+        let ptr_w = movetex.ptr_w.load(Ordering::Relaxed);
+        if !ptr_w.is_null() {
+            drop(unsafe { Box::from_raw(ptr_w) });
+            let writer = Box::into_raw(Box::new(snapshot.slot().clone()));
+            movetex.ptr_w.store(writer, Ordering::Relaxed);
+        }
+    }
+}
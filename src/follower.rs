@@ -0,0 +1,124 @@
+//! Follower mode: a locally read-only `Movetex` whose publishes come from a remote stream
+//! (feature `follower`).
+//!
+//! The crate does not ship a transport. Whatever delivers configuration updates (a gRPC
+//! streaming call, a message queue consumer, a file watcher) is adapted by the caller into a
+//! `Stream<Item = T>`, and the follower publishes every item it yields.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{Movetex, ReadGuard, StateSource, WriteError};
+
+/// A `Movetex` that can only be read locally and is updated from a remote stream.
+///
+/// The follower exposes no write API: the only way to publish is [`Follower::follow`], which
+/// drives a stream of values supplied by the caller. This makes the instance a local cache
+/// endpoint of a configuration distribution system, with the same lock-free read path as a
+/// regular `Movetex`.
+///
+/// ### Usage Example:
+/// ```rust,ignore
+/// use movetex::Follower;
+/// use std::sync::Arc;
+///
+/// let config = Arc::new(Follower::new(Config::default()));
+///
+/// // `updates` is any `Stream<Item = Config> + Unpin`, e.g. a boxed gRPC response stream.
+/// tokio::spawn({
+///     let config = Arc::clone(&config);
+///     async move { config.follow(updates).await.expect("follower stopped") }
+/// });
+///
+/// let current = config.read();
+/// ```
+pub struct Follower<T: Clone> {
+    movetex: Movetex<T>,
+}
+
+impl<T: Clone> Follower<T> {
+    /// Creates a follower publishing `initial` until the first remote value arrives.
+    pub fn new(initial: T) -> Self {
+        Self {
+            movetex: Movetex::new(initial),
+        }
+    }
+
+    /// Provides a reference to the most recently published value, see [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+
+    /// Returns a future that publishes every item of `stream` until the stream ends.
+    ///
+    /// The future resolves to the number of values published. If another `follow` future is
+    /// publishing to the same follower at that moment, the item is kept and the future yields
+    /// to try again on its next poll. It resolves to the error instead if an item cannot be
+    /// published at all, which happens once a panic while replacing the value, such as in the
+    /// `Drop` of the old value, has poisoned the instance. Streams that are not `Unpin` can be
+    /// passed as `Box::pin(stream)`.
+    pub fn follow<S>(&self, stream: S) -> Follow<'_, T, S>
+    where
+        S: Stream<Item = T> + Unpin,
+    {
+        Follow {
+            movetex: &self.movetex,
+            stream,
+            next: None,
+            published: 0,
+        }
+    }
+}
+
+/// Future returned by [`Follower::follow`].
+pub struct Follow<'a, T: Clone, S> {
+    movetex: &'a Movetex<T>,
+    stream: S,
+    // An item received but not published yet, because another stream was publishing
+    next: Option<T>,
+    published: u64,
+}
+
+// The pending item is never pinned.
+impl<T: Clone, S: Unpin> Unpin for Follow<'_, T, S> {}
+
+impl<T: Clone, S: Stream<Item = T> + Unpin> Future for Follow<'_, T, S> {
+    type Output = Result<u64, WriteError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            if this.next.is_none() {
+                match Pin::new(&mut this.stream).poll_next(cx) {
+                    Poll::Ready(Some(value)) => this.next = Some(value),
+                    Poll::Ready(None) => return Poll::Ready(Ok(this.published)),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            // The closure only runs, and takes the item, once the write has started.
+            match this.movetex.try_write(|v| {
+                if let Some(value) = this.next.take() {
+                    *v = value;
+                }
+            }) {
+                Ok(()) => this.published += 1,
+                // Only followers publish, so the writer copy is busy only while another stream
+                // drives the same follower; its write is short, so try again soon.
+                Err(WriteError::Busy) => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+}
+
+impl<T: Clone> StateSource<T> for Follower<T> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+}
@@ -0,0 +1,50 @@
+//! A process-global registry holding one `Movetex` per type.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+use std::sync::OnceLock;
+
+use crate::sync::Mutex;
+use crate::Movetex;
+
+const SHARDS: usize = 16;
+
+type Shard = Mutex<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>;
+
+// Shards are created on first use, so the registry costs nothing until it is used.
+static REGISTRY: [OnceLock<Shard>; SHARDS] = [const { OnceLock::new() }; SHARDS];
+
+/// Returns the process-wide `Movetex` for `T`, creating it with `T::default()` on first use.
+///
+/// Small applications can share hot state this way without threading `Arc<Movetex<T>>`
+/// through every constructor. Every call with the same `T` returns the same instance; it lives
+/// until the process exits. Use a newtype to keep several independent instances of the same
+/// payload type.
+///
+/// The registry is split into shards by type, so lookups of different types rarely contend.
+/// Keep the returned reference instead of calling `global` on hot paths.
+///
+/// ### Usage Example:
+/// ```rust
+/// #[derive(Clone, Default)]
+/// struct FeatureFlags {
+///     dark_mode: bool,
+/// }
+///
+/// movetex::global::<FeatureFlags>().write(|flags| flags.dark_mode = true);
+///
+/// assert!(movetex::global::<FeatureFlags>().read().dark_mode);
+/// ```
+pub fn global<T: Clone + Default + Send + Sync + 'static>() -> &'static Movetex<T> {
+    let type_id = TypeId::of::<T>();
+    let hash = BuildHasherDefault::<DefaultHasher>::default().hash_one(type_id);
+    let shard = REGISTRY[hash as usize % SHARDS].get_or_init(|| Mutex::new(HashMap::new()));
+    let instance = *shard.lock().entry(type_id).or_insert_with(|| {
+        let instance: &'static Movetex<T> = Box::leak(Box::new(Movetex::new(T::default())));
+        instance
+    });
+    instance
+        .downcast_ref()
+        .expect("the registry is keyed by the type of its instances")
+}
@@ -0,0 +1,229 @@
+//! Read guards keeping published snapshots alive.
+//!
+//! A read pins the current epoch only while it loads the published snapshot and takes a hold on
+//! it, see `epoch`; the guard it returns keeps just that hold. A publish unlinks the old
+//! snapshot right away and queues it on a retired list, from which a publish reclaims it once
+//! the epoch has moved past every reader that could still load it and its last guard is
+//! dropped. Writers never wait for readers, and readers that keep overlapping, or a guard that
+//! is held for long or leaked, only keep their own snapshots alive.
+//!
+//! Readers never reclaim: releasing a pin or a hold only decrements a counter, so a reader
+//! takes no lock and runs no destructor or retire hook. A snapshot whose last guard is dropped
+//! after the publishes that follow it is reclaimed by the next publish, or with the instance.
+
+use std::fmt;
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use crate::epoch::Epochs;
+use crate::snapshot::Holds;
+use crate::Movetex;
+
+/// A pin on the epoch of an instance, released on drop.
+pub(crate) struct ReaderPin<'a> {
+    epochs: &'a Epochs,
+    slot: usize,
+}
+
+impl<'a> ReaderPin<'a> {
+    /// Pins the current epoch of `epochs`.
+    pub(crate) fn new(epochs: &'a Epochs) -> Self {
+        Self {
+            epochs,
+            slot: epochs.pin(),
+        }
+    }
+}
+
+impl Drop for ReaderPin<'_> {
+    fn drop(&mut self) {
+        self.epochs.unpin(self.slot);
+    }
+}
+
+/// A hold on a snapshot of an instance, released on drop; empty for a value that is not part
+/// of a snapshot, like a fallback.
+pub(crate) struct SnapshotHold<'a> {
+    holds: Option<&'a Holds>,
+}
+
+impl<'a> SnapshotHold<'a> {
+    /// Wraps a hold the caller has already taken on `holds`.
+    pub(crate) fn new(holds: Option<&'a Holds>) -> Self {
+        Self { holds }
+    }
+}
+
+impl Drop for SnapshotHold<'_> {
+    fn drop(&mut self) {
+        if let Some(holds) = self.holds {
+            holds.release();
+        }
+    }
+}
+
+/// A published value, kept alive until the guard is dropped.
+///
+/// Returned by [`Movetex::read`](crate::Movetex::read) and the other read methods. A write that
+/// publishes while the guard is held replaces the value for new readers, but the value behind
+/// the guard stays unchanged and is only freed, or handed to the
+/// [retire hook](crate::MovetexBuilder::on_retire), by a publish after every guard that can see
+/// it has been dropped; dropping a guard never runs either. A guard keeps no other value alive,
+/// so holding one for long only costs the memory of its own value.
+///
+/// ```rust
+/// use movetex::{Movetex, ReadGuard};
+///
+/// let movetex = Movetex::new(vec![1, 2, 3]);
+///
+/// let guard = movetex.read();
+/// movetex.write(|v| v.push(4));
+/// assert_eq!(*guard, [1, 2, 3]);
+/// drop(guard);
+///
+/// let last = ReadGuard::map(movetex.read(), |v| v.last().unwrap());
+/// assert_eq!(*last, 4);
+/// ```
+pub struct ReadGuard<'a, T: ?Sized> {
+    value: &'a T,
+    _hold: SnapshotHold<'a>,
+}
+
+impl<'a, T: ?Sized> ReadGuard<'a, T> {
+    pub(crate) fn new(value: &'a T, hold: SnapshotHold<'a>) -> Self {
+        Self { value, _hold: hold }
+    }
+
+    /// Narrows the guard to a part of the value, keeping the same snapshot alive.
+    ///
+    /// This is an associated function, so it does not shadow methods of `T`.
+    pub fn map<U: ?Sized>(this: Self, f: impl FnOnce(&T) -> &U) -> ReadGuard<'a, U> {
+        ReadGuard {
+            value: f(this.value),
+            _hold: this._hold,
+        }
+    }
+
+    /// Narrows the guard to a part of the value that may be absent, returning the original
+    /// guard if `f` returns `None`.
+    pub fn filter_map<U: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&T) -> Option<&U>,
+    ) -> Result<ReadGuard<'a, U>, Self> {
+        match f(this.value) {
+            Some(value) => Ok(ReadGuard {
+                value,
+                _hold: this._hold,
+            }),
+            None => Err(this),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for ReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for ReadGuard<'_, T> {
+    fn as_ref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for ReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for ReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for ReadGuard<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for ReadGuard<'_, T> {}
+
+/// A published value, kept alive until the guard is dropped, that owns a reference to its
+/// instance.
+///
+/// Returned by [`Movetex::read_owned`](crate::Movetex::read_owned). It protects the value like
+/// [`ReadGuard`], but does not borrow the instance, so it can be moved to another thread or
+/// held across an `.await`.
+pub struct OwnedReadGuard<T: Clone> {
+    movetex: Arc<Movetex<T>>,
+    value: NonNull<T>,
+    holds: Option<NonNull<Holds>>,
+}
+
+// The guard only hands out `&T`, and releases its hold through an instance it keeps alive.
+unsafe impl<T: Clone + Send + Sync> Send for OwnedReadGuard<T> {}
+unsafe impl<T: Clone + Send + Sync> Sync for OwnedReadGuard<T> {}
+
+impl<T: Clone> OwnedReadGuard<T> {
+    /// Wraps `value`, which must stay alive while the hold the caller took on `holds` is held,
+    /// or live as long as `movetex` if there is none.
+    pub(crate) fn new(
+        movetex: Arc<Movetex<T>>,
+        value: NonNull<T>,
+        holds: Option<NonNull<Holds>>,
+    ) -> Self {
+        Self {
+            movetex,
+            value,
+            holds,
+        }
+    }
+
+    /// The instance the value was read from.
+    ///
+    /// This is an associated function, so it does not shadow methods of `T`.
+    pub fn movetex(this: &Self) -> &Arc<Movetex<T>> {
+        &this.movetex
+    }
+}
+
+impl<T: Clone> Drop for OwnedReadGuard<T> {
+    fn drop(&mut self) {
+        let holds = self.holds.map(|holds| unsafe { holds.as_ref() });
+        drop(SnapshotHold::new(holds));
+    }
+}
+
+impl<T: Clone> Deref for OwnedReadGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // The hold taken for this guard keeps the snapshot alive, the instance the fallback
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: Clone> AsRef<T> for OwnedReadGuard<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for OwnedReadGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: Clone + fmt::Display> fmt::Display for OwnedReadGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
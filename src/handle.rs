@@ -0,0 +1,267 @@
+//! Read-only and write-only views of a `Movetex`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    Meta, Movetex, NotInitialized, ReadGuard, StateSink, StateSource, Subscription, WriteError,
+};
+
+/// A view of an instance that can only read, returned by [`Movetex::readers`].
+///
+/// Hand it to components that consume the value, so the type system rather than convention
+/// keeps them from writing. It is `Copy` and as cheap to pass around as a reference.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, ReadOnly};
+///
+/// fn render(config: ReadOnly<'_, String>) -> String {
+///     format!("config: {}", *config.read())
+/// }
+///
+/// let config = Movetex::new(String::from("v1"));
+/// config.writer().write(|v| *v = String::from("v2")).unwrap();
+/// assert_eq!(render(config.readers()), "config: v2");
+/// ```
+pub struct ReadOnly<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+}
+
+/// A view of an instance that can only write, returned by [`Movetex::writer`].
+///
+/// Hand it to components that produce the value, such as a configuration loader, so they
+/// cannot come to depend on reading it. It is `Copy` and as cheap to pass around as a
+/// reference.
+pub struct WriteOnly<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+}
+
+/// A cloneable handle that can only read, returned by [`Movetex::split`].
+///
+/// The owned counterpart of [`ReadOnly`], for consumers that outlive the scope the instance
+/// was created in.
+pub struct MovetexReader<T: Clone> {
+    movetex: Arc<Movetex<T>>,
+}
+
+/// The only handle that can write to an instance, returned by [`Movetex::split`].
+///
+/// It is not `Clone`, and writing takes `&mut self`, so no two writes can ever overlap: unlike
+/// [`Movetex::write`], [`MovetexWriter::write`] cannot fail because another write is in
+/// progress.
+pub struct MovetexWriter<T: Clone> {
+    movetex: Arc<Movetex<T>>,
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Splits the instance into a cloneable reader handle and the unique writer handle, for the
+    /// common topology of one producer and many consumers.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::thread;
+    ///
+    /// let (reader, mut writer) = Movetex::new(0u64).split();
+    /// thread::scope(|s| {
+    ///     for _ in 0..4 {
+    ///         let reader = reader.clone();
+    ///         s.spawn(move || assert!(*reader.read() <= 100));
+    ///     }
+    ///     for _ in 0..100 {
+    ///         writer.write(|v| *v += 1);
+    ///     }
+    /// });
+    /// assert_eq!(*reader.read(), 100);
+    /// ```
+    pub fn split(self) -> (MovetexReader<T>, MovetexWriter<T>) {
+        let movetex = Arc::new(self);
+        let reader = MovetexReader {
+            movetex: Arc::clone(&movetex),
+        };
+        (reader, MovetexWriter { movetex })
+    }
+
+    /// Returns a view of the instance that can only read.
+    pub fn readers(&self) -> ReadOnly<'_, T> {
+        ReadOnly { movetex: self }
+    }
+
+    /// Returns a view of the instance that can only write.
+    pub fn writer(&self) -> WriteOnly<'_, T> {
+        WriteOnly { movetex: self }
+    }
+}
+
+impl<'a, T: Clone> ReadOnly<'a, T> {
+    /// See [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'a, T> {
+        self.movetex.read()
+    }
+
+    /// See [`Movetex::read_map`].
+    pub fn read_map<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.movetex.read_map(f)
+    }
+
+    /// See [`Movetex::try_read`].
+    pub fn try_read(&self) -> Result<ReadGuard<'a, T>, NotInitialized> {
+        self.movetex.try_read()
+    }
+
+    /// See [`Movetex::read_timeout`].
+    pub fn read_timeout(&self, timeout: Duration) -> Result<ReadGuard<'a, T>, NotInitialized> {
+        self.movetex.read_timeout(timeout)
+    }
+
+    /// See [`Movetex::read_meta`].
+    pub fn read_meta(&self) -> (ReadGuard<'a, T>, Meta) {
+        self.movetex.read_meta()
+    }
+
+    /// See [`Movetex::read_versioned`].
+    pub fn read_versioned(&self) -> (u64, ReadGuard<'a, T>) {
+        self.movetex.read_versioned()
+    }
+
+    /// See [`Movetex::version`].
+    pub fn version(&self) -> u64 {
+        self.movetex.version()
+    }
+
+    /// See [`Movetex::subscribe`].
+    pub fn subscribe(&self) -> Subscription<'a, T> {
+        self.movetex.subscribe()
+    }
+}
+
+impl<T: Clone> WriteOnly<'_, T> {
+    /// See [`Movetex::try_write`].
+    pub fn write(&self, f: impl FnOnce(&mut T)) -> Result<(), WriteError> {
+        self.movetex.try_write(f)
+    }
+
+    /// See [`Movetex::try_write_with`].
+    pub fn write_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, WriteError> {
+        self.movetex.try_write_with(f)
+    }
+
+    /// See [`Movetex::write_blocking`].
+    pub fn write_blocking(&self, f: impl FnOnce(&mut T)) -> Result<(), WriteError> {
+        self.movetex.write_blocking(f)
+    }
+
+    /// See [`Movetex::swap`].
+    pub fn swap(&self, value: T) -> Result<T, T> {
+        self.movetex.swap(value)
+    }
+
+    /// See [`Movetex::swap_and_publish`].
+    pub fn swap_and_publish(&self, value: T) -> Option<T> {
+        self.movetex.swap_and_publish(value)
+    }
+
+    /// See [`Movetex::publish`].
+    pub fn publish(&self) -> bool {
+        self.movetex.publish()
+    }
+}
+
+impl<T: Clone> MovetexReader<T> {
+    /// Returns a view of the instance that can only read, with all of its read methods.
+    pub fn readers(&self) -> ReadOnly<'_, T> {
+        self.movetex.readers()
+    }
+
+    /// See [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+
+    /// See [`Movetex::version`].
+    pub fn version(&self) -> u64 {
+        self.movetex.version()
+    }
+}
+
+impl<T: Clone> MovetexWriter<T> {
+    /// Publishes the value modified by `f`.
+    ///
+    /// There is no other writer to contend with, so this only fails to publish if a
+    /// configured validator or maximum write duration discards the modification; use
+    /// [`MovetexWriter::try_write`] to find out.
+    pub fn write(&mut self, f: impl FnOnce(&mut T)) {
+        let _ = self.try_write(f);
+    }
+
+    /// Same as [`MovetexWriter::write`], but reports why the value was not published, see
+    /// [`Movetex::try_write`]. Never fails with [`WriteError::Busy`].
+    ///
+    /// A [`Movetex::verify_published`] call on another handle publishes its repair through the
+    /// writer copy, see [`MovetexBuilder::repair`](crate::MovetexBuilder::repair); the write
+    /// waits for such a repair to finish instead of failing.
+    pub fn try_write(&mut self, f: impl FnOnce(&mut T)) -> Result<(), WriteError> {
+        self.movetex.write_blocking(f)
+    }
+
+    /// See [`Movetex::swap`].
+    pub fn swap(&mut self, value: T) -> Result<T, T> {
+        self.movetex.swap(value)
+    }
+
+    /// See [`Movetex::publish`].
+    pub fn publish(&mut self) -> bool {
+        Movetex::publish(&self.movetex)
+    }
+
+    /// Returns a new reader handle for the instance.
+    pub fn reader(&self) -> MovetexReader<T> {
+        MovetexReader {
+            movetex: Arc::clone(&self.movetex),
+        }
+    }
+}
+
+impl<T: Clone> Clone for MovetexReader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            movetex: Arc::clone(&self.movetex),
+        }
+    }
+}
+
+impl<T: Clone> StateSource<T> for MovetexReader<T> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+}
+
+impl<T: Clone> Clone for ReadOnly<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Clone> Copy for ReadOnly<'_, T> {}
+
+impl<T: Clone> Clone for WriteOnly<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Clone> Copy for WriteOnly<'_, T> {}
+
+impl<T: Clone> StateSource<T> for ReadOnly<'_, T> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+}
+
+impl<T: Clone> StateSink<T> for WriteOnly<'_, T> {
+    type Error = WriteError;
+
+    fn publish(&self, value: T) -> Result<(), WriteError> {
+        self.movetex.try_write(|current| *current = value)
+    }
+}
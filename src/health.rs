@@ -0,0 +1,166 @@
+//! Health reporting for orchestrators and readiness probes.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::clock::Clock;
+use crate::sync::atomic::{AtomicU64, Ordering};
+
+/// Thresholds used to judge whether an instance is healthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthPolicy {
+    /// The published value is considered stale once it is older than this. `None` disables the
+    /// check for instances that are legitimately written rarely.
+    pub max_publish_age: Option<Duration>,
+    /// A writer holding the writer copy for longer than this is reported as stuck.
+    pub max_write_hold: Duration,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            max_publish_age: None,
+            max_write_hold: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A point-in-time health report of an instance.
+///
+/// New fields may be added as more subsystems report their state, so the struct cannot be
+/// constructed outside the crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Health {
+    /// Time since the last publish, or `None` if no value has been published yet.
+    pub last_publish_age: Option<Duration>,
+    /// `true` if `last_publish_age` exceeds [`HealthPolicy::max_publish_age`].
+    pub stale: bool,
+    /// How long the current writer has been holding the writer copy, if a write is in progress.
+    pub write_held_for: Option<Duration>,
+    /// `true` if `write_held_for` exceeds [`HealthPolicy::max_write_hold`].
+    pub writer_stuck: bool,
+    /// `true` if a write panicked and the instance refuses writes until
+    /// [`Movetex::clear_poison`](crate::Movetex::clear_poison) is called.
+    pub poisoned: bool,
+    /// The number of subscriptions that have not seen the published value yet.
+    pub subscriber_lag: usize,
+}
+
+impl Health {
+    /// Returns `true` if a value is published, it is not stale, no writer is stuck, and the
+    /// instance is not poisoned.
+    ///
+    /// Lagging subscribers do not make an instance unhealthy: they only delay themselves,
+    /// unless the writer waits for them through [`Backpressure`](crate::Backpressure) or
+    /// [`Delivery::Block`](crate::Delivery::Block).
+    pub fn is_healthy(&self) -> bool {
+        self.last_publish_age.is_some() && !self.stale && !self.writer_stuck && !self.poisoned
+    }
+}
+
+/// Durations are logged in microseconds, since `defmt` has no format for `Duration`.
+#[cfg(feature = "defmt")]
+impl defmt::Format for Health {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let micros = |age: Option<Duration>| age.map(|age| age.as_micros() as u64);
+        defmt::write!(
+            f,
+            "Health {{ last_publish_age_us: {}, stale: {}, write_held_for_us: {}, writer_stuck: {}, poisoned: {}, subscriber_lag: {} }}",
+            micros(self.last_publish_age),
+            self.stale,
+            micros(self.write_held_for),
+            self.writer_stuck,
+            self.poisoned,
+            self.subscriber_lag,
+        );
+    }
+}
+
+/// Implemented by types that can report their [`Health`].
+///
+/// This is the integration point for health-check frameworks: a readiness or liveness probe
+/// can hold any `HealthCheck` and restart the service once the published state goes stale or
+/// a writer stops making progress.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{HealthCheck, HealthPolicy, Movetex};
+///
+/// let movetex = Movetex::new(1);
+/// let health = movetex.health(&HealthPolicy::default());
+///
+/// assert!(health.is_healthy());
+/// assert!(!health.writer_stuck);
+/// ```
+pub trait HealthCheck {
+    /// Produces a report judged against `policy`.
+    fn health(&self, policy: &HealthPolicy) -> Health;
+}
+
+const NEVER: u64 = u64::MAX;
+
+/// Timestamps of publishes and writer-slot acquisitions, stored as nanoseconds since creation.
+pub(crate) struct Activity {
+    clock: Arc<dyn Clock>,
+    origin: Instant,
+    last_publish: AtomicU64,
+    write_started: AtomicU64,
+}
+
+impl Activity {
+    pub(crate) fn new(published: bool, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            origin: clock.now(),
+            clock,
+            last_publish: AtomicU64::new(if published { 0 } else { NEVER }),
+            write_started: AtomicU64::new(NEVER),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now().duration_since(self.origin).as_nanos() as u64
+    }
+
+    /// The clock of the instance, for the other features measuring time.
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    pub(crate) fn published(&self) {
+        self.last_publish.store(self.now(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn write_started(&self) {
+        self.write_started.store(self.now(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn write_finished(&self) {
+        self.write_started.store(NEVER, Ordering::Relaxed);
+    }
+
+    fn age(&self, at: &AtomicU64, now: u64) -> Option<Duration> {
+        match at.load(Ordering::Relaxed) {
+            NEVER => None,
+            at => Some(Duration::from_nanos(now.saturating_sub(at))),
+        }
+    }
+
+    /// The report of the timestamps alone; the instance fills in the state it tracks itself.
+    pub(crate) fn report(&self, policy: &HealthPolicy) -> Health {
+        let now = self.now();
+        let last_publish_age = self.age(&self.last_publish, now);
+        let write_held_for = self.age(&self.write_started, now);
+        Health {
+            last_publish_age,
+            stale: matches!(
+                (last_publish_age, policy.max_publish_age),
+                (Some(age), Some(max)) if age > max
+            ),
+            write_held_for,
+            writer_stuck: write_held_for.is_some_and(|held| held > policy.max_write_hold),
+            poisoned: false,
+            subscriber_lag: 0,
+        }
+    }
+}
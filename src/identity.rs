@@ -0,0 +1,34 @@
+//! Writer identity tracking for auditing publishes.
+
+use std::thread::{self, ThreadId};
+use std::time::SystemTime;
+
+/// Who performed a publish, recorded when writer tracking is enabled.
+///
+/// Tracking is enabled with [`MovetexBuilder::track_writers`](crate::MovetexBuilder::track_writers)
+/// and the most recent identity is returned by
+/// [`Movetex::last_writer`](crate::Movetex::last_writer). It answers the "who changed this
+/// config at 3am" question without wrapping every call site in logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterIdentity {
+    /// The thread that published the value.
+    pub thread: ThreadId,
+    /// The name of that thread, if it had one.
+    pub thread_name: Option<String>,
+    /// The label passed to [`Movetex::write_as`](crate::Movetex::write_as), if any.
+    pub label: Option<String>,
+    /// Wall-clock time of the publish.
+    pub published_at: SystemTime,
+}
+
+impl WriterIdentity {
+    pub(crate) fn current(label: Option<&str>) -> Self {
+        let thread = thread::current();
+        Self {
+            thread: thread.id(),
+            thread_name: thread.name().map(str::to_owned),
+            label: label.map(str::to_owned),
+            published_at: SystemTime::now(),
+        }
+    }
+}
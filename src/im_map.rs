@@ -0,0 +1,131 @@
+//! A `Movetex` publishing a persistent hash map (feature `im`).
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Movetex, ReadGuard, WriteError};
+
+/// A concurrent hash map published through a `Movetex<im::HashMap<K, V>>`.
+///
+/// A write starts by cloning the published value into the writer copy, which for a standard
+/// `HashMap` copies every entry. An `im::HashMap` shares its structure between clones instead:
+/// the clone copies a pointer, and an insert or remove copies only the O(log n) nodes on the
+/// path to the key. Large maps can thus be updated one entry at a time, without the clone cost
+/// that [`ShardedMap`](crate::ShardedMap) avoids by splitting the map.
+///
+/// The whole map is published at once, so unlike a sharded map every read sees the effect of
+/// all earlier writes.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexIm;
+///
+/// let routes = MovetexIm::new();
+/// routes.insert("/api", 8080u16).unwrap();
+/// routes.insert("/static", 8081).unwrap();
+///
+/// let before = routes.snapshot();
+/// assert_eq!(routes.remove("/static"), Ok(Some(8081)));
+/// assert_eq!(routes.get("/api").as_deref(), Some(&8080));
+/// assert_eq!((routes.len(), before.len()), (1, 2));
+/// ```
+pub struct MovetexIm<K: Clone, V: Clone, S = RandomState> {
+    movetex: Movetex<im::HashMap<K, V, S>>,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> MovetexIm<K, V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for MovetexIm<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, S: BuildHasher> MovetexIm<K, V, S> {
+    /// Same as [`MovetexIm::new`], but hashes keys with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            movetex: Movetex::new(im::HashMap::with_hasher(hasher)),
+        }
+    }
+
+    /// Returns the published value of `key`, keeping the snapshot of the map alive while the
+    /// guard lives.
+    pub fn get<Q>(&self, key: &Q) -> Option<ReadGuard<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        ReadGuard::filter_map(self.movetex.read(), |map| map.get(key)).ok()
+    }
+
+    /// Returns `true` if a value is published for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.movetex.read_map(|map| map.contains_key(key))
+    }
+
+    /// Publishes `value` for `key` and returns the value it replaced.
+    ///
+    /// Waits for a write in progress like [`Movetex::write_blocking`], and fails like it if
+    /// the map is poisoned by a panic in a previous write.
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, WriteError> {
+        let mut previous = None;
+        self.movetex
+            .write_blocking(|map| previous = map.insert(key, value))?;
+        Ok(previous)
+    }
+
+    /// Removes `key` and returns its value; waits and fails like [`MovetexIm::insert`].
+    ///
+    /// A key that is not present costs a read, not a write.
+    pub fn remove<Q>(&self, key: &Q) -> Result<Option<V>, WriteError>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return Ok(None);
+        }
+        let mut removed = None;
+        self.movetex
+            .write_blocking(|map| removed = map.remove(key))?;
+        Ok(removed)
+    }
+
+    /// Applies `f` to the map and publishes the result in one write; waits and fails like
+    /// [`MovetexIm::insert`].
+    pub fn update(&self, f: impl FnOnce(&mut im::HashMap<K, V, S>)) -> Result<(), WriteError> {
+        self.movetex.write_blocking(f)
+    }
+
+    /// Returns the published map; the clone shares its structure and costs a reference count
+    /// update.
+    pub fn snapshot(&self) -> im::HashMap<K, V, S> {
+        self.movetex.snapshot()
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.movetex.read_map(im::HashMap::len)
+    }
+
+    /// Returns `true` if the map holds no entry.
+    pub fn is_empty(&self) -> bool {
+        self.movetex.read_map(im::HashMap::is_empty)
+    }
+
+    /// The underlying instance, for the rest of the `Movetex` API.
+    pub fn movetex(&self) -> &Movetex<im::HashMap<K, V, S>> {
+        &self.movetex
+    }
+}
@@ -0,0 +1,112 @@
+//! Sharing of equal published values across instances.
+//!
+//! Instances configured with the same [`InternPool`] look up every value they publish in the
+//! pool. If an equal value is already published by another instance, the new snapshot shares
+//! its `Arc` instead of holding a copy of its own. The pool only keeps weak references, so a
+//! value is freed as soon as no instance publishes it anymore.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, Weak};
+
+use crate::spill::Slot;
+use crate::sync::Mutex;
+
+/// Interns a published value, see [`MovetexBuilder::intern`](crate::MovetexBuilder::intern).
+pub(crate) type Interner<T> = Box<dyn Fn(Arc<T>) -> Arc<T> + Send + Sync>;
+
+/// A pool of published values shared by the instances configured with it.
+///
+/// Suited to many instances, such as per-tenant configurations, that frequently hold the same
+/// value: each distinct value is stored once, however many instances publish it. Interning
+/// costs a hash and, for values already in the pool, a comparison on every publish.
+///
+/// ```rust
+/// use movetex::{InternPool, Movetex};
+/// use std::sync::Arc;
+///
+/// let pool = Arc::new(InternPool::new());
+/// let tenants: Vec<_> = (0..3)
+///     .map(|_| Movetex::builder(String::from("default")).intern(&pool).build())
+///     .collect();
+///
+/// tenants[1].write(|v| *v = String::from("custom"));
+/// assert_eq!(pool.len(), 2);
+/// ```
+pub struct InternPool<T> {
+    // Weak references to interned values, by hash
+    entries: Mutex<HashMap<u64, Vec<Weak<T>>>>,
+    hasher: RandomState,
+}
+
+impl<T: Hash + Eq> InternPool<T> {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Returns the pooled value equal to `value`, or adds `value` to the pool if there is none.
+    pub fn intern(&self, value: Arc<T>) -> Arc<T> {
+        let hash = self.hasher.hash_one(&*value);
+        let mut entries = self.entries.lock();
+        let bucket = entries.entry(hash).or_default();
+        bucket.retain(|entry| entry.strong_count() > 0);
+        if let Some(pooled) = bucket
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|pooled| **pooled == *value)
+        {
+            return pooled;
+        }
+        bucket.push(Arc::downgrade(&value));
+        value
+    }
+
+    /// The number of distinct values in the pool that are still alive.
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .values()
+            .flatten()
+            .filter(|entry| entry.strong_count() > 0)
+            .count()
+    }
+
+    /// Returns `true` if no value in the pool is alive.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the entries of values that have been freed.
+    ///
+    /// Entries are also cleaned up whenever an equal-hashing value is interned, so calling this
+    /// is only needed to reclaim memory after many distinct values have come and gone.
+    pub fn purge(&self) {
+        self.entries.lock().retain(|_, bucket| {
+            bucket.retain(|entry| entry.strong_count() > 0);
+            !bucket.is_empty()
+        });
+    }
+}
+
+impl<T: Hash + Eq> Default for InternPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moves the value of `slot` into the pool of `interner`, if there is one.
+pub(crate) fn intern<T: Clone>(interner: &Option<Interner<T>>, slot: Slot<T>) -> Slot<T> {
+    let Some(interner) = interner else {
+        return slot;
+    };
+    let arc = match slot {
+        Slot::Inline(value) => Arc::new(value),
+        Slot::Spilled(arc) => arc,
+    };
+    Slot::Spilled(interner(arc))
+}
@@ -0,0 +1,69 @@
+//! Reporting of internal invariant violations.
+//!
+//! The pointer pair of a `Movetex` is only ever null in states where the state machine rules
+//! out access. Should that ever not hold, the violation is reported to a process-wide handler
+//! instead of dereferencing a null pointer. The default handler panics; deployments that prefer
+//! to keep serving the last good snapshot can install their own with [`set_invariant_handler`].
+
+use std::fmt;
+
+use crate::sync::RwLock;
+
+/// An internal invariant of `Movetex` that was found not to hold.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvariantViolation {
+    /// The writer copy was missing although the state machine granted access to it.
+    ///
+    /// The writer copy is rebuilt from the published value, or replaced by the value passed to
+    /// `swap`, and the operation continues.
+    MissingWriterCopy,
+    /// The published value was missing on an initialized instance.
+    ///
+    /// The operation is abandoned and the instance falls back to the uninitialized state, so it
+    /// can be initialized again.
+    MissingPublishedValue,
+    /// A published snapshot no longer matches the checksum recorded when it was published.
+    ///
+    /// The snapshot is repaired if a repair hook is configured, and served as it is otherwise;
+    /// see [`MovetexBuilder::checksum`](crate::MovetexBuilder::checksum) and
+    /// [`MovetexBuilder::repair`](crate::MovetexBuilder::repair).
+    ChecksumMismatch,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::MissingWriterCopy => f.write_str("writer copy is missing"),
+            InvariantViolation::MissingPublishedValue => f.write_str("published value is missing"),
+            InvariantViolation::ChecksumMismatch => {
+                f.write_str("published snapshot does not match its checksum")
+            }
+        }
+    }
+}
+
+static HANDLER: RwLock<Option<fn(InvariantViolation)>> = RwLock::new(None);
+
+/// Installs `handler` to be called, instead of panicking, when an internal invariant violation
+/// is detected.
+///
+/// The handler applies to every `Movetex` in the process. After it returns, the affected
+/// operation recovers as described on each [`InvariantViolation`] variant, and readers keep
+/// the last published value.
+///
+/// ```rust
+/// movetex::set_invariant_handler(|violation| eprintln!("movetex: {violation}"));
+/// ```
+pub fn set_invariant_handler(handler: fn(InvariantViolation)) {
+    *HANDLER.write() = Some(handler);
+}
+
+pub(crate) fn report(violation: InvariantViolation) {
+    let handler = *HANDLER.read();
+    match handler {
+        Some(handler) => handler(violation),
+        None => panic!("movetex invariant violated: {violation}"),
+    }
+}
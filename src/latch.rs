@@ -0,0 +1,84 @@
+//! Readiness latch released by the first publish.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use crate::clock::Deadline;
+use crate::sync::atomic::{AtomicBool, Ordering};
+use crate::sync::{Condvar, Mutex};
+
+/// Released once, by the first publish of an instance that started without a real value.
+///
+/// Both blocking and async waiters are supported: threads wait on the condition variable and
+/// tasks register their wakers. The atomic flag keeps the fast path lock-free, so only the very
+/// first publish and the waiters themselves ever touch the lock.
+pub(crate) struct PublishLatch {
+    released: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+}
+
+impl PublishLatch {
+    pub(crate) fn new(released: bool) -> Self {
+        Self {
+            released: AtomicBool::new(released),
+            wakers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn is_released(&self) -> bool {
+        self.released.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn release(&self) {
+        if self.is_released() || self.released.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let wakers = std::mem::take(&mut *self.wakers.lock());
+        self.condvar.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Blocks until released or until `deadline` passes; returns `true` if released.
+    pub(crate) fn wait_until(&self, deadline: Deadline<'_>) -> bool {
+        let mut wakers = self.wakers.lock();
+        while !self.is_released() {
+            let Some(sleep) = deadline.sleep() else {
+                return false;
+            };
+            wakers = self.condvar.wait_timeout(wakers, sleep).0;
+        }
+        true
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_released() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.wakers.lock();
+        // `release` sets the flag before taking the wakers, so checking again under the lock
+        // guarantees the waker is either registered in time or the flag is observed.
+        if self.is_released() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// Future returned by [`Movetex::first_publish`](crate::Movetex::first_publish).
+pub struct FirstPublish<'a> {
+    pub(crate) latch: &'a PublishLatch,
+}
+
+impl Future for FirstPublish<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.latch.poll(cx)
+    }
+}
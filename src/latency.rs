@@ -0,0 +1,73 @@
+//! Latency histograms of read and write operations (feature `hdrhistogram`).
+
+use std::time::{Duration, Instant};
+
+use hdrhistogram::Histogram;
+
+use crate::sync::Mutex;
+
+/// Percentile data of the operations of a `Movetex`, returned by
+/// [`Movetex::latency_report`](crate::Movetex::latency_report).
+///
+/// Values are recorded in nanoseconds with three significant digits, up to one hour.
+#[derive(Debug, Clone)]
+pub struct LatencyReport {
+    /// Latencies of reads. Reads never wait for the recorder, so samples taken while another
+    /// reader is recording are dropped.
+    pub read: Histogram<u64>,
+    /// Latencies of successful writes, from acquiring the writer copy to publishing, including
+    /// the closure.
+    pub write: Histogram<u64>,
+    /// Latencies of the publish step of successful writes: validation, the clone of the new
+    /// writer copy, and the pointer swap.
+    pub publish: Histogram<u64>,
+}
+
+// Longer latencies are recorded as one hour.
+const HIGHEST: u64 = 60 * 60 * 1_000_000_000;
+
+pub(crate) struct LatencyRecorder {
+    read: Mutex<Histogram<u64>>,
+    write: Mutex<Histogram<u64>>,
+    publish: Mutex<Histogram<u64>>,
+}
+
+impl LatencyRecorder {
+    pub(crate) fn new() -> Self {
+        let histogram =
+            || Mutex::new(Histogram::new_with_bounds(1, HIGHEST, 3).expect("the bounds are valid"));
+        Self {
+            read: histogram(),
+            write: histogram(),
+            publish: histogram(),
+        }
+    }
+
+    pub(crate) fn record_read(&self, started: Instant) {
+        let elapsed = started.elapsed();
+        if let Some(mut read) = self.read.try_lock() {
+            read.saturating_record(nanos(elapsed));
+        }
+    }
+
+    pub(crate) fn record_write(&self, started: Instant, publish_started: Instant) {
+        self.write
+            .lock()
+            .saturating_record(nanos(started.elapsed()));
+        self.publish
+            .lock()
+            .saturating_record(nanos(publish_started.elapsed()));
+    }
+
+    pub(crate) fn report(&self) -> LatencyReport {
+        LatencyReport {
+            read: self.read.lock().clone(),
+            write: self.write.lock().clone(),
+            publish: self.publish.lock().clone(),
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,60 @@
+//! Compile-time memory footprint of `Movetex` instances.
+
+use std::mem::{align_of, size_of};
+
+use crate::snapshot::Snapshot;
+use crate::spill::Slot;
+use crate::Movetex;
+
+/// The memory a [`Movetex<T>`] occupies, available at compile time as [`Movetex::LAYOUT`].
+///
+/// An instance consists of the `Movetex` itself plus two heap allocations: the published
+/// snapshot read by readers and the private writer copy. With the `single-copy` feature, and
+/// for zero-sized payloads, there is no standing writer copy and `writer_copy_size` is zero.
+/// Memory owned by the payload (the buffer of a `Vec`, for example) is not included. While a
+/// write publishes, a third allocation of `snapshot_size` bytes exists until the previous
+/// snapshot is retired.
+///
+/// ```rust
+/// use movetex::Movetex;
+///
+/// const BUDGET: usize = 64 * 1024;
+/// const PER_INSTANCE: usize = Movetex::<[u8; 256]>::LAYOUT.total_size();
+/// const _: () = assert!(8 * PER_INSTANCE <= BUDGET);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MovetexLayout {
+    /// Size of the `Movetex` value itself.
+    pub size: usize,
+    /// Alignment of the `Movetex` value itself.
+    pub align: usize,
+    /// Size of the heap allocation holding the published snapshot.
+    pub snapshot_size: usize,
+    /// Size of the heap allocation holding the writer copy, or zero if there is none.
+    pub writer_copy_size: usize,
+    /// Bytes each copy spends beyond the payload, for the spill tag and snapshot metadata.
+    pub snapshot_overhead: usize,
+}
+
+impl MovetexLayout {
+    /// The size of the instance plus both heap allocations.
+    pub const fn total_size(&self) -> usize {
+        self.size + self.snapshot_size + self.writer_copy_size
+    }
+}
+
+impl<T: Clone> Movetex<T> {
+    /// The memory footprint of an instance, for budgeting memory at compile time.
+    pub const LAYOUT: MovetexLayout = MovetexLayout {
+        size: size_of::<Self>(),
+        align: align_of::<Self>(),
+        snapshot_size: size_of::<Snapshot<T>>(),
+        writer_copy_size: if Self::STANDING_WRITER_COPY {
+            size_of::<Slot<T>>()
+        } else {
+            0
+        },
+        snapshot_overhead: size_of::<Snapshot<T>>() - size_of::<T>(),
+    };
+}
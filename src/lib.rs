@@ -34,8 +34,148 @@
 //!
 //! This example shows how Movetex ensures atomicity for complex data reads/writes, with controlled handling for write contention.
 
-use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+mod ahead;
+mod arc;
+#[cfg(feature = "arc-swap")]
+mod arc_swap_interop;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod builder;
+mod capabilities;
+mod checksum;
+mod clock;
+mod copy;
+mod cost;
+mod cow;
+mod diff;
+mod dropper;
+mod epoch;
+mod error;
+mod event;
+mod exclusive;
+#[cfg(feature = "follower")]
+mod follower;
+mod global;
+mod guard;
+mod handle;
+mod health;
+mod identity;
+#[cfg(feature = "im")]
+mod im_map;
+mod intern;
+mod invariant;
+mod latch;
+#[cfg(feature = "hdrhistogram")]
+mod latency;
+mod layout;
+mod multi;
+#[cfg(feature = "tokio")]
+mod offload;
+mod ordering;
+#[cfg(feature = "json")]
+mod patch;
+mod per_key;
+mod phase;
+mod pipeline;
+mod retry;
+mod scatter;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sharded;
+mod slab;
+mod snapshot;
+mod spill;
+mod state;
+mod stats;
+mod sync;
+mod transaction;
+mod validate;
+mod watch;
+mod write_through;
+
+use std::fmt;
+use std::mem::{self, MaybeUninit};
+use std::panic;
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ahead::CloneAhead;
+pub use arc::MovetexArc;
+#[cfg(feature = "arc-swap")]
+pub use arc_swap_interop::ArcSwapMirror;
+pub use builder::MovetexBuilder;
+pub use capabilities::{capabilities, Capabilities, Reclamation};
+use checksum::{Checksum, Repair};
+use clock::Deadline;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use copy::{AtomicCopy, MovetexCopy};
+pub use cost::CloneCost;
+pub use cow::MovetexCow;
+pub use diff::{Diff, MapDiff};
+use epoch::Epochs;
+pub use error::{
+    AlreadyInitialized, AlreadyPublished, CompareAndSwapError, NotInitialized, WriteError,
+};
+pub use event::{EventSourced, Journal};
+pub use exclusive::MutGuard;
+#[cfg(feature = "follower")]
+pub use follower::{Follow, Follower};
+pub use global::global;
+pub use guard::{OwnedReadGuard, ReadGuard};
+use guard::{ReaderPin, SnapshotHold};
+pub use handle::{MovetexReader, MovetexWriter, ReadOnly, WriteOnly};
+use health::Activity;
+pub use health::{Health, HealthCheck, HealthPolicy};
+pub use identity::WriterIdentity;
+#[cfg(feature = "im")]
+pub use im_map::MovetexIm;
+pub use intern::InternPool;
+use intern::Interner;
+pub use invariant::{set_invariant_handler, InvariantViolation};
+pub use latch::FirstPublish;
+use latch::PublishLatch;
+#[cfg(feature = "hdrhistogram")]
+use latency::LatencyRecorder;
+#[cfg(feature = "hdrhistogram")]
+pub use latency::LatencyReport;
+pub use layout::MovetexLayout;
+pub use multi::{read_pair, read_pair_reported, write_pair, ConsistencyReport};
+pub use ordering::MemoryOrdering;
+pub use per_key::MovetexPerKey;
+pub use phase::MovetexBuilderPhase;
+pub use pipeline::{StateSink, StateSource};
+pub use retry::{Attempt, Backoff, RetryPolicy};
+pub use scatter::ScatterGather;
+pub use sharded::{ShardCount, ShardedMap};
+pub use slab::{MovetexSlab, SlabBatch};
+pub use snapshot::Meta;
+use snapshot::Snapshot;
+use spill::Slot;
+pub use spill::SpillPolicy;
+use state::{Acquired, PoisonOnUnwind, State, WriterState};
+use stats::Counters;
+#[cfg(feature = "stats")]
+pub use stats::MovetexStats;
+use sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+use sync::Mutex;
+pub use transaction::{WriteGuard, WriteTransaction};
+#[cfg(feature = "jsonschema")]
+pub use validate::JsonSchemaValidator;
+pub use validate::{ValidationError, Validator};
+pub use watch::{Backpressure, Changed, Delivery, DiffSubscription, Subscription};
+use watch::{Deliveries, PublishWatch};
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::multi::{
+        begin_write_2, begin_write_3, begin_write_4, begin_write_5, begin_write_6, begin_write_7,
+        begin_write_8, commit_2, commit_3, commit_4, commit_5, commit_6, commit_7, commit_8,
+        read_stable, read_stable_reported, transaction_2, transaction_3, transaction_4,
+        transaction_5, transaction_6, transaction_7, transaction_8, VersionedRead,
+    };
+}
+pub use write_through::{PersistFailure, PersistOrder, Store, WriteThrough, WriteThroughError};
 
 /// Movetex: A lock-free synchronization primitive for concurrent data access
 ///
@@ -82,14 +222,128 @@ use std::sync::atomic::{AtomicPtr, Ordering};
 /// this case by blocking, retrying, or yielding if in an async context. Further explanations of
 /// the `write` and `swap` mechanisms, and the cloning rationale in `write`, are provided in the
 /// detailed documentation.
-
+///
+/// ### Thread Safety
+///
+/// `Movetex<T>` is `Send` if `T` is `Send`, since it owns its values and drops them wherever
+/// it is dropped. It is `Sync` only if `T` is both `Send` and `Sync`: readers on any thread
+/// share `&T`, and writes on any thread clone values and drop replaced ones, possibly on the
+/// thread releasing the last read guard.
+///
+/// ```compile_fail
+/// use movetex::Movetex;
+/// use std::rc::Rc;
+///
+/// fn share<S: Send>(_: S) {}
+/// share(Movetex::new(Rc::new(1)));
+/// ```
+///
+/// ```compile_fail
+/// use movetex::Movetex;
+/// use std::cell::Cell;
+///
+/// fn share<S: Sync>(_: S) {}
+/// share(Movetex::new(Cell::new(1)));
+/// ```
 pub struct Movetex<T: Clone> {
     // Atomic pointer for reading
-    ptr_r: AtomicPtr<T>,
+    ptr_r: AtomicPtr<Snapshot<T>>,
     // Atomic pointer for writing
-    ptr_w: AtomicPtr<T>,
+    ptr_w: AtomicPtr<Slot<T>>,
+    // Orderings of the accesses to `ptr_r`, `ptr_w` and `state`
+    ordering: MemoryOrdering,
+    // State machine guarding `ptr_w`
+    state: WriterState,
+    // Decides when the payload is moved behind an `Arc`
+    spill: SpillPolicy<T>,
+    // Checks every candidate value before it is published
+    validator: Option<Box<dyn Validator<T>>>,
+    // Released by the first publish of an instance created from a placeholder
+    latch: PublishLatch,
+    // Wakes subscribers after every publish
+    watch: PublishWatch,
+    // Queues of the subscriptions that take every published value
+    deliveries: Deliveries<T>,
+    // Publish and writer-slot timestamps for health reports
+    activity: Activity,
+    // Identity of the most recent publisher, if writer tracking is enabled
+    last_writer: Option<Mutex<Option<WriterIdentity>>>,
+    // Number of publishes since construction
+    version: AtomicU64,
+    // Readers loading the published snapshot, see `epoch`
+    epochs: Epochs,
+    // Snapshots replaced by a publish that a guard may still see, with the epoch they were
+    // unlinked in; guards point into the boxes, so they must not move
+    retired: Mutex<Vec<(usize, Box<Snapshot<T>>)>>,
+    // A reclaimed snapshot kept for the next publish to reuse, see `retire`
+    spare: Mutex<Option<Box<Snapshot<T>>>>,
+    // The allocation of the last spare turned into a writer copy, for the next publish to
+    // store its snapshot in
+    spare_allocation: Mutex<Option<Box<MaybeUninit<Snapshot<T>>>>>,
+    // Served by `read` while no published value is available
+    fallback: Option<T>,
+    // Verifies published snapshots on every read, if configured
+    checksum: Option<Checksum<T>>,
+    // Replaces published snapshots found to be bad, if configured
+    repair: Option<Repair<T>>,
+    // Set by a read that found a bad snapshot, for the next write to repair
+    repair_pending: AtomicBool,
+    // Receives retired values instead of dropping them, if configured
+    on_retire: Option<fn(T)>,
+    // Seal the instance as soon as it holds a value
+    write_once: bool,
+    // Prepares the next writer copy in the background, if enabled
+    clone_ahead: Option<CloneAhead<T>>,
+    // Drops retired snapshots on another thread, if enabled
+    drop_later: Option<fn(Box<Snapshot<T>>)>,
+    // Shares published values with other instances, if configured
+    intern: Option<Interner<T>>,
+    // Writes whose closure runs longer are discarded, if configured
+    max_write_duration: Option<Duration>,
+    // Operation latency histograms, if enabled
+    #[cfg(feature = "hdrhistogram")]
+    latency: Option<LatencyRecorder>,
+    // Operation counters; empty without the `stats` feature
+    stats: Counters,
+}
+
+/// A write that owns the writer copy and has taken it out of `ptr_w`, see
+/// `Movetex::begin_write_acquired`.
+struct PendingWrite<'a, T: Clone> {
+    acquired: PoisonOnUnwind<'a>,
+    // Kept boxed, so the publish can store the next writer copy back into its allocation
+    slot: Box<Slot<T>>,
+    #[cfg(feature = "hdrhistogram")]
+    started: Instant,
+    #[cfg(feature = "hdrhistogram")]
+    publish_started: Instant,
+    // When the modification started, and how long it may take
+    applied: Option<(Instant, Duration)>,
+    // Open from acquiring the writer copy until the write publishes or backs out
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
+// The pointers own `Snapshot<T>` and `Slot<T>` allocations, which the auto traits of
+// `AtomicPtr` would not account for.
+unsafe impl<T: Clone + Send> Send for Movetex<T> {}
+unsafe impl<T: Clone + Send + Sync> Sync for Movetex<T> {}
+
+impl<T: Clone> Movetex<T> {
+    /// Whether a clone of the published value is kept as the writer copy between writes.
+    ///
+    /// The minimal engine of the `single-copy` feature never keeps one, and neither do
+    /// zero-sized payloads, whose clones cost nothing to make on demand.
+    pub(crate) const STANDING_WRITER_COPY: bool =
+        !cfg!(feature = "single-copy") && mem::size_of::<T>() != 0;
+}
+
+/// How many times [`Movetex::read_fresh`] polls for a write in progress before giving up.
+const FRESH_SPINS: u32 = 128;
+
+/// How many times [`Movetex::write_blocking`] polls for a write in progress before parking.
+const BLOCKING_SPINS: u32 = 128;
+
 impl<T: Clone> Movetex<T> {
     /// Creates a new `Movetex` instance containing an initial value.
     ///
@@ -99,28 +353,775 @@ impl<T: Clone> Movetex<T> {
     /// guarantees that the internal pointers for read and write are synchronized initially,
     /// supporting atomic operations on the data.
     pub fn new(value: T) -> Self {
+        Self::builder(value).build()
+    }
+
+    /// Returns a [`MovetexBuilder`] for configuring optional policies before construction.
+    pub fn builder(value: T) -> MovetexBuilder<T> {
+        MovetexBuilder::new(value)
+    }
+
+    pub(crate) fn from_builder(builder: MovetexBuilder<T>) -> Self {
+        let MovetexBuilder {
+            value,
+            spill,
+            validator,
+            published,
+            track_writers,
+            clock,
+            fallback,
+            checksum,
+            write_once,
+            clone_ahead,
+            drop_later,
+            repair,
+            on_retire,
+            intern,
+            max_write_duration,
+            memory_ordering,
+            #[cfg(feature = "hdrhistogram")]
+            record_latencies,
+        } = builder;
+        let state = match value {
+            Some(_) if write_once => State::Sealed,
+            Some(_) => State::Idle,
+            None => State::Uninitialized,
+        };
+        let (ptr_r, ptr_w) = match value {
+            Some(value) if !Self::STANDING_WRITER_COPY => (
+                Box::into_raw(Box::new(Snapshot::new(
+                    intern::intern(&intern, value.apply(&spill)),
+                    Meta::new(0, None),
+                    checksum,
+                ))),
+                ptr::null_mut(),
+            ),
+            Some(value) => {
+                let (reader, writer) = value.publish(&spill);
+                (
+                    Box::into_raw(Box::new(Snapshot::new(
+                        intern::intern(&intern, reader),
+                        Meta::new(0, None),
+                        checksum,
+                    ))),
+                    Box::into_raw(Box::new(writer)),
+                )
+            }
+            None => (ptr::null_mut(), ptr::null_mut()),
+        };
         Self {
-            ptr_r: AtomicPtr::new(Box::into_raw(Box::new(value.clone()))),
-            ptr_w: AtomicPtr::new(Box::into_raw(Box::new(value))),
+            ptr_r: AtomicPtr::new(ptr_r),
+            ptr_w: AtomicPtr::new(ptr_w),
+            ordering: memory_ordering,
+            state: WriterState::new(state, memory_ordering),
+            spill,
+            validator,
+            latch: PublishLatch::new(published),
+            watch: PublishWatch::new(),
+            deliveries: Deliveries::new(),
+            activity: Activity::new(published, clock),
+            last_writer: track_writers.then(|| Mutex::new(None)),
+            version: AtomicU64::new(0),
+            epochs: Epochs::new(),
+            retired: Mutex::new(Vec::new()),
+            spare: Mutex::new(None),
+            spare_allocation: Mutex::new(None),
+            fallback,
+            checksum,
+            repair,
+            repair_pending: AtomicBool::new(false),
+            on_retire,
+            write_once,
+            // The prepared copy would be the standing writer copy these instances avoid.
+            clone_ahead: clone_ahead
+                .filter(|_| Self::STANDING_WRITER_COPY)
+                .map(CloneAhead::new),
+            drop_later,
+            intern,
+            max_write_duration,
+            #[cfg(feature = "hdrhistogram")]
+            latency: record_latencies.then(LatencyRecorder::new),
+            stats: Counters::default(),
+        }
+    }
+
+    /// Creates an instance that holds no value yet.
+    ///
+    /// Unlike wrapping the payload in an `Option`, the "not loaded yet" state does not leak into
+    /// every read path: once [`Movetex::initialize`] has been called, readers get the value itself.
+    /// Before that, [`Movetex::try_read`] reports [`NotInitialized`], [`Movetex::read_timeout`]
+    /// blocks until the value arrives, [`Movetex::first_publish`] can be awaited, and writes fail
+    /// with [`WriteError::NotInitialized`].
+    ///
+    /// ### Usage Example:
+    /// ```rust
+    /// use movetex::{Movetex, NotInitialized};
+    ///
+    /// let movetex = Movetex::<String>::uninit();
+    /// assert_eq!(movetex.try_read(), Err(NotInitialized));
+    ///
+    /// movetex.initialize("loaded".to_string()).unwrap();
+    /// assert_eq!(*movetex.read(), "loaded");
+    /// ```
+    pub fn uninit() -> Self {
+        Self::uninit_builder().build()
+    }
+
+    /// Returns a [`MovetexBuilder`] for an instance created without a value, see
+    /// [`Movetex::uninit`].
+    pub fn uninit_builder() -> MovetexBuilder<T> {
+        MovetexBuilder::uninit()
+    }
+
+    /// Publishes the first value of an instance created with [`Movetex::uninit`].
+    ///
+    /// Returns the value back inside [`AlreadyInitialized`] if the instance already holds one.
+    /// Initialization releases [`Movetex::first_publish`] and [`Movetex::wait_first_publish`].
+    pub fn initialize(&self, value: T) -> Result<(), AlreadyInitialized<T>> {
+        let state = if self.write_once {
+            State::Sealed
+        } else {
+            State::Idle
+        };
+        self.initialize_into(value, state)
+            .map_err(AlreadyInitialized)
+    }
+
+    /// Publishes the only value an instance created with [`Movetex::uninit`] will ever hold.
+    ///
+    /// The instance is sealed in the same step (see [`Movetex::seal`]), so every later write
+    /// fails with [`WriteError::Sealed`]. This suits data computed at startup and immutable
+    /// afterwards that still wants the lock-free read path; combine it with
+    /// [`MovetexBuilder::write_once`] to make [`Movetex::initialize`] behave the same way.
+    ///
+    /// Returns the value back inside [`AlreadyPublished`] if the instance already holds one.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, WriteError};
+    ///
+    /// let table = Movetex::<Vec<u32>>::uninit();
+    /// table.publish_once((0..4).map(|i| i * i).collect()).unwrap();
+    ///
+    /// assert_eq!(table.read()[3], 9);
+    /// assert_eq!(table.try_write(|t| t.clear()), Err(WriteError::Sealed));
+    /// assert!(table.publish_once(Vec::new()).is_err());
+    /// ```
+    pub fn publish_once(&self, value: T) -> Result<(), AlreadyPublished<T>> {
+        self.initialize_into(value, State::Sealed)
+            .map_err(AlreadyPublished)
+    }
+
+    /// Publishes the first value and leaves the writer copy in `state`.
+    fn initialize_into(&self, value: T, state: State) -> Result<(), T> {
+        let Ok(acquired) = self
+            .state
+            .acquire(|state| state == State::Uninitialized, State::Writing)
+        else {
+            return Err(value);
+        };
+        let reader = if !Self::STANDING_WRITER_COPY {
+            Slot::Inline(value).apply(&self.spill)
+        } else {
+            let (reader, writer) = Slot::Inline(value).publish(&self.spill);
+            self.ptr_w.store(
+                Box::into_raw(Box::new(writer)),
+                self.ordering.writer_release(),
+            );
+            reader
+        };
+        let reader = intern::intern(&self.intern, reader);
+        let meta = Meta::new(self.begin_publish(), None);
+        let snapshot = Box::into_raw(Box::new(Snapshot::new(reader, meta, self.checksum)));
+        self.ptr_r.store(snapshot, self.ordering.store());
+        self.deliveries.deliver(unsafe { &*snapshot });
+        self.state.release(acquired, state);
+        self.on_publish(None);
+        Ok(())
+    }
+
+    /// Returns `false` for an instance created with [`Movetex::uninit`] that has not been
+    /// initialized yet.
+    pub fn is_initialized(&self) -> bool {
+        !self.ptr_r.load(self.ordering.load()).is_null()
+    }
+
+    /// Loads the published slot; the caller must hold a pin or own the writer copy.
+    fn published(&self) -> Option<&Slot<T>> {
+        self.published_snapshot().map(Snapshot::slot)
+    }
+
+    /// Same as `published`, but returns the whole snapshot.
+    fn published_snapshot(&self) -> Option<&Snapshot<T>> {
+        #[cfg(feature = "hdrhistogram")]
+        if let Some(latency) = &self.latency {
+            let started = Instant::now();
+            let published = self.load_snapshot();
+            latency.record_read(started);
+            return published.inspect(|_| self.stats.read());
+        }
+        self.load_snapshot().inspect(|_| self.stats.read())
+    }
+
+    /// Loads the published snapshot and takes a hold on it for a guard.
+    fn hold_published(&self) -> (Option<&Snapshot<T>>, SnapshotHold<'_>) {
+        let _pin = self.pin();
+        let snapshot = self.published_snapshot();
+        let holds = snapshot.map(Snapshot::holds);
+        if let Some(holds) = holds {
+            holds.hold();
+        }
+        (snapshot, SnapshotHold::new(holds))
+    }
+
+    fn load_published(&self) -> Option<&Slot<T>> {
+        self.load_snapshot().map(Snapshot::slot)
+    }
+
+    /// Loads the published slot without checking it; for writers cloning it into a writer copy,
+    /// which must own the writer copy through `state`.
+    fn published_unverified(&self) -> Option<&Slot<T>> {
+        unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }.map(Snapshot::slot)
+    }
+
+    /// Loads the published snapshot, leaving a repair for the next write if it fails its
+    /// checksum.
+    fn load_snapshot(&self) -> Option<&Snapshot<T>> {
+        let snapshot = unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }?;
+        if !snapshot.verify(self.checksum) && self.repair.is_some() {
+            self.repair_pending.store(true, Ordering::Relaxed);
+        }
+        Some(snapshot)
+    }
+
+    /// Replaces the writer copy with the repair of the published snapshot, if a read found it
+    /// bad and it still is; the writer must own the writer copy through `state`.
+    fn repair_writer_copy(&self, slot: &mut Slot<T>) {
+        if !self.repair_pending.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        // Owning the writer copy keeps the published snapshot from being replaced.
+        let Some(snapshot) = (unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }) else {
+            return;
+        };
+        if snapshot.is_intact(self.checksum) {
+            return;
+        }
+        if let Some(value) = self.repair.and_then(|repair| repair(snapshot.slot())) {
+            *slot.make_mut() = value;
+        }
+    }
+
+    /// Publishes the repaired replacement of a bad snapshot; returns `true` if it was published.
+    ///
+    /// Fails while another write is in progress.
+    fn repair_published(&self, snapshot: &Snapshot<T>) -> bool {
+        let Some(value) = self.repair.and_then(|repair| repair(snapshot.slot())) else {
+            return false;
+        };
+        self.try_write(|current| *current = value).is_ok()
+    }
+
+    /// Checks the published snapshot against its checksum and the validator, and repairs it
+    /// if it fails either check.
+    ///
+    /// Long-running processes can call this periodically to catch snapshots that went bad
+    /// after publishing, see [`MovetexBuilder::repair`]. Returns `true` if the published
+    /// snapshot passes both checks, possibly after being repaired, and `false` if it is still
+    /// bad. An instance without a published value has nothing to check and returns `true`.
+    pub fn verify_published(&self) -> bool {
+        let _pin = self.pin();
+        let Some(snapshot) = (unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }) else {
+            return true;
+        };
+        let valid = self
+            .validator
+            .as_ref()
+            .is_none_or(|validator| validator.validate(snapshot.slot()).is_ok());
+        if snapshot.verify(self.checksum) && valid {
+            return true;
         }
+        self.repair_published(snapshot)
+    }
+
+    #[cfg(feature = "arc-swap")]
+    pub(crate) fn from_arc(value: Arc<T>) -> Self {
+        MovetexBuilder::from_slot(Slot::Spilled(value))
+            .spill(SpillPolicy::always())
+            .build()
     }
 
-    /// Provides a reference to the read-only copy of the data in `Movetex`.
+    /// Returns the published value as an `Arc`, sharing the allocation if it is spilled.
+    #[cfg(feature = "arc-swap")]
+    pub(crate) fn published_arc(&self) -> Arc<T> {
+        let _pin = self.pin();
+        match self.published().expect("movetex has not been initialized") {
+            Slot::Spilled(arc) => Arc::clone(arc),
+            Slot::Inline(value) => Arc::new(value.clone()),
+        }
+    }
+
+    /// Provides a guard dereferencing to the read-only copy of the data in `Movetex`.
     ///
-    /// The `read` method returns a `&T` reference, which is always safe to access and never null.
+    /// The `read` method returns a [`ReadGuard`], which is always safe to access and never null.
     /// `Movetex` maintains separate atomic pointers for reading and writing, ensuring
-    /// that the reader always accesses a valid, initialized copy of the data.
+    /// that the reader always accesses a valid, initialized copy of the data. A write publishing
+    /// while the guard is held does not free the value behind it; the value is reclaimed by the
+    /// first publish after the last guard that can see it is dropped.
     ///
     /// Readers do not block each other, and they are isolated from writers by accessing a separate copy.
-    pub fn read(&self) -> &T {
-        unsafe { &*self.ptr_r.load(Ordering::Acquire) }
+    ///
+    /// If the instance was created with [`Movetex::uninit`] and has not been initialized yet,
+    /// the fallback configured with [`MovetexBuilder::fallback`] is returned instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized and no fallback is configured; use
+    /// [`Movetex::try_read`], [`Movetex::read_or`] or [`Movetex::read_timeout`] for such
+    /// instances.
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        let (snapshot, hold) = self.hold_published();
+        ReadGuard::new(self.value_or_fallback(snapshot), hold)
+    }
+
+    /// Calls `f` with the published value and returns its result, releasing the snapshot
+    /// right after.
+    ///
+    /// Suited to extracting a field of a large value without holding a guard to all of it; use
+    /// [`ReadGuard::map`] to keep a borrowed field instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// #[derive(Clone)]
+    /// struct Config {
+    ///     port: u16,
+    ///     routes: Vec<String>,
+    /// }
+    ///
+    /// let config = Movetex::new(Config { port: 8080, routes: vec![] });
+    /// assert_eq!(config.read_map(|c| c.port), 8080);
+    /// assert_eq!(config.read_map(|c| c.routes.len()), 0);
+    /// ```
+    pub fn read_map<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Same as [`Movetex::read`], but the guard owns a reference to the instance instead of
+    /// borrowing it.
+    ///
+    /// The guard of [`Movetex::read`] is the cheaper one: it only borrows the instance and
+    /// cannot leave the thread that took it. The owned guard costs an additional reference
+    /// count update on creation and drop, but it is `'static` and `Send` when `T` is `Send`
+    /// and `Sync`, so it can be moved into another thread or held across an `.await`.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let movetex = Arc::new(Movetex::new(vec![1, 2, 3]));
+    /// let guard = movetex.read_owned();
+    /// movetex.write(|v| v.clear());
+    ///
+    /// let len = thread::spawn(move || guard.len()).join().unwrap();
+    /// assert_eq!(len, 3);
+    /// ```
+    pub fn read_owned(self: &Arc<Self>) -> OwnedReadGuard<T> {
+        let (snapshot, hold) = self.hold_published();
+        let value = NonNull::from(self.value_or_fallback(snapshot));
+        // The owned guard releases the hold through its own reference to the instance.
+        mem::forget(hold);
+        let holds = snapshot.map(|snapshot| NonNull::from(snapshot.holds()));
+        OwnedReadGuard::new(Arc::clone(self), value, holds)
+    }
+
+    /// Returns a clone of the published value, which is owned and independent of the instance.
+    ///
+    /// Unlike a guard, the clone keeps no snapshot alive and can be sent anywhere; it is the
+    /// simplest choice when the value is cheap to clone. For large values, see
+    /// [`Movetex::snapshot_arc`] and [`Movetex::read_owned`].
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    pub fn snapshot(&self) -> T {
+        T::clone(&self.read())
+    }
+
+    /// Same as [`Movetex::snapshot`], but returns the value behind an `Arc`.
+    ///
+    /// A value spilled behind an `Arc` (see [`SpillPolicy`]) is shared without cloning, so with
+    /// [`SpillPolicy::always`] this is a reference count update; an inline value is cloned into
+    /// a new `Arc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, SpillPolicy};
+    /// use std::sync::Arc;
+    ///
+    /// let movetex = Movetex::builder(vec![0u8; 4096])
+    ///     .spill(SpillPolicy::always())
+    ///     .build();
+    /// let first = movetex.snapshot_arc();
+    /// assert!(Arc::ptr_eq(&first, &movetex.snapshot_arc()));
+    /// ```
+    pub fn snapshot_arc(&self) -> Arc<T> {
+        let _pin = self.pin();
+        match self.published() {
+            Some(Slot::Spilled(arc)) => Arc::clone(arc),
+            _ => Arc::new(self.published_or_fallback().clone()),
+        }
+    }
+
+    /// The published value, or the fallback if there is none; the caller holds a pin.
+    fn published_or_fallback(&self) -> &T {
+        self.value_or_fallback(self.published_snapshot())
+    }
+
+    /// The value of `snapshot`, or the fallback if there is none.
+    fn value_or_fallback<'a>(&'a self, snapshot: Option<&'a Snapshot<T>>) -> &'a T {
+        match snapshot {
+            Some(snapshot) => snapshot.slot(),
+            None => self
+                .fallback
+                .as_ref()
+                .expect("movetex has not been initialized"),
+        }
+    }
+
+    /// Returns the published value together with the metadata of its publish.
+    ///
+    /// Both come from the same snapshot, so unlike separate calls they cannot describe different
+    /// publishes. The writer label is only recorded for writes made with [`Movetex::write_as`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized; a fallback has no metadata.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(1);
+    /// movetex.write_as("deploy", |v| *v = 2);
+    ///
+    /// let (value, meta) = movetex.read_meta();
+    /// assert_eq!(*value, 2);
+    /// assert_eq!(meta.version, 1);
+    /// assert_eq!(meta.writer_label.as_deref(), Some("deploy"));
+    /// ```
+    pub fn read_meta(&self) -> (ReadGuard<'_, T>, Meta) {
+        let (snapshot, hold) = self.hold_published();
+        let snapshot = snapshot.expect("movetex has not been initialized");
+        (
+            ReadGuard::new(snapshot.slot(), hold),
+            snapshot.meta().clone(),
+        )
+    }
+
+    /// Calls `f` with a pointer to the published value that stays valid, and points to the same
+    /// unchanged value, until `f` returns.
+    ///
+    /// While the scope is open, writes still publish new values, but the snapshot seen by `f`
+    /// is not freed: the scope holds a [`ReadGuard`] until it ends. This makes it safe to hand
+    /// the pointer to C callbacks that cache it for the duration of the call. The pointer must
+    /// not be used after `f` returns.
+    ///
+    /// Like [`Movetex::read`], `f` receives the fallback of an uninitialized instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized and no fallback is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// extern "C" fn callback(value: *const u32) -> u32 {
+    ///     unsafe { *value }
+    /// }
+    ///
+    /// let movetex = Movetex::new(7u32);
+    /// let mut seen = 0;
+    /// movetex.with_pinned(|value| seen = callback(value));
+    /// assert_eq!(seen, 7);
+    /// ```
+    pub fn with_pinned<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        let guard = self.read();
+        f(&*guard)
+    }
+
+    /// Returns the approximate number of reads currently holding on to a snapshot.
+    ///
+    /// Counts the live guards on published and retired snapshots, including those held by
+    /// [`Movetex::with_pinned`] scopes in progress; guards on a fallback are not counted. The
+    /// counts are loaded one after the other and may be outdated by the time they are
+    /// returned, which is enough for dashboards showing reader pressure and for polling until a
+    /// graceful drain has completed.
+    pub fn active_readers(&self) -> usize {
+        let _pin = self.pin();
+        let published = unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }
+            .map_or(0, |snapshot| snapshot.holds().count());
+        let retired = self.retired.lock();
+        published
+            + retired
+                .iter()
+                .map(|(_, snapshot)| snapshot.holds().count())
+                .sum::<usize>()
+    }
+
+    /// Same as [`Movetex::read`], but first waits briefly for a write in progress to publish.
+    ///
+    /// If a write owns the writer copy, the call spins for a bounded number of iterations
+    /// (`FRESH_SPINS`) until the writer releases it, then returns the newest published value.
+    /// If the write takes longer, the current snapshot is returned as `read` would. This trades
+    /// a few nanoseconds of spinning for not serving a value that is about to be superseded.
+    pub fn read_fresh(&self) -> ReadGuard<'_, T> {
+        for _ in 0..FRESH_SPINS {
+            if self.state.get() != State::Writing {
+                break;
+            }
+            crate::sync::spin_loop();
+        }
+        self.read()
+    }
+
+    /// Calls `f` up to `n` times with one pinned snapshot, stopping early once a newer value
+    /// is being published; returns the number of calls made.
+    ///
+    /// Tight polling loops can use this to pay for the pointer load and the pin once per batch
+    /// instead of once per iteration. Before every call, including the first, the version of
+    /// the pinned snapshot is compared with the version of the instance, so `f` never sees a
+    /// value that has been superseded for longer than one call. The caller can start a new
+    /// batch when fewer than `n` calls were made.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has not been initialized and no fallback is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(3);
+    /// let mut sum = 0;
+    /// assert_eq!(movetex.read_batch(4, |v| sum += v), 4);
+    /// assert_eq!(sum, 12);
+    /// ```
+    pub fn read_batch(&self, n: usize, mut f: impl FnMut(&T)) -> usize {
+        let (version, guard) = self.read_versioned();
+        for calls in 0..n {
+            if self.publish_count() != version {
+                return calls;
+            }
+            f(&guard);
+        }
+        n
+    }
+
+    /// Same as [`Movetex::read`], but also returns the version of the value read.
+    ///
+    /// The version is the one [`Movetex::version`] reported while the value was published, so
+    /// a caller can remember it and later tell whether the value has changed without cloning
+    /// or comparing it. A fallback has version 0.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(vec![0u8; 1024]);
+    /// let (seen, _) = movetex.read_versioned();
+    /// assert_eq!(movetex.version(), seen);
+    ///
+    /// movetex.write(|v| v[0] = 1);
+    /// assert_ne!(movetex.version(), seen);
+    /// ```
+    pub fn read_versioned(&self) -> (u64, ReadGuard<'_, T>) {
+        let (snapshot, hold) = self.hold_published();
+        let version = snapshot.map_or(0, |snapshot| snapshot.meta().version);
+        let value = self.value_or_fallback(snapshot);
+        (version, ReadGuard::new(value, hold))
+    }
+
+    /// Same as [`Movetex::read_versioned`], but fails instead of serving a fallback.
+    pub(crate) fn try_read_versioned(&self) -> Result<(u64, ReadGuard<'_, T>), NotInitialized> {
+        let (snapshot, hold) = self.hold_published();
+        let snapshot = snapshot.ok_or(NotInitialized)?;
+        Ok((
+            snapshot.meta().version,
+            ReadGuard::new(snapshot.slot(), hold),
+        ))
+    }
+
+    /// Returns the version of the published value: 0 for the value an instance was created
+    /// with, incremented by every publish.
+    ///
+    /// The version changes once the new value is visible to readers, and never decreases. An
+    /// instance without a published value reports 0.
+    pub fn version(&self) -> u64 {
+        let _pin = self.pin();
+        unsafe { self.ptr_r.load(self.ordering.load()).as_ref() }
+            .map_or(0, |snapshot| snapshot.meta().version)
+    }
+
+    /// Returns a [`Subscription`] that is notified every time a new value is published.
+    ///
+    /// The subscription starts out having seen the current value, so it reports the next
+    /// publish as the first change. It works on uninitialized instances too, where
+    /// [`Movetex::initialize`] counts as a publish. Publishes made before the subscription
+    /// looks again are observed as one change, see [`Delivery::Coalesce`].
+    pub fn subscribe(&self) -> Subscription<'_, T> {
+        Subscription::new(self, Delivery::Coalesce)
+    }
+
+    /// Same as [`Movetex::subscribe`], but with the given policy for publishes made faster
+    /// than the subscription looks at them.
+    ///
+    /// ```rust
+    /// use movetex::{Delivery, Movetex};
+    ///
+    /// let movetex = Movetex::new(0);
+    /// let mut queued = movetex.subscribe_with(Delivery::DropOldest(2));
+    /// for i in 1..=3 {
+    ///     movetex.write(|v| *v = i);
+    /// }
+    /// assert_eq!((queued.lag(), queued.dropped()), (3, 1));
+    /// assert_eq!(*queued.borrow_and_update(), 2);
+    /// assert_eq!(*queued.borrow_and_update(), 3);
+    /// assert_eq!(queued.lag(), 0);
+    /// ```
+    pub fn subscribe_with(&self, delivery: Delivery) -> Subscription<'_, T> {
+        Subscription::new(self, delivery)
+    }
+
+    /// Returns a [`DiffSubscription`] that hands out the [`Diff`] from the value it saw last
+    /// to each newly published one.
+    ///
+    /// The subscription starts out having seen the current value. Fails on an instance that
+    /// has not been initialized yet, which has no value to take diffs from.
+    pub fn subscribe_diffs(&self) -> Result<DiffSubscription<'_, T>, NotInitialized>
+    where
+        T: Diff,
+    {
+        DiffSubscription::new(self)
+    }
+
+    /// Same as [`Movetex::try_write`], but first applies `policy` to subscriptions that have
+    /// not seen the published value yet.
+    ///
+    /// A plain write publishes regardless of subscribers, so a slow [`Subscription`] observes
+    /// several publishes as a single change and never sees the values in between. When every
+    /// value must reach every subscriber, as in replication, the writer can instead wait for
+    /// lagging subscriptions to mark the published value as seen with
+    /// [`Subscription::borrow_and_update`] (or one of the `wait` methods), or fail with
+    /// [`WriteError::Lagging`]. The writer copy is owned while waiting, so other writes fail
+    /// with [`WriteError::Busy`] until the subscribers have caught up; subscriptions that are
+    /// dropped no longer count. A subscription that needs every value regardless of how the
+    /// writer publishes can ask for it with [`Movetex::subscribe_with`] instead.
+    ///
+    /// ```rust
+    /// use movetex::{Backpressure, Movetex, WriteError};
+    ///
+    /// let movetex = Movetex::new(0);
+    /// let mut replica = movetex.subscribe();
+    ///
+    /// movetex.publish_with_backpressure(|v| *v = 1, Backpressure::Fail).unwrap();
+    /// assert_eq!(
+    ///     movetex.publish_with_backpressure(|v| *v = 2, Backpressure::Fail),
+    ///     Err(WriteError::Lagging)
+    /// );
+    ///
+    /// assert_eq!(*replica.borrow_and_update(), 1);
+    /// movetex.publish_with_backpressure(|v| *v = 2, Backpressure::Fail).unwrap();
+    /// ```
+    pub fn publish_with_backpressure(
+        &self,
+        f: impl FnOnce(&mut T),
+        policy: Backpressure,
+    ) -> Result<(), WriteError> {
+        let acquired = self
+            .state
+            .acquire_resting(State::Writing)
+            .map_err(|state| self.refuse_write(state))?;
+        let version = self.version();
+        let caught_up = match policy {
+            Backpressure::Wait => self.watch.wait_caught_up(version, None),
+            Backpressure::WaitTimeout(timeout) => self.watch.wait_caught_up(
+                version,
+                Some(Deadline::after(self.activity.clock(), timeout)),
+            ),
+            Backpressure::Skip => true,
+            Backpressure::Fail => self.watch.lagging(version) == 0,
+        };
+        if !caught_up {
+            self.state.restore(acquired);
+            return Err(WriteError::Lagging);
+        }
+        self.write_acquired(acquired, f, |_| Ok(()), None)
+    }
+
+    /// Same as [`Movetex::read`], but returns `fallback` whenever no published value is
+    /// available, so request paths never have to branch on the state of the instance.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::<u32>::uninit();
+    /// assert_eq!(*movetex.read_or(&0), 0);
+    ///
+    /// movetex.initialize(7).unwrap();
+    /// assert_eq!(*movetex.read_or(&0), 7);
+    /// ```
+    pub fn read_or<'a>(&'a self, fallback: &'a T) -> ReadGuard<'a, T> {
+        let (snapshot, hold) = self.hold_published();
+        ReadGuard::new(snapshot.map_or(fallback, |snapshot| snapshot.slot()), hold)
+    }
+
+    /// Same as [`Movetex::read`], but returns [`NotInitialized`] instead of panicking for an
+    /// instance that has not been initialized yet.
+    pub fn try_read(&self) -> Result<ReadGuard<'_, T>, NotInitialized> {
+        let (snapshot, hold) = self.hold_published();
+        let snapshot = snapshot.ok_or(NotInitialized)?;
+        Ok(ReadGuard::new(snapshot.slot(), hold))
+    }
+
+    /// Reads the value, blocking for up to `timeout` until an uninitialized instance receives
+    /// its first value.
+    pub fn read_timeout(&self, timeout: Duration) -> Result<ReadGuard<'_, T>, NotInitialized> {
+        if let Ok(value) = self.try_read() {
+            return Ok(value);
+        }
+        self.latch
+            .wait_until(Deadline::after(self.activity.clock(), timeout));
+        self.try_read()
+    }
+
+    /// Returns `true` if the published value is currently stored behind an `Arc`.
+    ///
+    /// Spilling is controlled by the [`SpillPolicy`] set through [`Movetex::builder`].
+    pub fn is_spilled(&self) -> bool {
+        let _pin = self.pin();
+        self.published().is_some_and(Slot::is_spilled)
     }
 
     /// The `write` method attempts an exclusive update to the stored value.
     ///
-    /// During a write operation, the writer pointer (`ptr_w`) is temporarily set to `null_mut`
-    /// to block concurrent writes, allowing only one writer at a time. If `ptr_w` is already `null_mut`,
-    /// another write is in progress, and the function returns `false` to indicate the write cannot proceed.
+    /// During a write operation, the writer copy (`ptr_w`) is owned exclusively through a
+    /// generation-counted state word, allowing only one writer at a time. If another write already
+    /// owns it, the function returns `false` to indicate the write cannot proceed. A concurrent
+    /// `swap` only holds the writer copy for a pointer exchange, so `write` waits for it instead of
+    /// failing.
     ///
     /// Readers are not blocked during the write operation; they can continue to read the current value.
     ///
@@ -128,53 +1129,1074 @@ impl<T: Clone> Movetex<T> {
     /// the reader pointer (`ptr_r`) is atomically swapped to point to the new data, so that readers can
     /// immediately access the updated content without delays.
     ///
-    /// Returns `true` if the write succeeds, or `false` if another write is in progress.
+    /// Once no guard can see the snapshot a publish replaced, which is right away unless a
+    /// reader holds it, the value it held is refreshed with `clone_from` to become the next
+    /// writer copy. `String`, `Vec` and `HashMap` values thus keep their buffers instead of
+    /// reallocating on every write, and the instance holds two copies of the value between
+    /// writes. A snapshot still held during the publish is kept for the next one instead. This
+    /// is skipped when a retire hook, interning, or clone-ahead is configured, for spilled
+    /// values, and in `single-copy` builds.
+    ///
+    /// If the value has been spilled behind an `Arc` (see [`SpillPolicy`]), the closure receives the
+    /// writer copy through `Arc::make_mut` and the readers share the result instead of receiving a
+    /// deep clone.
+    ///
+    /// Returns `true` if the write succeeds, or `false` if another write is in progress, the
+    /// configured [`Validator`] rejected the new value, or the instance is not initialized. Use
+    /// [`Movetex::try_write`] to tell these cases apart.
     pub fn write(&self, f: impl FnOnce(&mut T)) -> bool {
-        if !self.ptr_w.load(Ordering::Acquire).is_null() {
-            let ptr = self.ptr_w.swap(ptr::null_mut(), Ordering::Release);
+        self.try_write(f).is_ok()
+    }
+
+    /// Same as [`Movetex::write`], but reports why the value was not published.
+    ///
+    /// Returns [`WriteError::Busy`] if another write is in progress, or [`WriteError::Rejected`]
+    /// if the configured [`Validator`] refused the modified value. A rejected modification is
+    /// discarded: the writer copy is restored from the published value, so the next write starts
+    /// from what readers currently see.
+    pub fn try_write(&self, f: impl FnOnce(&mut T)) -> Result<(), WriteError> {
+        self.write_inner(f, None)
+    }
+
+    /// Same as [`Movetex::try_write`], but returns the result of the closure once the modified
+    /// value is published.
+    ///
+    /// If the write fails, the closure either did not run or its modification was discarded,
+    /// and so is its result.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let queue = Movetex::new(vec![1, 2, 3]);
+    /// let last = queue.try_write_with(|v| v.pop()).unwrap();
+    /// assert_eq!(last, Some(3));
+    /// assert_eq!(*queue.read(), [1, 2]);
+    /// ```
+    pub fn try_write_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, WriteError> {
+        let mut result = None;
+        self.try_write(|value| result = Some(f(value)))?;
+        Ok(result.expect("a successful write runs the closure"))
+    }
 
-            if ptr.is_null() {
-                return false;
+    /// Replaces the published value with `f` applied to it, recomputing the replacement if
+    /// another write publishes in the meantime.
+    ///
+    /// Unlike [`Movetex::write`], `f` runs on a read guard without owning the writer copy, so
+    /// slow computations do not make concurrent writes fail with [`WriteError::Busy`]; losing
+    /// the race to another writer means calling `f` again on the newer value. The replacement
+    /// is published only if no other publish happened since the value passed to `f` was read,
+    /// which is checked while the writer copy is owned. This mirrors `ArcSwap::rcu`.
+    ///
+    /// Fails like [`Movetex::write_blocking`] if the instance is sealed, poisoned or not
+    /// initialized, or if the validator rejects the replacement.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::thread;
+    ///
+    /// let hits = Movetex::new(0u64);
+    /// thread::scope(|s| {
+    ///     for _ in 0..4 {
+    ///         s.spawn(|| hits.rcu(|count| count + 1).unwrap());
+    ///     }
+    /// });
+    /// assert_eq!(*hits.read(), 4);
+    /// ```
+    pub fn rcu(&self, f: impl Fn(&T) -> T) -> Result<(), WriteError> {
+        loop {
+            let (version, current) = self.read_versioned();
+            let new = f(&current);
+            drop(current);
+
+            let acquired = self
+                .state
+                .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, None)
+                .map_err(|state| self.refuse_write(state))?;
+            if self.version() != version {
+                self.state.restore(acquired);
+                continue;
             }
+            return self.write_acquired(acquired, |value| *value = new, |_| Ok(()), None);
+        }
+    }
+
+    /// Publishes `new` only if the published value equals `expected`, and returns the value it
+    /// replaced.
+    ///
+    /// The comparison and the publish happen while the writer copy is owned, so no other write
+    /// can publish in between; a write in progress is waited for like in
+    /// [`Movetex::write_blocking`]. This allows optimistic updates: read a value, compute a
+    /// replacement without holding anything, and retry if the value changed in the meantime.
+    ///
+    /// Returns the value back inside a [`CompareAndSwapError`] if it was not published:
+    /// [`Mismatch`](CompareAndSwapError::Mismatch) if the published value differs from
+    /// `expected`, [`Pending`](CompareAndSwapError::Pending) if a value swapped in with
+    /// [`Movetex::swap`] waits to be published, and [`Write`](CompareAndSwapError::Write) if the
+    /// instance is sealed, poisoned or not initialized, or the write fails its checks: the
+    /// validator rejects `new` or the write exceeds [`MovetexBuilder::max_write_duration`].
+    ///
+    /// ```rust
+    /// use movetex::{CompareAndSwapError, Movetex};
+    ///
+    /// let leader = Movetex::new(String::from("node-a"));
+    ///
+    /// let seen = leader.read().clone();
+    /// assert_eq!(leader.compare_and_swap(&seen, "node-b".into()), Ok(seen));
+    ///
+    /// let stale = String::from("node-a");
+    /// assert_eq!(
+    ///     leader.compare_and_swap(&stale, "node-c".into()),
+    ///     Err(CompareAndSwapError::Mismatch("node-c".into())),
+    /// );
+    /// assert_eq!(*leader.read(), "node-b");
+    /// ```
+    pub fn compare_and_swap(&self, expected: &T, new: T) -> Result<T, CompareAndSwapError<T>>
+    where
+        T: PartialEq,
+    {
+        let acquired =
+            match self
+                .state
+                .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, None)
+            {
+                Ok(acquired) => acquired,
+                Err(state) => {
+                    return Err(CompareAndSwapError::Write(new, self.refuse_write(state)))
+                }
+            };
+        if acquired.previous() == State::Dirty {
+            self.stats.write_failed();
+            self.state.restore(acquired);
+            return Err(CompareAndSwapError::Pending(new));
+        }
+        let matches = self
+            .published_unverified()
+            .is_some_and(|current| **current == *expected);
+        if !matches {
+            self.stats.write_failed();
+            self.state.restore(acquired);
+            return Err(CompareAndSwapError::Mismatch(new));
+        }
+        let mut pending = match self.begin_write_acquired(acquired) {
+            Ok(pending) => pending,
+            Err(error) => return Err(CompareAndSwapError::Write(new, error)),
+        };
+        let previous = mem::replace(pending.slot.make_mut(), new);
+        if let Err(error) = self.check_write(&mut pending, |_| Ok(())) {
+            self.stats.write_failed();
+            let new = mem::replace(pending.slot.make_mut(), previous);
+            self.restore_write(pending);
+            return Err(CompareAndSwapError::Write(new, error));
+        }
+        self.publish_write(pending, None, None);
+        Ok(previous)
+    }
+
+    /// Same as [`Movetex::try_write`], but waits for a write in progress instead of failing
+    /// with [`WriteError::Busy`].
+    ///
+    /// The call spins for a bounded number of iterations (`BLOCKING_SPINS`) and then parks the
+    /// thread until the current writer releases the writer copy, so it replaces hand-written
+    /// `while !movetex.write(..) { yield_now() }` loops without burning a core. It still fails
+    /// immediately if the instance is sealed, poisoned or not initialized, and if the validator
+    /// rejects the new value. Do not call it from async tasks, which should not block.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::thread;
+    ///
+    /// let counter = Movetex::new(0);
+    /// thread::scope(|s| {
+    ///     for _ in 0..4 {
+    ///         s.spawn(|| {
+    ///             for _ in 0..100 {
+    ///                 counter.write_blocking(|c| *c += 1).unwrap();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(*counter.read(), 400);
+    /// ```
+    pub fn write_blocking(&self, f: impl FnOnce(&mut T)) -> Result<(), WriteError> {
+        let acquired = self
+            .state
+            .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, None)
+            .map_err(|state| self.refuse_write(state))?;
+        self.write_acquired(acquired, f, |_| Ok(()), None)
+    }
+
+    /// Same as [`Movetex::write_blocking`], but gives up once `timeout` has elapsed without the
+    /// writer copy becoming free.
+    ///
+    /// Returns `true` if `f` was applied and the value published. Like [`Movetex::write`], it
+    /// returns `false` right away if the instance is sealed, poisoned or not initialized, or if
+    /// the validator rejects the new value; `f` is not called unless the write can start.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::time::Duration;
+    ///
+    /// let movetex = Movetex::new(1);
+    /// assert!(movetex.write_timeout(|v| *v += 1, Duration::from_millis(5)));
+    /// assert_eq!(*movetex.read(), 2);
+    /// ```
+    pub fn write_timeout(&self, f: impl FnOnce(&mut T), timeout: Duration) -> bool {
+        let deadline = Deadline::after(self.activity.clock(), timeout);
+        let Ok(acquired) =
+            self.state
+                .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, Some(deadline))
+        else {
+            self.stats.write_failed();
+            return false;
+        };
+        self.write_acquired(acquired, f, |_| Ok(()), None).is_ok()
+    }
+
+    /// Same as [`Movetex::write`], but records `label` as the publisher when writer tracking is
+    /// enabled (see [`MovetexBuilder::track_writers`]).
+    ///
+    /// The label is ignored if tracking is disabled.
+    pub fn write_as(&self, label: &str, f: impl FnOnce(&mut T)) -> bool {
+        self.write_inner(f, Some(label)).is_ok()
+    }
+
+    /// Same as [`Movetex::write`], but prepares the new value in caller-provided storage.
+    ///
+    /// `f` mutates `buf`, which is then exchanged with the writer copy and published. On
+    /// return, `buf` holds the previous writer copy, which equals the value readers saw before
+    /// this write. Frameworks that manage their own buffers can keep passing the same scratch
+    /// value, so allocations owned by the payload, such as the capacity of a `Vec`, are
+    /// recycled between writes instead of being allocated in the closure.
+    ///
+    /// If the write cannot start, `f` is not called and `buf` is left untouched. If the
+    /// [`Validator`] rejects the new value, it is discarded and `buf` still receives the
+    /// previous writer copy.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(vec![1, 2]);
+    /// let mut scratch = Vec::with_capacity(64);
+    ///
+    /// assert!(movetex.write_into(&mut scratch, |buf| buf.extend([3, 4, 5])));
+    /// assert_eq!(*movetex.read(), vec![3, 4, 5]);
+    /// assert_eq!(scratch, vec![1, 2]);
+    /// ```
+    pub fn write_into(&self, buf: &mut T, f: impl FnOnce(&mut T)) -> bool {
+        self.write_inner(
+            |value| {
+                f(buf);
+                mem::swap(value, buf);
+            },
+            None,
+        )
+        .is_ok()
+    }
+
+    /// Returns who performed the most recent publish, if writer tracking is enabled and a write
+    /// or [`Movetex::initialize`] has happened since construction.
+    pub fn last_writer(&self) -> Option<WriterIdentity> {
+        self.last_writer.as_ref()?.lock().clone()
+    }
+
+    fn write_inner(&self, f: impl FnOnce(&mut T), label: Option<&str>) -> Result<(), WriteError> {
+        self.write_checked(f, |_| Ok(()), label)
+    }
+
+    /// Same as `write_inner`, but `check` can still refuse the modified value after
+    /// it passed the validator; a refused value is discarded like a rejected one.
+    pub(crate) fn write_checked(
+        &self,
+        f: impl FnOnce(&mut T),
+        check: impl FnOnce(&T) -> Result<(), WriteError>,
+        label: Option<&str>,
+    ) -> Result<(), WriteError> {
+        let acquired = self
+            .state
+            .acquire_resting(State::Writing)
+            .map_err(|state| self.refuse_write(state))?;
+        self.write_acquired(acquired, f, check, label)
+    }
+
+    /// Runs a write that already owns the writer copy through `acquired`.
+    ///
+    /// A panic in `f`, the validator, `check`, or a publish hook poisons the instance.
+    fn write_acquired(
+        &self,
+        acquired: Acquired,
+        f: impl FnOnce(&mut T),
+        check: impl FnOnce(&T) -> Result<(), WriteError>,
+        label: Option<&str>,
+    ) -> Result<(), WriteError> {
+        let mut pending = self.begin_write_acquired(acquired)?;
+        #[cfg(feature = "tracing")]
+        let _entered = pending.span.clone().entered();
+
+        // Apply the modification to the writer copy.
+        f(pending.slot.make_mut());
+
+        self.finish_write(pending, check, label)
+    }
+
+    /// Takes the writer copy for a write that owns it through `acquired`.
+    ///
+    /// Dropping the returned write while unwinding poisons the instance; otherwise it must be
+    /// passed to `finish_write` or `discard_write`.
+    fn begin_write_acquired(&self, acquired: Acquired) -> Result<PendingWrite<'_, T>, WriteError> {
+        let acquired = self.state.poison_on_unwind(acquired);
+        #[cfg(feature = "hdrhistogram")]
+        let started = Instant::now();
+        let Some(mut slot) = self.take_writer_copy() else {
+            self.stats.write_failed();
+            acquired.release(State::Uninitialized);
+            return Err(WriteError::NotInitialized);
+        };
+        self.repair_writer_copy(&mut slot);
+        self.activity.write_started();
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            target: "movetex",
+            "write",
+            type_name = std::any::type_name::<T>(),
+            version = self.publish_count(),
+        );
+        #[cfg(feature = "tracing")]
+        tracing::trace!(target: "movetex", parent: &span, "write acquired");
+        Ok(PendingWrite {
+            acquired,
+            slot,
+            #[cfg(feature = "hdrhistogram")]
+            started,
+            #[cfg(feature = "hdrhistogram")]
+            publish_started: started,
+            applied: self
+                .max_write_duration
+                .map(|limit| (self.activity.clock().now(), limit)),
+            #[cfg(feature = "tracing")]
+            span,
+        })
+    }
+
+    /// Validates and publishes the modified writer copy of `pending`.
+    fn finish_write(
+        &self,
+        mut pending: PendingWrite<'_, T>,
+        check: impl FnOnce(&T) -> Result<(), WriteError>,
+        label: Option<&str>,
+    ) -> Result<(), WriteError> {
+        if let Err(e) = self.check_write(&mut pending, check) {
+            self.stats.write_failed();
+            #[cfg(feature = "tracing")]
+            tracing::debug!(target: "movetex", parent: &pending.span, error = %e, "write discarded");
+            self.discard_write(pending);
+            return Err(e);
+        }
+        self.publish_write(pending, None, label);
+        Ok(())
+    }
+
+    /// Runs the checks a modified writer copy must pass before it is published: the maximum
+    /// write duration, the validator, and `check`.
+    fn check_write(
+        &self,
+        pending: &mut PendingWrite<'_, T>,
+        check: impl FnOnce(&T) -> Result<(), WriteError>,
+    ) -> Result<(), WriteError> {
+        #[cfg(feature = "hdrhistogram")]
+        {
+            pending.publish_started = Instant::now();
+        }
+        let timed_out = pending.applied.is_some_and(|(started, limit)| {
+            self.activity.clock().now().duration_since(started) > limit
+        });
+        let verdict = match &self.validator {
+            _ if timed_out => Err(WriteError::TimedOut),
+            Some(validator) => validator
+                .validate(&pending.slot)
+                .map_err(WriteError::Rejected),
+            None => Ok(()),
+        };
+        verdict.and_then(|()| check(&pending.slot))
+    }
+
+    /// Publishes the writer copy of `pending`, which passed `check_write`.
+    ///
+    /// `version` is the result of a `begin_publish` the caller already made, or `None` to
+    /// make it here, as late as possible.
+    fn publish_write(
+        &self,
+        pending: PendingWrite<'_, T>,
+        version: Option<u64>,
+        label: Option<&str>,
+    ) {
+        self.watch.wait_blocking(self.version());
+        let PendingWrite { acquired, slot, .. } = pending;
+        let (slot, writer_box) = snapshot::unbox(slot);
+        let recycle =
+            self.recycles_snapshots() && !slot.is_spilled() && !self.spill.should_spill(&slot);
+        let allocation = if recycle {
+            self.spare_allocation.lock().take()
+        } else {
+            None
+        };
+        let (reader, writer) = if self.keeps_writer_copy() && !recycle {
+            let (reader, writer) = slot.publish(&self.spill);
+            (reader, Some(writer))
+        } else {
+            (slot.apply(&self.spill), None)
+        };
+        let reader = intern::intern(&self.intern, reader);
+        let version = version.unwrap_or_else(|| self.begin_publish());
+        let snapshot = Snapshot::new(reader, Meta::new(version, label), self.checksum);
+        let snapshot = match allocation {
+            Some(allocation) => Box::write(allocation, snapshot),
+            None => Box::new(snapshot),
+        };
+
+        let new_ptr_r = Box::into_raw(snapshot);
+        let published = unsafe { &*new_ptr_r }.slot();
+        let writer = match &self.clone_ahead {
+            // Without a worker thread, the clone is made here as without clone-ahead.
+            Some(clone_ahead) if !clone_ahead.start(published) => Some(published.clone()),
+            _ => writer,
+        };
+
+        let old_ptr_r = self.ptr_r.swap(new_ptr_r, self.ordering.swap());
+        self.deliveries.deliver(unsafe { &*new_ptr_r });
+        if !old_ptr_r.is_null() {
+            self.defer_retire(unsafe { Box::from_raw(old_ptr_r) });
+        }
 
-            let mut value = unsafe { *Box::from_raw(ptr) };
+        self.activity.write_finished();
 
-            // Применение функции к значению
-            f(&mut value);
+        // The snapshot just replaced is usually reclaimed by now, and its value becomes the
+        // next writer copy.
+        let writer = if recycle {
+            Some(self.recycled_writer_copy(published))
+        } else {
+            writer
+        };
 
-            // Обновляем ptr_r, проверяя значение перед `Box::from_raw`
-            let new_ptr_r = Box::into_raw(Box::new(value.clone()));
+        // The next writer copy takes the allocation of the one just published.
+        if let Some(writer) = writer {
+            self.ptr_w.store(
+                Box::into_raw(Box::write(writer_box, writer)),
+                self.ordering.writer_release(),
+            );
+        }
+        acquired.release(State::Idle);
 
-            drop(unsafe { Box::from_raw(self.ptr_r.swap(new_ptr_r, Ordering::Release)) });
+        #[cfg(feature = "hdrhistogram")]
+        if let Some(latency) = &self.latency {
+            latency.record_write(pending.started, pending.publish_started);
+        }
+        self.on_publish(label);
+    }
 
-            // Восстанавливаем ptr_w
+    /// Drops the modification of `pending` and restores the writer copy from the published
+    /// value.
+    fn discard_write(&self, pending: PendingWrite<'_, T>) {
+        self.activity.write_finished();
+        let PendingWrite { acquired, slot, .. } = pending;
+        drop(slot);
+        let Some(published) = self.load_published() else {
+            invariant::report(InvariantViolation::MissingPublishedValue);
+            acquired.release(State::Uninitialized);
+            return;
+        };
+        if Self::STANDING_WRITER_COPY {
+            self.ptr_w.store(
+                Box::into_raw(Box::new(published.clone())),
+                self.ordering.writer_release(),
+            );
+        }
+        acquired.release(State::Idle);
+    }
+
+    /// Puts back the writer copy of `pending`, which must be unmodified, and returns to the
+    /// state it was acquired from, so a value swapped in stays pending.
+    fn restore_write(&self, pending: PendingWrite<'_, T>) {
+        self.activity.write_finished();
+        let PendingWrite { acquired, slot, .. } = pending;
+        // Without a standing writer copy, the one taken from the published value is dropped.
+        if Self::STANDING_WRITER_COPY || acquired.previous() == State::Dirty {
             self.ptr_w
-                .store(Box::into_raw(Box::new(value)), Ordering::Release);
+                .store(Box::into_raw(slot), self.ordering.writer_release());
+        }
+        acquired.restore();
+    }
 
-            return true;
+    /// Moves the writer copy out of `ptr_w`; the caller must own it through `state`.
+    ///
+    /// A missing writer copy is an invariant violation and is rebuilt from the published value.
+    /// Returns `None` if that is missing as well.
+    fn take_writer_copy(&self) -> Option<Box<Slot<T>>> {
+        self.settle_writer_copy();
+        let ptr = self
+            .ptr_w
+            .swap(ptr::null_mut(), self.ordering.writer_acquire());
+        if !ptr.is_null() {
+            return Some(unsafe { Box::from_raw(ptr) });
+        }
+        // Without a standing writer copy, there only is one between a `swap` and the next
+        // publish.
+        if Self::STANDING_WRITER_COPY {
+            invariant::report(InvariantViolation::MissingWriterCopy);
+        }
+        let published = self.published_unverified().cloned();
+        if published.is_none() {
+            invariant::report(InvariantViolation::MissingPublishedValue);
+        }
+        published.map(Box::new)
+    }
+
+    /// Returns the next writer copy after a publish of `published`, refreshing the value of a
+    /// reclaimed snapshot with `clone_from` so it keeps its buffers; see `retire`. The emptied
+    /// allocation of the snapshot is kept for the next publish.
+    fn recycled_writer_copy(&self, published: &Slot<T>) -> Slot<T> {
+        let Some(spare) = self.spare.lock().take() else {
+            return published.clone();
+        };
+        let (spare, allocation) = snapshot::unbox(spare);
+        *self.spare_allocation.lock() = Some(allocation);
+        let mut slot = spare.into_slot();
+        slot.make_mut().clone_from(published);
+        slot
+    }
+
+    /// Returns `true` if publishes of inline values turn a reclaimed snapshot into the next
+    /// writer copy.
+    ///
+    /// Only publishes that leave a writer copy behind can use one, and interned values are
+    /// shared with other instances.
+    fn recycles_snapshots(&self) -> bool {
+        self.keeps_writer_copy() && self.intern.is_none()
+    }
+
+    /// Returns `true` if a publish leaves a clone of the new value behind as the writer copy.
+    ///
+    /// With clone-ahead the clone is prepared in the background instead, and without a
+    /// standing writer copy it is made at the start of the next write.
+    fn keeps_writer_copy(&self) -> bool {
+        self.clone_ahead.is_none() && Self::STANDING_WRITER_COPY
+    }
+
+    /// Disposes of a snapshot replaced by a publish, through the retire hook if configured.
+    ///
+    /// Without a hook, one inline snapshot is kept as a spare for the next writer copy to
+    /// reuse the buffers of its value, if the instance recycles snapshots; the others are
+    /// dropped, in the background if enabled.
+    fn retire(&self, snapshot: Box<Snapshot<T>>) {
+        if let Some(on_retire) = self.on_retire {
+            if let Some(value) = snapshot.into_slot().into_unshared() {
+                on_retire(value);
+            }
+            return;
+        }
+        if self.recycles_snapshots() && !snapshot.slot().is_spilled() {
+            let mut spare = self.spare.lock();
+            if spare.is_none() {
+                *spare = Some(snapshot);
+                return;
+            }
+        }
+        if let Some(drop_later) = self.drop_later {
+            drop_later(snapshot);
         }
-        false
+    }
+
+    /// Pins the epoch, keeping every snapshot loaded while the pin is held alive, see `epoch`.
+    fn pin(&self) -> ReaderPin<'_> {
+        ReaderPin::new(&self.epochs)
+    }
+
+    /// Queues a snapshot unlinked from `ptr_r` until no guard can see it anymore, and reclaims
+    /// the queued snapshots that no guard can see anymore.
+    ///
+    /// Only publishes reclaim, so readers never take the locks or run the destructors and
+    /// retire hook involved; a snapshot is reclaimed by the first publish after its last guard
+    /// is dropped.
+    fn defer_retire(&self, snapshot: Box<Snapshot<T>>) {
+        let mut retired = self.retired.lock();
+        retired.push((self.epochs.current(), snapshot));
+        let epoch = self.epochs.advance();
+        drop(retired);
+        // One at a time, so the retire hook runs without the lock and nothing is allocated.
+        loop {
+            let mut retired = self.retired.lock();
+            let Some(index) = retired.iter().position(|(unlinked, snapshot)| {
+                epoch::expired(*unlinked, epoch) && !snapshot.holds().is_held()
+            }) else {
+                break;
+            };
+            let (_, snapshot) = retired.remove(index);
+            drop(retired);
+            self.retire(snapshot);
+        }
+    }
+
+    /// Stores the writer copy prepared by clone-ahead, waiting for it if necessary, and resumes
+    /// the panic of a clone that failed.
+    ///
+    /// Every path that takes the writer copy calls this first; the caller must own it through
+    /// `state` in a way that poisons it on unwind, or have exclusive access.
+    fn settle_writer_copy(&self) {
+        if let Err(panic) = self.collect_writer_copy() {
+            panic::resume_unwind(panic);
+        }
+    }
+
+    /// Same as `settle_writer_copy`, but returns the panic of a clone that failed.
+    fn collect_writer_copy(&self) -> thread::Result<()> {
+        if let Some(slot) = self.clone_ahead.as_ref().and_then(CloneAhead::finish) {
+            self.ptr_w.store(
+                Box::into_raw(Box::new(slot?)),
+                self.ordering.writer_release(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Bookkeeping shared by every path that publishes a new value to readers.
+    fn on_publish(&self, label: Option<&str>) {
+        self.stats.write();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "movetex",
+            type_name = std::any::type_name::<T>(),
+            version = self.publish_count(),
+            writer = label,
+            "published",
+        );
+        self.activity.published();
+        if let Some(last_writer) = &self.last_writer {
+            *last_writer.lock() = Some(WriterIdentity::current(label));
+        }
+        self.latch.release();
+        self.watch.notify();
+    }
+
+    /// Maps the state that kept a write from starting to its error, counting the failed write.
+    fn refuse_write(&self, state: State) -> WriteError {
+        self.stats.write_failed();
+        let error = state.write_error();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "movetex",
+            type_name = std::any::type_name::<T>(),
+            version = self.publish_count(),
+            %error,
+            "write refused",
+        );
+        error
+    }
+
+    /// The number of publishes started since construction; used to detect concurrent publishes.
+    ///
+    /// The counter is bumped before a new snapshot is stored, so a reader that loaded a
+    /// snapshot and then finds the counter unchanged knows no publish has started since.
+    pub(crate) fn publish_count(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Bumps the publish counter ahead of storing a new snapshot; returns the new version.
+    fn begin_publish(&self) -> u64 {
+        self.version.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Returns `true` once the instance holds a published value.
+    ///
+    /// Instances created with [`Movetex::new`] are published from the start; instances created
+    /// with [`Movetex::empty`] become published with their first successful write.
+    pub fn is_published(&self) -> bool {
+        self.latch.is_released()
+    }
+
+    /// Returns a future that resolves once the instance holds a published value.
+    ///
+    /// This lets services gate readiness on the initial configuration load without polling.
+    /// The future resolves immediately if the value is already published.
+    ///
+    /// ### Usage Example:
+    /// ```rust,ignore
+    /// let config = Arc::new(Movetex::<Option<Config>>::empty());
+    ///
+    /// tokio::spawn(load_config(Arc::clone(&config)));
+    ///
+    /// config.first_publish().await;
+    /// let config = config.read().as_ref().unwrap();
+    /// ```
+    pub fn first_publish(&self) -> FirstPublish<'_> {
+        FirstPublish { latch: &self.latch }
+    }
+
+    /// Blocks the current thread until the instance holds a published value or `timeout`
+    /// elapses.
+    ///
+    /// Returns `true` if the value is published, `false` if the timeout elapsed first.
+    pub fn wait_first_publish(&self, timeout: Duration) -> bool {
+        self.latch
+            .wait_until(Deadline::after(self.activity.clock(), timeout))
     }
 
     /// The `swap` method atomically replaces the value stored in `ptr_w` without cloning.
     /// This operation allows for multiple threads to simultaneously replace the value without any copying overhead, as long as there is no ongoing `write` operation.
+    /// Concurrent swaps are serialized through the same state word as `write`, so a swap can
+    /// never slip into the writer copy while a `write` owns it.
     ///
     /// ---
     /// ⚠️ It's important to note that `swap` only updates the writer (`ptr_w`) and does not affect the reader (`ptr_r`).
     /// This means that while the writer's value can be swapped atomically, the reader will continue to see the old value
-    /// until a `write` operation occurs, which is the only operation that can update both the writer and reader values.
+    /// until it is published by [`Movetex::publish`] or the next `write`. Use [`Movetex::swap_and_publish`] to do both at once.
     ///
-    /// If a `write` operation is in progress, the `swap` will return `None`, signaling that the operation could not be performed at that time.
+    /// If a `write` operation is in progress, the `swap` fails and gives the passed value back
+    /// as `Err(value)`, leaving the writer copy untouched, so the caller can retry with the same
+    /// value. An instance created with [`Movetex::uninit`] also fails until it is initialized,
+    /// and a sealed or poisoned instance (see [`Movetex::seal`]) always does.
+    /// A successful swap marks the instance as dirty until the next publish (see [`Movetex::is_dirty`]).
     ///
-    /// Returns `Some(old_value)` if the swap was successful, or `None` if a `write` operation was in progress.
-    pub fn swap(&self, value: T) -> Option<T> {
+    /// Returns `Ok(old_value)` if the swap was successful, or `Err(value)` if it could not be performed.
+    pub fn swap(&self, value: T) -> Result<T, T> {
+        let acquired = match self.state.acquire_resting(State::Swapping) {
+            Ok(acquired) => acquired,
+            Err(state) => {
+                self.refuse_write(state);
+                return Err(value);
+            }
+        };
+        // Collecting a clone-ahead copy or cloning the published value can panic.
+        let acquired = self.state.poison_on_unwind(acquired);
+        self.settle_writer_copy();
         let ptr = self
             .ptr_w
-            .swap(Box::into_raw(Box::new(value)), Ordering::Release);
-        if ptr.is_null() {
-            return None;
+            .swap(ptr::null_mut(), self.ordering.writer_swap());
+        let previous = if !ptr.is_null() {
+            Some(unsafe { Box::from_raw(ptr) }.into_inner())
+        } else if !Self::STANDING_WRITER_COPY {
+            // Without a standing writer copy, the published value stands in for it.
+            self.published_unverified().map(|slot| T::clone(slot))
+        } else {
+            None
+        };
+        let Some(previous) = previous else {
+            invariant::report(InvariantViolation::MissingWriterCopy);
+            acquired.restore();
+            return Err(value);
+        };
+        self.ptr_w.store(
+            Box::into_raw(Box::new(Slot::Inline(value))),
+            self.ordering.writer_release(),
+        );
+        acquired.release(State::Dirty);
+        self.stats.swap();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "movetex",
+            type_name = std::any::type_name::<T>(),
+            version = self.publish_count(),
+            "swapped",
+        );
+        Ok(previous)
+    }
+
+    /// Publishes the value a [`Movetex::swap`] left in the writer copy, so readers see it
+    /// without a no-op `write`.
+    ///
+    /// Returns `true` if the swapped-in value was published, and `false` if there was none
+    /// (see [`Movetex::is_dirty`]), another write is in progress, the instance is sealed,
+    /// poisoned or not initialized, or the validator rejected the value. A rejected value is
+    /// discarded like the modification of a rejected write.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(1);
+    /// movetex.swap(2).unwrap();
+    /// assert_eq!(*movetex.read(), 1);
+    ///
+    /// assert!(movetex.publish());
+    /// assert_eq!(*movetex.read(), 2);
+    /// assert!(!movetex.publish());
+    /// ```
+    pub fn publish(&self) -> bool {
+        let Ok(acquired) = self.state.acquire_resting(State::Writing) else {
+            self.stats.write_failed();
+            return false;
+        };
+        if acquired.previous() != State::Dirty {
+            self.state.restore(acquired);
+            return false;
         }
-        Some(unsafe { *Box::from_raw(ptr) })
+        self.write_acquired(acquired, |_| {}, |_| Ok(()), None)
+            .is_ok()
+    }
+
+    /// Same as [`Movetex::swap`] followed by [`Movetex::publish`], but in a single write, so no
+    /// other write can publish in between.
+    ///
+    /// Returns the replaced writer copy, or `None` if the value could not be published for the
+    /// reasons listed under [`Movetex::publish`]; `value` is dropped in that case.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(String::from("old"));
+    /// assert_eq!(movetex.swap_and_publish("new".into()).as_deref(), Some("old"));
+    /// assert_eq!(*movetex.read(), "new");
+    /// ```
+    pub fn swap_and_publish(&self, value: T) -> Option<T> {
+        let acquired = self
+            .state
+            .acquire_resting(State::Writing)
+            .map_err(|state| self.refuse_write(state))
+            .ok()?;
+        let mut previous = None;
+        self.write_acquired(
+            acquired,
+            |current| previous = Some(mem::replace(current, value)),
+            |_| Ok(()),
+            None,
+        )
+        .ok()?;
+        previous
+    }
+
+    /// Returns percentile data of the read and write latencies recorded since construction, or
+    /// `None` unless enabled with [`MovetexBuilder::record_latencies`].
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::builder(0u64).record_latencies().build();
+    /// movetex.write(|v| *v += 1);
+    /// movetex.read();
+    ///
+    /// let report = movetex.latency_report().unwrap();
+    /// println!("p99 publish: {}ns", report.publish.value_at_quantile(0.99));
+    /// assert_eq!(report.write.len(), 1);
+    /// ```
+    #[cfg(feature = "hdrhistogram")]
+    pub fn latency_report(&self) -> Option<LatencyReport> {
+        self.latency.as_ref().map(LatencyRecorder::report)
+    }
+
+    /// Returns the operation counts since construction.
+    ///
+    /// The counters are updated with relaxed atomics, so a report taken while other threads
+    /// operate on the instance is not a consistent snapshot across counters.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// let movetex = Movetex::new(0u64);
+    /// movetex.write(|v| *v += 1);
+    /// movetex.read();
+    ///
+    /// let stats = movetex.stats();
+    /// assert_eq!((stats.reads, stats.writes, stats.failed_writes), (1, 1, 0));
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> MovetexStats {
+        self.stats.report()
+    }
+
+    /// Returns `true` if a [`Movetex::swap`] replaced the writer copy since the last publish.
+    ///
+    /// Readers keep seeing the previously published value until the next `write` publishes the
+    /// swapped-in value.
+    pub fn is_dirty(&self) -> bool {
+        self.state.get() == State::Dirty
+    }
+
+    /// Returns `true` if a write panicked while owning the writer copy.
+    ///
+    /// A panic in a write closure, the validator, or a hook running during a publish leaves
+    /// the writer copy in an unknown state, so the instance stops accepting writes and they
+    /// fail with [`WriteError::Poisoned`]. Readers are not affected: they keep the value
+    /// published last, or the one published just before the panic if it happened after the
+    /// publish. Call [`Movetex::clear_poison`] to accept writes again.
+    pub fn is_poisoned(&self) -> bool {
+        self.state.get() == State::Poisoned
+    }
+
+    /// Rebuilds the writer copy of a poisoned instance from the published value and accepts
+    /// writes again.
+    ///
+    /// Returns `false` if the instance was not poisoned.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, WriteError};
+    /// use std::panic::{self, AssertUnwindSafe};
+    ///
+    /// let movetex = Movetex::new(vec![1]);
+    /// let result = panic::catch_unwind(AssertUnwindSafe(|| {
+    ///     movetex.write(|v| {
+    ///         v.push(2);
+    ///         panic!("update failed halfway");
+    ///     })
+    /// }));
+    /// assert!(result.is_err());
+    /// assert!(movetex.is_poisoned());
+    /// assert_eq!(movetex.try_write(|v| v.push(3)), Err(WriteError::Poisoned));
+    /// assert_eq!(*movetex.read(), [1]);
+    ///
+    /// assert!(movetex.clear_poison());
+    /// assert!(movetex.write(|v| v.push(3)));
+    /// assert_eq!(*movetex.read(), [1, 3]);
+    /// ```
+    pub fn clear_poison(&self) -> bool {
+        let Ok(acquired) = self
+            .state
+            .acquire(|state| state == State::Poisoned, State::Writing)
+        else {
+            return false;
+        };
+        let acquired = self.state.poison_on_unwind(acquired);
+        self.settle_writer_copy();
+        let stale = self
+            .ptr_w
+            .swap(ptr::null_mut(), self.ordering.writer_acquire());
+        if !stale.is_null() {
+            drop(unsafe { Box::from_raw(stale) });
+        }
+        self.activity.write_finished();
+        let Some(published) = self.published_unverified() else {
+            acquired.release(State::Uninitialized);
+            return true;
+        };
+        if Self::STANDING_WRITER_COPY {
+            self.ptr_w.store(
+                Box::into_raw(Box::new(published.clone())),
+                self.ordering.writer_release(),
+            );
+        }
+        acquired.release(State::Idle);
+        true
+    }
+
+    /// Permanently stops accepting writes; readers keep the value published last.
+    ///
+    /// After sealing, [`Movetex::try_write`] fails with [`WriteError::Sealed`] and
+    /// [`Movetex::swap`] gives the value back. A value swapped in but not yet published is never
+    /// published.
+    ///
+    /// Returns `false` if a write is in progress or the instance is not initialized, and `true`
+    /// once the instance is sealed, including when it already was.
+    pub fn seal(&self) -> bool {
+        self.state
+            .acquire(
+                |state| state.is_resting() || state == State::Sealed,
+                State::Sealed,
+            )
+            .is_ok()
+    }
+
+    /// Frees the writer copy of a sealed instance, which no operation touches anymore.
+    pub(crate) fn drop_writer_copy(&self) {
+        debug_assert_eq!(self.state.get(), State::Sealed);
+        self.settle_writer_copy();
+        let ptr = self
+            .ptr_w
+            .swap(ptr::null_mut(), self.ordering.writer_swap());
+        if !ptr.is_null() {
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+
+    /// Returns `true` once [`Movetex::seal`] has succeeded.
+    pub fn is_sealed(&self) -> bool {
+        self.state.get() == State::Sealed
+    }
+}
+
+impl<T: Clone> Movetex<Option<T>> {
+    /// Creates an instance holding `None` that does not count as published yet.
+    ///
+    /// Readers see `None` until the first successful write, which can be awaited with
+    /// [`Movetex::first_publish`] or [`Movetex::wait_first_publish`].
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use std::time::Duration;
+    ///
+    /// let config = Movetex::<Option<String>>::empty();
+    /// assert!(!config.wait_first_publish(Duration::from_millis(1)));
+    ///
+    /// config.write(|c| *c = Some("loaded".to_string()));
+    /// assert!(config.wait_first_publish(Duration::from_millis(1)));
+    /// ```
+    pub fn empty() -> Self {
+        let mut builder = Self::builder(None);
+        builder.published = false;
+        builder.build()
+    }
+}
+
+impl<T: Clone> HealthCheck for Movetex<T> {
+    /// Reports the age of the published value, whether a writer is holding the writer copy
+    /// for too long, whether the instance is poisoned, and how many subscriptions lag behind.
+    fn health(&self, policy: &HealthPolicy) -> Health {
+        Health {
+            poisoned: self.is_poisoned(),
+            subscriber_lag: self.watch.lagging(self.version()),
+            ..self.activity.report(policy)
+        }
+    }
+}
+
+impl<T: Clone + Default> Default for Movetex<T> {
+    /// Creates an instance publishing `T::default()`.
+    ///
+    /// A `Movetex<Option<T>>` created this way counts as published; use [`Movetex::empty`] for
+    /// one that becomes published with its first write.
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> Clone for Movetex<T> {
+    /// Creates an independent instance publishing a clone of the published value.
+    ///
+    /// The value is cloned from a single snapshot, so it is consistent even while another
+    /// thread writes; a value swapped in but not yet published is not carried over. The new
+    /// instance starts at version 0 with the default configuration, since validators, hooks and
+    /// other builder options are not cloned. Cloning an uninitialized instance gives an
+    /// uninitialized one.
+    fn clone(&self) -> Self {
+        match self.try_read() {
+            Ok(value) => Self::new(T::clone(&value)),
+            Err(NotInitialized) => Self::uninit(),
+        }
+    }
+}
+
+impl<T: Clone> From<T> for Movetex<T> {
+    /// Creates an instance publishing `value`, same as [`Movetex::new`].
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for Movetex<T> {
+    /// Prints the published value and its version, without waiting for a write in progress.
+    ///
+    /// The snapshot is loaded directly, so printing is not counted as a read and does not
+    /// verify or repair it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let _pin = self.pin();
+        let snapshot = unsafe { self.ptr_r.load(self.ordering.load()).as_ref() };
+        let mut debug = f.debug_struct("Movetex");
+        match snapshot {
+            Some(snapshot) => debug.field("value", &**snapshot.slot()),
+            None => debug.field("value", &format_args!("<uninitialized>")),
+        };
+        let version = snapshot.map_or(0, |snapshot| snapshot.meta().version);
+        debug.field("version", &version).finish()
     }
 }
 
@@ -182,6 +2204,9 @@ impl<T: Clone> Movetex<T> {
 impl<T: Clone> Drop for Movetex<T> {
     /// Ensures that the internal pointers are correctly deallocated.
     fn drop(&mut self) {
+        // A pending clone still reads the published snapshot. The panic of a failed clone is
+        // dropped, since unwinding out of `drop` could abort.
+        let _ = self.collect_writer_copy();
         unsafe {
             let ptr_r = self.ptr_r.load(Ordering::Relaxed);
             if !ptr_r.is_null() {
@@ -0,0 +1,644 @@
+//! Consistent reads and writes across several instances.
+//!
+//! Each instance is read on its own, so reading two instances one after the other can observe
+//! the first before and the second after a pair of related writes. The helpers here read all
+//! instances, then check that none of them has started a publish since its value was read; if
+//! one has, they read again. On success there was a moment at which every returned value was
+//! the published value of its instance. The `_reported` variants also return a
+//! [`ConsistencyReport`] of how many reads that took and how far apart the parts drifted.
+//!
+//! [`Movetex::write_all`] is the writing counterpart: it publishes to several instances only if
+//! the writes to all of them succeed, and starts every publish before storing any of them, so
+//! the helpers above see either all of its values or none. [`write_pair`] and
+//! [`transaction!`](crate::transaction) do the same for instances of different types.
+//! [`begin_write_all!`](crate::begin_write_all) and [`commit_all!`](crate::commit_all) split
+//! such a write in two for async code: the first waits for the writer copies without blocking
+//! the thread, and the second publishes the modified values together.
+
+use std::future;
+use std::ptr;
+use std::task::{Context, Poll};
+
+use crate::state::{Acquired, State, WriterState};
+use crate::{Movetex, PendingWrite, ReadGuard, WriteError, WriteTransaction};
+
+/// Reads `a` and `b` so that both values were published at the same moment.
+///
+/// Retries while either instance publishes between the reads, which is cheap for the common
+/// case of infrequent writes. Use [`read_n!`](crate::read_n) for more than two instances.
+///
+/// # Panics
+///
+/// Panics if either instance has not been initialized.
+///
+/// ```rust
+/// use movetex::{read_pair, Movetex};
+///
+/// let primary = Movetex::new("10.0.0.1");
+/// let port = Movetex::new(8080);
+///
+/// let (primary, port) = read_pair(&primary, &port);
+/// assert_eq!(format!("{primary}:{port}"), "10.0.0.1:8080");
+/// ```
+pub fn read_pair<'a, 'b, A: Clone, B: Clone>(
+    a: &'a Movetex<A>,
+    b: &'b Movetex<B>,
+) -> (ReadGuard<'a, A>, ReadGuard<'b, B>) {
+    read_stable(|| (VersionedRead::new(a), VersionedRead::new(b)))
+}
+
+/// Same as [`read_pair`], but also reports the retries and the version skew observed on the
+/// way to a consistent read.
+///
+/// ```rust
+/// use movetex::{read_pair_reported, ConsistencyReport, Movetex};
+///
+/// let primary = Movetex::new("10.0.0.1");
+/// let port = Movetex::new(8080);
+///
+/// let ((_, port), report) = read_pair_reported(&primary, &port);
+/// assert_eq!(*port, 8080);
+/// assert_eq!(report, ConsistencyReport::default());
+/// ```
+pub fn read_pair_reported<'a, 'b, A: Clone, B: Clone>(
+    a: &'a Movetex<A>,
+    b: &'b Movetex<B>,
+) -> ((ReadGuard<'a, A>, ReadGuard<'b, B>), ConsistencyReport) {
+    read_stable_reported(|| (VersionedRead::new(a), VersionedRead::new(b)))
+}
+
+/// Reads any number of instances so that all values were published at the same moment.
+///
+/// Expands to a tuple with one [`ReadGuard`](crate::ReadGuard) per instance, in argument order; see
+/// [`read_pair`](crate::read_pair) for the retry behaviour and panics.
+///
+/// ```rust
+/// use movetex::{read_n, Movetex};
+///
+/// let host = Movetex::new("db.internal");
+/// let port = Movetex::new(5432);
+/// let pool = Movetex::new(16);
+///
+/// let (host, port, pool) = read_n!(&host, &port, &pool);
+/// assert_eq!((*host, *port, *pool), ("db.internal", 5432, 16));
+/// ```
+#[macro_export]
+macro_rules! read_n {
+    ($($movetex:expr),+ $(,)?) => {
+        $crate::__private::read_stable(|| ($($crate::__private::VersionedRead::new($movetex),)+))
+    };
+}
+
+/// Same as [`read_n!`](crate::read_n), but expands to the tuple of guards and a
+/// [`ConsistencyReport`](crate::ConsistencyReport), like
+/// [`read_pair_reported`](crate::read_pair_reported).
+///
+/// ```rust
+/// use movetex::{read_n_reported, Movetex};
+///
+/// let host = Movetex::new("db.internal");
+/// let port = Movetex::new(5432);
+/// let pool = Movetex::new(16);
+///
+/// let ((host, port, pool), report) = read_n_reported!(&host, &port, &pool);
+/// assert_eq!((*host, *port, *pool), ("db.internal", 5432, 16));
+/// assert_eq!((report.retries, report.max_skew), (0, 0));
+/// ```
+#[macro_export]
+macro_rules! read_n_reported {
+    ($($movetex:expr),+ $(,)?) => {
+        $crate::__private::read_stable_reported(
+            || ($($crate::__private::VersionedRead::new($movetex),)+),
+        )
+    };
+}
+
+/// How a consistent read across several instances was assembled.
+///
+/// A consumer that needs bounded staleness across parts can watch these numbers: a large skew
+/// means the parts drift apart quickly under the current write rate, and many retries mean
+/// consistent reads are getting expensive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// The number of times the parts were read again because one of them published in between.
+    pub retries: u32,
+    /// The largest number of publishes any part was behind its instance when the parts were
+    /// checked, over all attempts; 0 if the first read was already consistent.
+    pub max_skew: u64,
+}
+
+impl ConsistencyReport {
+    /// Records a check that found a part `skew` publishes behind.
+    pub(crate) fn observe(&mut self, skew: u64) {
+        self.max_skew = self.max_skew.max(skew);
+    }
+}
+
+/// A value read from an instance, with the version of the snapshot it came from.
+#[doc(hidden)]
+pub struct VersionedRead<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+    value: ReadGuard<'a, T>,
+    version: u64,
+}
+
+impl<'a, T: Clone> VersionedRead<'a, T> {
+    pub fn new(movetex: &'a Movetex<T>) -> Self {
+        let (value, meta) = movetex.read_meta();
+        Self {
+            movetex,
+            value,
+            version: meta.version,
+        }
+    }
+
+    /// The number of publishes the instance has started since the value was read.
+    fn skew(&self) -> u64 {
+        self.movetex.publish_count().saturating_sub(self.version)
+    }
+}
+
+/// A tuple of [`VersionedRead`]s that can be checked as a whole.
+#[doc(hidden)]
+pub trait StableReads {
+    type Values;
+
+    /// The largest skew of the reads in the tuple.
+    fn skew(&self) -> u64;
+
+    fn into_values(self) -> Self::Values;
+}
+
+macro_rules! stable_reads {
+    ($($name:ident $lifetime:lifetime: $index:tt),+) => {
+        impl<$($lifetime,)+ $($name: Clone),+> StableReads for ($(VersionedRead<$lifetime, $name>,)+) {
+            type Values = ($(ReadGuard<$lifetime, $name>,)+);
+
+            fn skew(&self) -> u64 {
+                0 $(.max(self.$index.skew()))+
+            }
+
+            fn into_values(self) -> Self::Values {
+                ($(self.$index.value,)+)
+            }
+        }
+    };
+}
+
+stable_reads!(A 'a: 0);
+stable_reads!(A 'a: 0, B 'b: 1);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2, D 'd: 3);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2, D 'd: 3, E 'e: 4);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2, D 'd: 3, E 'e: 4, F 'f: 5);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2, D 'd: 3, E 'e: 4, F 'f: 5, G 'g: 6);
+stable_reads!(A 'a: 0, B 'b: 1, C 'c: 2, D 'd: 3, E 'e: 4, F 'f: 5, G 'g: 6, H 'h: 7);
+
+/// Repeats `read` until every value it returns is still current.
+#[doc(hidden)]
+pub fn read_stable<R: StableReads>(read: impl Fn() -> R) -> R::Values {
+    read_stable_reported(read).0
+}
+
+/// Same as [`read_stable`], and reports the attempts it took.
+#[doc(hidden)]
+pub fn read_stable_reported<R: StableReads>(
+    read: impl Fn() -> R,
+) -> (R::Values, ConsistencyReport) {
+    let mut report = ConsistencyReport::default();
+    loop {
+        let reads = read();
+        let skew = reads.skew();
+        report.observe(skew);
+        if skew == 0 {
+            return (reads.into_values(), report);
+        }
+        report.retries += 1;
+        std::hint::spin_loop();
+    }
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Modifies the writer copies of all `instances` with `f` and publishes them together, or
+    /// none of them.
+    ///
+    /// `f` receives the writer copies in the order of `instances`. The write fails without
+    /// calling `f` if any instance is busy, sealed, poisoned or not initialized, including an
+    /// instance that appears twice. After `f`, every modified value is validated first; if any
+    /// is rejected, all modifications are discarded and the error of the first rejected one is
+    /// returned. Readers of a single instance can still see one publish before the others, but
+    /// [`read_pair`] and [`read_n!`](crate::read_n) see all of them or none.
+    ///
+    /// ```rust
+    /// use movetex::{read_pair, Movetex};
+    ///
+    /// let from = Movetex::new(100u64);
+    /// let to = Movetex::new(0u64);
+    ///
+    /// Movetex::write_all(&[&from, &to], |[from, to]| {
+    ///     *from -= 30;
+    ///     *to += 30;
+    /// })
+    /// .unwrap();
+    ///
+    /// let (from, to) = read_pair(&from, &to);
+    /// assert_eq!(*from + *to, 100);
+    /// ```
+    pub fn write_all<const N: usize>(
+        instances: &[&Movetex<T>; N],
+        f: impl FnOnce([&mut T; N]),
+    ) -> Result<(), WriteError> {
+        let mut pending = Vec::with_capacity(N);
+        for movetex in instances {
+            match begin(movetex) {
+                Ok(write) => pending.push(write),
+                Err(e) => {
+                    // Dropping `pending` would poison the earlier instances, so their untouched
+                    // writer copies are put back instead.
+                    for (movetex, write) in instances.iter().zip(pending) {
+                        movetex.restore_write(write);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut slots = pending.iter_mut().map(|write| write.slot.make_mut());
+        f(std::array::from_fn(|_| {
+            slots.next().expect("one writer copy per instance")
+        }));
+
+        let mut verdict = Ok(());
+        for (movetex, write) in instances.iter().zip(pending.iter_mut()) {
+            verdict = verdict.and(movetex.check_write(write, |_| Ok(())));
+        }
+        if let Err(e) = verdict {
+            instances
+                .iter()
+                .for_each(|movetex| movetex.stats.write_failed());
+            discard_all(instances, pending);
+            return Err(e);
+        }
+
+        // All versions are bumped before any snapshot is stored, see `read_stable`.
+        let versions: Vec<u64> = instances
+            .iter()
+            .map(|movetex| movetex.begin_publish())
+            .collect();
+        for ((movetex, write), version) in instances.iter().zip(pending).zip(versions) {
+            movetex.publish_write(write, Some(version), None);
+        }
+        Ok(())
+    }
+}
+
+/// Modifies the writer copies of `a` and `b` with `f` and publishes them together, or neither.
+///
+/// Same as [`Movetex::write_all`], for two instances of different types, such as an index and
+/// the data it points into. Use [`transaction!`](crate::transaction) for more than two
+/// instances.
+///
+/// ```rust
+/// use movetex::{read_pair, write_pair, Movetex};
+///
+/// let names = Movetex::new(vec![String::from("alice")]);
+/// let count = Movetex::new(1usize);
+///
+/// write_pair(&names, &count, |names, count| {
+///     names.push(String::from("bob"));
+///     *count += 1;
+/// })
+/// .unwrap();
+///
+/// let (names, count) = read_pair(&names, &count);
+/// assert_eq!(names.len(), *count);
+/// ```
+pub fn write_pair<A: Clone, B: Clone>(
+    a: &Movetex<A>,
+    b: &Movetex<B>,
+    f: impl FnOnce(&mut A, &mut B),
+) -> Result<(), WriteError> {
+    transaction_2(a, b, f)
+}
+
+/// Modifies the writer copies of any number of instances, of any types, and publishes them
+/// together, or none of them.
+///
+/// Takes the instances, then `=>` and a closure receiving one writer copy per instance, in
+/// argument order. Expands to a `Result<(), WriteError>` and fails like
+/// [`Movetex::write_all`]; up to eight instances are supported.
+///
+/// ```rust
+/// use movetex::{read_n, transaction, Movetex};
+///
+/// let host = Movetex::new(String::from("db-1"));
+/// let port = Movetex::new(5432u16);
+/// let generation = Movetex::new(0u64);
+///
+/// transaction!(&host, &port, &generation => |host, port, generation| {
+///     *host = String::from("db-2");
+///     *port = 5433;
+///     *generation += 1;
+/// })
+/// .unwrap();
+///
+/// let (host, port, generation) = read_n!(&host, &port, &generation);
+/// assert_eq!((host.as_str(), *port, *generation), ("db-2", 5433, 1));
+/// ```
+#[macro_export]
+macro_rules! transaction {
+    ($a:expr, $b:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_2($a, $b, $f)
+    };
+    ($a:expr, $b:expr, $c:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_3($a, $b, $c, $f)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_4($a, $b, $c, $d, $f)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_5($a, $b, $c, $d, $e, $f)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_6($a, $b, $c, $d, $e, $g, $f)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_7($a, $b, $c, $d, $e, $g, $h, $f)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr, $i:expr => $f:expr $(,)?) => {
+        $crate::__private::transaction_8($a, $b, $c, $d, $e, $g, $h, $i, $f)
+    };
+}
+
+/// Starts writes on several instances, of any types, waiting without blocking the thread
+/// while another write owns any of them.
+///
+/// Takes the instances and expands to a future of a `Result` with one [`WriteTransaction`]
+/// per instance, in argument order; two to eight instances are supported, see
+/// [`Movetex::begin_write_async`] for one. The writer copies
+/// are taken all at once or not at all: while one is owned by another write, the future gives
+/// back the ones it took and waits for that write to finish, so tasks starting writes on the
+/// same instances in different orders cannot deadlock. Fails like [`Movetex::write_all`] if
+/// an instance is sealed, poisoned or not initialized, or appears twice.
+///
+/// The transactions can be modified across `.await` points, then published together with
+/// [`commit_all!`](crate::commit_all), or committed or dropped one by one like any
+/// [`WriteTransaction`].
+///
+/// ```rust
+/// use movetex::{begin_write_all, commit_all, read_pair, Movetex};
+/// # use std::future::Future;
+/// # use std::pin::pin;
+/// # use std::task::{Context, Poll, Waker};
+/// # fn block_on<F: Future>(future: F) -> F::Output {
+/// #     let mut future = pin!(future);
+/// #     let mut cx = Context::from_waker(Waker::noop());
+/// #     loop {
+/// #         if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+/// #             return output;
+/// #         }
+/// #     }
+/// # }
+///
+/// async fn fetch_price() -> u64 {
+///     42
+/// }
+///
+/// let prices = Movetex::new(vec![10u64]);
+/// let total = Movetex::new(10u64);
+///
+/// block_on(async {
+///     let (mut prices, mut total) = begin_write_all!(&prices, &total).await.unwrap();
+///     let price = fetch_price().await;
+///     prices.push(price);
+///     *total += price;
+///     commit_all!(prices, total).unwrap();
+/// });
+///
+/// let (prices, total) = read_pair(&prices, &total);
+/// assert_eq!(prices.iter().sum::<u64>(), *total);
+/// ```
+#[macro_export]
+macro_rules! begin_write_all {
+    ($a:expr, $b:expr $(,)?) => {
+        $crate::__private::begin_write_2($a, $b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        $crate::__private::begin_write_3($a, $b, $c)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {
+        $crate::__private::begin_write_4($a, $b, $c, $d)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {
+        $crate::__private::begin_write_5($a, $b, $c, $d, $e)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr $(,)?) => {
+        $crate::__private::begin_write_6($a, $b, $c, $d, $e, $g)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr $(,)?) => {
+        $crate::__private::begin_write_7($a, $b, $c, $d, $e, $g, $h)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr, $i:expr $(,)?) => {
+        $crate::__private::begin_write_8($a, $b, $c, $d, $e, $g, $h, $i)
+    };
+}
+
+/// Publishes several [`WriteTransaction`]s, of any types, together, or none of them.
+///
+/// Takes the transactions, as returned by [`begin_write_all!`](crate::begin_write_all) or
+/// [`Movetex::begin_write`], and expands to a `Result<(), WriteError>`; two to eight
+/// transactions are supported. Every modified value is validated first; if any is rejected,
+/// all modifications are discarded and the error of the first rejected one is returned.
+/// Otherwise the values are published like [`Movetex::write_all`] publishes them, so
+/// [`read_pair`] and [`read_n!`](crate::read_n) see all of them or none.
+#[macro_export]
+macro_rules! commit_all {
+    ($a:expr, $b:expr $(,)?) => {
+        $crate::__private::commit_2($a, $b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        $crate::__private::commit_3($a, $b, $c)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {
+        $crate::__private::commit_4($a, $b, $c, $d)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {
+        $crate::__private::commit_5($a, $b, $c, $d, $e)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr $(,)?) => {
+        $crate::__private::commit_6($a, $b, $c, $d, $e, $g)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr $(,)?) => {
+        $crate::__private::commit_7($a, $b, $c, $d, $e, $g, $h)
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $g:expr, $h:expr, $i:expr $(,)?) => {
+        $crate::__private::commit_8($a, $b, $c, $d, $e, $g, $h, $i)
+    };
+}
+
+macro_rules! transactions {
+    (
+        $name:ident, $begin_write:ident, $commit:ident:
+        $($value:ident $movetex:ident $write:ident),+
+    ) => {
+        /// Same as [`Movetex::write_all`], for instances of different types; see
+        /// [`transaction!`](crate::transaction).
+        #[doc(hidden)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name<$($value: Clone),+>(
+            $($movetex: &Movetex<$value>,)+
+            f: impl FnOnce($(&mut $value),+),
+        ) -> Result<(), WriteError> {
+            let ($(mut $write,)+) = match ($(begin($movetex),)+) {
+                ($(Ok($write),)+) => ($($write,)+),
+                ($($write,)+) => {
+                    // Dropping a begun write would poison its instance, so the untouched writer
+                    // copies are put back instead.
+                    let mut error = None;
+                    $(match $write {
+                        Ok(write) => $movetex.restore_write(write),
+                        Err(e) => {
+                            error.get_or_insert(e);
+                        }
+                    })+
+                    return Err(error.expect("one write failed to begin"));
+                }
+            };
+
+            f($($write.slot.make_mut()),+);
+
+            $commit($(WriteTransaction::new($movetex, $write)),+)
+        }
+
+        /// Starts writes on several instances; see [`begin_write_all!`](crate::begin_write_all).
+        #[doc(hidden)]
+        #[allow(clippy::too_many_arguments)]
+        pub async fn $begin_write<'a, $($value: Clone),+>(
+            $($movetex: &'a Movetex<$value>,)+
+        ) -> Result<($(WriteTransaction<'a, $value>,)+), WriteError> {
+            let states = [$(&$movetex.state),+];
+            let mut acquired = match future::poll_fn(|cx| poll_acquire_all(&states, cx)).await {
+                Ok(acquired) => acquired.into_iter(),
+                Err((index, state)) => {
+                    let refusals: [&dyn Fn(State) -> WriteError; _] =
+                        [$(&|state| $movetex.refuse_write(state)),+];
+                    return Err(refusals[index](state));
+                }
+            };
+            let mut next = || acquired.next().expect("one writer copy per instance");
+            match ($($movetex.begin_write_acquired(next()),)+) {
+                ($(Ok($write),)+) => Ok(($(WriteTransaction::new($movetex, $write),)+)),
+                ($($write,)+) => {
+                    // Each write that failed to begin has released its writer copy already.
+                    let mut error = None;
+                    $(match $write {
+                        Ok(write) => $movetex.restore_write(write),
+                        Err(e) => {
+                            error.get_or_insert(e);
+                        }
+                    })+
+                    Err(error.expect("one write failed to begin"))
+                }
+            }
+        }
+
+        /// Publishes several transactions together; see [`commit_all!`](crate::commit_all).
+        #[doc(hidden)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn $commit<$($value: Clone),+>(
+            $($write: WriteTransaction<'_, $value>,)+
+        ) -> Result<(), WriteError> {
+            $(let ($movetex, mut $write) = $write.into_parts();)+
+
+            let verdict = Ok(())$(.and($movetex.check_write(&mut $write, |_| Ok(()))))+;
+            if let Err(e) = verdict {
+                $(
+                    $movetex.stats.write_failed();
+                    $movetex.discard_write($write);
+                )+
+                return Err(e);
+            }
+
+            // All versions are bumped before any snapshot is stored, see `read_stable`.
+            let ($($write,)+) = ($(($write, $movetex.begin_publish()),)+);
+            $($movetex.publish_write($write.0, Some($write.1), None);)+
+            Ok(())
+        }
+    };
+}
+
+transactions!(transaction_2, begin_write_2, commit_2: A a a_write, B b b_write);
+transactions!(transaction_3, begin_write_3, commit_3: A a a_write, B b b_write, C c c_write);
+transactions!(
+    transaction_4, begin_write_4, commit_4: A a a_write, B b b_write, C c c_write, D d d_write
+);
+transactions!(
+    transaction_5, begin_write_5, commit_5: A a a_write, B b b_write, C c c_write, D d d_write,
+    E e e_write
+);
+transactions!(
+    transaction_6, begin_write_6, commit_6: A a a_write, B b b_write, C c c_write, D d d_write,
+    E e e_write, F g g_write
+);
+transactions!(
+    transaction_7, begin_write_7, commit_7: A a a_write, B b b_write, C c c_write, D d d_write,
+    E e e_write, F g g_write, G h h_write
+);
+transactions!(
+    transaction_8, begin_write_8, commit_8: A a a_write, B b b_write, C c c_write, D d d_write,
+    E e e_write, F g g_write, G h h_write, H i i_write
+);
+
+/// Takes the writer copies behind `states` for writing, all of them or none, waiting while a
+/// write owns any of them; fails with the index and state of an instance that cannot be
+/// written, or appears twice.
+///
+/// The writer copies taken so far are given back before waiting, so writers of overlapping
+/// sets of instances never hold one while waiting for another.
+fn poll_acquire_all(
+    states: &[&WriterState],
+    cx: &mut Context<'_>,
+) -> Poll<Result<Vec<Acquired>, (usize, State)>> {
+    if let Some(index) =
+        (1..states.len()).find(|&i| states[..i].iter().any(|s| ptr::eq(*s, states[i])))
+    {
+        return Poll::Ready(Err((index, State::Writing)));
+    }
+    'retry: loop {
+        let mut acquired = Vec::with_capacity(states.len());
+        for (index, state) in states.iter().enumerate() {
+            match state.acquire_resting(State::Writing) {
+                Ok(taken) => acquired.push(taken),
+                Err(busy) => {
+                    for (state, taken) in states.iter().zip(acquired) {
+                        state.restore(taken);
+                    }
+                    if busy != State::Writing {
+                        return Poll::Ready(Err((index, busy)));
+                    }
+                    match state.poll_while(State::Writing, cx) {
+                        Poll::Ready(()) => continue 'retry,
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+            }
+        }
+        return Poll::Ready(Ok(acquired));
+    }
+}
+
+/// Starts a write on `movetex` as part of writing several instances together.
+fn begin<T: Clone>(movetex: &Movetex<T>) -> Result<PendingWrite<'_, T>, WriteError> {
+    let acquired = movetex
+        .state
+        .acquire_resting(State::Writing)
+        .map_err(|state| movetex.refuse_write(state))?;
+    movetex.begin_write_acquired(acquired)
+}
+
+/// Discards the writes begun on `instances`.
+fn discard_all<T: Clone>(instances: &[&Movetex<T>], pending: Vec<PendingWrite<'_, T>>) {
+    for (movetex, write) in instances.iter().zip(pending) {
+        movetex.discard_write(write);
+    }
+}
@@ -0,0 +1,41 @@
+//! Writes that run on Tokio's blocking thread pool (feature `tokio`).
+
+use std::panic;
+use std::sync::Arc;
+
+use crate::{Movetex, WriteError};
+
+impl<T: Clone + Send + Sync + 'static> Movetex<T> {
+    /// Same as [`Movetex::try_write`], but runs the closure and the clone of the new writer copy
+    /// on Tokio's blocking thread pool.
+    ///
+    /// Cloning a large payload can take milliseconds, which would stall every task on an async
+    /// worker thread. With `write_offloaded` the calling task only awaits completion; the
+    /// modification, validation, clone, and the final pointer swap all happen on a thread
+    /// obtained from [`tokio::task::spawn_blocking`].
+    ///
+    /// The write runs to completion even if the returned future is dropped. A panic in `f` is
+    /// propagated to the caller.
+    ///
+    /// ### Usage Example:
+    /// ```rust,ignore
+    /// let routes = Arc::new(Movetex::new(RoutingTable::load()?));
+    ///
+    /// routes
+    ///     .write_offloaded(move |table| table.rebuild_from(&update))
+    ///     .await?;
+    /// ```
+    pub async fn write_offloaded(
+        self: &Arc<Self>,
+        f: impl FnOnce(&mut T) + Send + 'static,
+    ) -> Result<(), WriteError> {
+        let this = Arc::clone(self);
+        match tokio::task::spawn_blocking(move || this.write_inner(f, None)).await {
+            Ok(result) => result,
+            Err(e) => match e.try_into_panic() {
+                Ok(payload) => panic::resume_unwind(payload),
+                Err(e) => panic!("offloaded write was cancelled: {e}"),
+            },
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Memory orderings of the atomics of the publish protocol.
+
+use std::sync::atomic::Ordering;
+
+/// The memory orderings an instance publishes and loads its value with, and hands its writer
+/// copy between writers with, set through
+/// [`MovetexBuilder::memory_ordering`](crate::MovetexBuilder::memory_ordering).
+///
+/// Every profile makes every publish visible to readers together with everything the writer
+/// did before it, on every architecture: the guarantee comes from the Rust memory model, and
+/// the compiler emits the barriers weakly ordered hardware such as ARM or POWER needs for it.
+/// The profiles differ in what threads observe across *different* instances.
+///
+/// Two parts of the protocol ignore the profile, because weakening them would break it and
+/// strengthening them would buy nothing: the publish counter and the fences of reclamation
+/// are always sequentially consistent, since readers and writers pair up through them in a
+/// store-then-load pattern that acquire and release cannot order; the counters that only
+/// gate those fences, such as the pins of an epoch, stay relaxed.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryOrdering {
+    /// Publishes with release and loads with acquire ordering, and hands the writer copy over
+    /// the same way.
+    ///
+    /// Publishes to different instances are not ordered with respect to each other: two
+    /// threads that each read two instances can disagree on which of two independent
+    /// publishes happened first. [`read_pair`](crate::read_pair) and
+    /// [`read_n!`](crate::read_n) are the way to read several instances consistently.
+    #[default]
+    AcquireRelease,
+    /// Publishes and loads the value with sequentially consistent ordering, and hands the
+    /// writer copy over with acquire and release ordering.
+    ///
+    /// Readers get the total order of publishes of [`MemoryOrdering::SeqCst`]. The writer copy
+    /// is only ever handed between writers of the same instance, which acquire and release
+    /// already order, so writers skip the stronger barriers on it. This is the profile for
+    /// code that reasons about the order of publishes across instances, but not about the
+    /// order in which writers of different instances start their writes.
+    Mixed,
+    /// Uses sequentially consistent ordering for the published value, the writer copy and the
+    /// state of the writer slot.
+    ///
+    /// All publishes to and loads of instances with this profile fall into a single total
+    /// order that every thread agrees on, as does every start and end of a write, for code
+    /// that reasons about the order of updates across instances without coordinating its
+    /// reads. On x86 and ARMv8 this adds little to the cost of a publish and none to a read; on
+    /// POWER every read pays for a full barrier.
+    SeqCst,
+}
+
+impl MemoryOrdering {
+    /// Loads of the published value.
+    pub(crate) const fn load(self) -> Ordering {
+        match self {
+            Self::AcquireRelease => Ordering::Acquire,
+            Self::Mixed | Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Publishes of a new value.
+    pub(crate) const fn store(self) -> Ordering {
+        match self {
+            Self::AcquireRelease => Ordering::Release,
+            Self::Mixed | Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Swaps of the published value, which retire the previous one.
+    pub(crate) const fn swap(self) -> Ordering {
+        match self {
+            Self::AcquireRelease => Ordering::AcqRel,
+            Self::Mixed | Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Taking the writer copy, or the writer slot, over from the previous writer.
+    pub(crate) const fn writer_acquire(self) -> Ordering {
+        match self {
+            Self::AcquireRelease | Self::Mixed => Ordering::Acquire,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Handing the writer copy, or the writer slot, over to the next writer.
+    pub(crate) const fn writer_release(self) -> Ordering {
+        match self {
+            Self::AcquireRelease | Self::Mixed => Ordering::Release,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+
+    /// Taking the writer copy over while handing its place over in the same step.
+    pub(crate) const fn writer_swap(self) -> Ordering {
+        match self {
+            Self::AcquireRelease | Self::Mixed => Ordering::AcqRel,
+            Self::SeqCst => Ordering::SeqCst,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! JSON merge-patch updates (feature `json`).
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+
+use crate::Movetex;
+
+impl<T: Clone + Serialize + DeserializeOwned> Movetex<T> {
+    /// Applies a JSON merge patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)) to the
+    /// stored value and publishes the result.
+    ///
+    /// The writer copy is serialized to JSON, the patch is merged into it and the merged document
+    /// is deserialized back into `T`. Members set to `null` in the patch are removed, objects are
+    /// merged recursively and every other value replaces the target as a whole. This lets a
+    /// control plane push partial configuration updates without resending the full value.
+    ///
+    /// Returns `Ok(false)` if another write is in progress, like [`Movetex::write`]. If the patch
+    /// cannot be parsed or the merged document no longer deserializes into `T`, the error is
+    /// returned and readers keep observing the previous value.
+    ///
+    /// ### Usage Example:
+    /// ```rust
+    /// use movetex::Movetex;
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_json::value::RawValue;
+    ///
+    /// #[derive(Clone, Serialize, Deserialize)]
+    /// struct Config {
+    ///     name: String,
+    ///     retries: Option<u32>,
+    /// }
+    ///
+    /// let movetex = Movetex::new(Config { name: "api".into(), retries: Some(3) });
+    ///
+    /// let patch = RawValue::from_string(r#"{"retries": null}"#.to_string()).unwrap();
+    /// assert!(movetex.write_patch(&patch).unwrap());
+    /// assert_eq!(movetex.read().name, "api");
+    /// assert_eq!(movetex.read().retries, None);
+    /// ```
+    pub fn write_patch(&self, patch: &RawValue) -> Result<bool, serde_json::Error> {
+        let patch: Value = serde_json::from_str(patch.get())?;
+        let mut result = Ok(());
+        let written = self.write(|value| {
+            result = serde_json::to_value(&*value).and_then(|mut document| {
+                merge_patch(&mut document, &patch);
+                *value = serde_json::from_value(document)?;
+                Ok(())
+            });
+        });
+        result.map(|()| written)
+    }
+}
+
+/// Merges `patch` into `target` following RFC 7386.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
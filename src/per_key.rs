@@ -0,0 +1,313 @@
+//! Lazily created, independently published `Movetex` instances per key.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, Clock};
+use crate::sync::Mutex;
+use crate::{ConsistencyReport, Movetex};
+
+/// A registry of independent `Movetex` instances keyed by tenant, shard, or any other key.
+///
+/// Instances are created on first access with the initializer passed to
+/// [`MovetexPerKey::new`], so a service can serve per-tenant configuration without registering
+/// every tenant up front. Each instance is a regular `Movetex`: reads and writes on different
+/// keys never contend with each other. The registry itself is guarded by a lock that is only
+/// taken to look up, create, or evict entries; the returned `Arc<Movetex<T>>` can be kept and
+/// used without touching the registry again.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexPerKey;
+///
+/// let limits = MovetexPerKey::new(|_tenant: &String| 100u32);
+///
+/// limits.entry("acme".to_string()).write(|limit| *limit = 500);
+///
+/// assert_eq!(*limits.entry("acme".to_string()).read(), 500);
+/// assert_eq!(*limits.entry("globex".to_string()).read(), 100);
+/// assert_eq!(limits.len(), 2);
+/// ```
+///
+/// Keys are hashed with `S`, the standard library's randomly seeded hasher by default. Use
+/// [`MovetexPerKey::with_hasher`] to supply a faster one for trusted keys, or a differently
+/// seeded one for keys that an attacker may choose.
+pub struct MovetexPerKey<K, T: Clone, S = RandomState> {
+    entries: Mutex<HashMap<K, Entry<T>, S>>,
+    init: Box<dyn Fn(&K) -> T + Send + Sync>,
+    clock: Arc<dyn Clock>,
+}
+
+struct Entry<T: Clone> {
+    movetex: Arc<Movetex<T>>,
+    last_access: Instant,
+}
+
+impl<K: Eq + Hash, T: Clone> MovetexPerKey<K, T> {
+    /// Creates an empty registry; `init` produces the initial value for a newly seen key.
+    pub fn new(init: impl Fn(&K) -> T + Send + Sync + 'static) -> Self {
+        Self::with_clock(init, clock::system())
+    }
+
+    /// Same as [`MovetexPerKey::new`], but measures idle time with `clock`.
+    pub fn with_clock(
+        init: impl Fn(&K) -> T + Send + Sync + 'static,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self::with_hasher_and_clock(init, RandomState::new(), clock)
+    }
+}
+
+impl<K: Eq + Hash, T: Clone, S: BuildHasher> MovetexPerKey<K, T, S> {
+    /// Same as [`MovetexPerKey::new`], but hashes keys with `hasher`.
+    ///
+    /// ```rust
+    /// use movetex::MovetexPerKey;
+    /// use std::hash::BuildHasherDefault;
+    /// use std::collections::hash_map::DefaultHasher;
+    ///
+    /// let hasher = BuildHasherDefault::<DefaultHasher>::default();
+    /// let limits = MovetexPerKey::with_hasher(|_tenant: &u64| 100u32, hasher);
+    /// assert_eq!(*limits.entry(7).read(), 100);
+    /// ```
+    pub fn with_hasher(init: impl Fn(&K) -> T + Send + Sync + 'static, hasher: S) -> Self {
+        Self::with_hasher_and_clock(init, hasher, clock::system())
+    }
+
+    /// Same as [`MovetexPerKey::with_hasher`], but measures idle time with `clock`.
+    pub fn with_hasher_and_clock(
+        init: impl Fn(&K) -> T + Send + Sync + 'static,
+        hasher: S,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::with_hasher(hasher)),
+            init: Box::new(init),
+            clock,
+        }
+    }
+
+    /// Returns the instance for `key`, creating it with the initializer if it does not exist.
+    pub fn entry(&self, key: K) -> Arc<Movetex<T>> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock();
+        let entry = entries.entry(key).or_insert_with_key(|key| Entry {
+            movetex: Arc::new(Movetex::new((self.init)(key))),
+            last_access: now,
+        });
+        entry.last_access = now;
+        Arc::clone(&entry.movetex)
+    }
+
+    /// Returns the instance for `key` if it exists, without creating it.
+    pub fn get<Q>(&self, key: &Q) -> Option<Arc<Movetex<T>>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(key)?;
+        entry.last_access = self.clock.now();
+        Some(Arc::clone(&entry.movetex))
+    }
+
+    /// Creates instances for every `(key, value)` pair and publishes them in one step.
+    ///
+    /// All instances are built before the registry lock is taken (in parallel across the
+    /// available cores for large inputs), and then inserted under a single lock acquisition, so
+    /// other threads observe either none or all of the loaded keys. This keeps startup loading of
+    /// many entries off the per-key `write` path. Keys that already exist are replaced by fresh
+    /// instances; handles obtained earlier keep pointing at the old instance, as with
+    /// [`MovetexPerKey::remove`]. Returns the number of loaded keys.
+    pub fn bulk_load(&self, values: impl IntoIterator<Item = (K, T)>) -> usize
+    where
+        K: Send,
+        T: Send + Sync,
+    {
+        let values: Vec<(K, T)> = values.into_iter().collect();
+        let count = values.len();
+        let built = build_entries(values, self.clock.now());
+
+        let mut entries = self.entries.lock();
+        entries.extend(built);
+        count
+    }
+
+    /// Removes the instance for `key` from the registry and returns it.
+    ///
+    /// Handles obtained earlier stay valid; the next [`MovetexPerKey::entry`] call for the same
+    /// key creates a fresh instance.
+    pub fn remove<Q>(&self, key: &Q) -> Option<Arc<Movetex<T>>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.entries.lock().remove(key).map(|entry| entry.movetex)
+    }
+
+    /// Evicts instances that have not been accessed through the registry for at least `idle`.
+    ///
+    /// Instances that are still referenced by a handle outside the registry are kept, so an
+    /// in-flight writer never loses its updates to eviction. Returns the number of evicted keys.
+    pub fn evict_idle(&self, idle: Duration) -> usize {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock();
+        let before = entries.len();
+        entries.retain(|_, entry| {
+            now.duration_since(entry.last_access) < idle || Arc::strong_count(&entry.movetex) > 1
+        });
+        before - entries.len()
+    }
+
+    /// Returns the keys that currently have a live instance.
+    pub fn keys(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.entries.lock().keys().cloned().collect()
+    }
+
+    /// Calls `f` for every live key and its instance.
+    ///
+    /// The registry lock is held while `f` runs, so `f` must not call back into the registry.
+    pub fn for_each(&self, mut f: impl FnMut(&K, &Movetex<T>)) {
+        for (key, entry) in self.entries.lock().iter() {
+            f(key, &entry.movetex);
+        }
+    }
+
+    /// Captures the values of all live instances as one logically consistent view.
+    ///
+    /// The key set is taken under the registry lock, then every instance is read outside of it
+    /// together with its publish count. A verification pass re-checks all publish counts; any
+    /// instance that published in the meantime is read again and the whole view is verified
+    /// again, until a pass finds no change. The result is a view in which every value was the
+    /// published one at the moment of the final verification, which is what backups and
+    /// debugging dumps need.
+    ///
+    /// Under a sustained stream of writes to the same keys this keeps retrying until a quiet
+    /// pass is observed; [`MovetexPerKey::snapshot_all_reported`] tells how long that took.
+    pub fn snapshot_all(&self) -> HashMap<K, T>
+    where
+        K: Clone,
+    {
+        self.snapshot_all_reported().0
+    }
+
+    /// Same as [`MovetexPerKey::snapshot_all`], but also reports the verification passes that
+    /// found a change as retries, and the largest number of publishes an instance was ahead
+    /// of its captured value as the skew.
+    pub fn snapshot_all_reported(&self) -> (HashMap<K, T>, ConsistencyReport)
+    where
+        K: Clone,
+    {
+        let instances: Vec<(K, Arc<Movetex<T>>)> = self
+            .entries
+            .lock()
+            .iter()
+            .map(|(key, entry)| (key.clone(), Arc::clone(&entry.movetex)))
+            .collect();
+
+        let mut captured: Vec<(u64, T)> = instances
+            .iter()
+            .map(|(_, movetex)| capture(movetex))
+            .collect();
+        let mut report = ConsistencyReport::default();
+        loop {
+            let mut changed = false;
+            for ((_, movetex), (version, value)) in instances.iter().zip(captured.iter_mut()) {
+                let skew = movetex.publish_count().saturating_sub(*version);
+                if skew != 0 {
+                    report.observe(skew);
+                    (*version, *value) = capture(movetex);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+            report.retries += 1;
+            thread::yield_now();
+        }
+
+        let values = instances
+            .into_iter()
+            .zip(captured)
+            .map(|((key, _), (_, value))| (key, value))
+            .collect();
+        (values, report)
+    }
+
+    /// The number of live instances.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Returns `true` if the registry holds no instances.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+/// Reads a value together with the publish count it belongs to.
+fn capture<T: Clone>(movetex: &Movetex<T>) -> (u64, T) {
+    loop {
+        let (value, meta) = movetex.read_meta();
+        if movetex.publish_count() == meta.version {
+            return (meta.version, value.clone());
+        }
+    }
+}
+
+/// Inputs below this size are not worth spawning threads for.
+const PARALLEL_THRESHOLD: usize = 4096;
+
+fn build_entries<K: Send, T: Clone + Send + Sync>(
+    values: Vec<(K, T)>,
+    now: Instant,
+) -> Vec<(K, Entry<T>)> {
+    let build = |(key, value): (K, T)| {
+        let entry = Entry {
+            movetex: Arc::new(Movetex::new(value)),
+            last_access: now,
+        };
+        (key, entry)
+    };
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    if values.len() < PARALLEL_THRESHOLD || threads == 1 {
+        return values.into_iter().map(build).collect();
+    }
+
+    let chunk = values.len().div_ceil(threads);
+    let mut values = values.into_iter();
+    let chunks: Vec<Vec<(K, T)>> = (0..threads)
+        .map(|_| values.by_ref().take(chunk).collect())
+        .collect();
+    thread::scope(|s| {
+        chunks
+            .into_iter()
+            .map(|chunk| s.spawn(move || chunk.into_iter().map(build).collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+impl<K, T> FromIterator<(K, T)> for MovetexPerKey<K, T>
+where
+    K: Eq + Hash + Send,
+    T: Clone + Default + Send + Sync,
+{
+    /// Builds a registry from `(key, value)` pairs; keys seen later start from `T::default()`.
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let registry = MovetexPerKey::new(|_| T::default());
+        registry.bulk_load(iter);
+        registry
+    }
+}
@@ -0,0 +1,69 @@
+//! The warmup phase of a value that is served read-only afterwards.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::Movetex;
+
+/// A value under construction, mutated freely by its single owner before it is served.
+///
+/// Many values are assembled during startup and never change once traffic is served. This type
+/// encodes that lifecycle: while warming up, the value is a plain owned `T` with direct mutable
+/// access and no synchronisation; [`MovetexBuilderPhase::freeze`] then turns it into a sealed
+/// [`Movetex`] that only keeps the reader copy.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexBuilderPhase;
+///
+/// let mut routes = MovetexBuilderPhase::new(Vec::new());
+/// routes.push("/health");
+/// routes.push("/metrics");
+///
+/// let routes = routes.freeze();
+/// assert_eq!(routes.read().len(), 2);
+/// assert!(routes.is_sealed());
+/// ```
+pub struct MovetexBuilderPhase<T> {
+    value: T,
+}
+
+impl<T: Clone> MovetexBuilderPhase<T> {
+    /// Starts the warmup phase with `value`.
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// Ends the warmup phase and publishes the value read-only.
+    ///
+    /// The returned instance is sealed (see [`Movetex::seal`]) and does not keep a writer copy,
+    /// so the value is held in memory once.
+    pub fn freeze(self) -> Movetex<T> {
+        let movetex = Movetex::builder(self.value).write_once().build();
+        movetex.drop_writer_copy();
+        movetex
+    }
+
+    /// Ends the warmup phase and publishes the value as a regular, writable instance.
+    pub fn into_movetex(self) -> Movetex<T> {
+        Movetex::new(self.value)
+    }
+
+    /// Returns the value under construction.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for MovetexBuilderPhase<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for MovetexBuilderPhase<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
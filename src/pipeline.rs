@@ -0,0 +1,92 @@
+//! Traits for composing state pipelines.
+//!
+//! Middleware such as validators, throttlers or replicators can be written once against
+//! [`StateSource`] and [`StateSink`] and stacked on top of any instance or of each other.
+
+use std::sync::Arc;
+
+use crate::{Movetex, ReadGuard, WriteError};
+
+/// Something that serves a published value.
+pub trait StateSource<T> {
+    /// Returns the currently published value.
+    fn read(&self) -> ReadGuard<'_, T>;
+}
+
+/// Something that accepts values to publish.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, StateSink, StateSource};
+///
+/// /// Drops values that would move the published value backwards.
+/// struct Monotonic<S>(S);
+///
+/// impl<S: StateSink<u64> + StateSource<u64>> StateSink<u64> for Monotonic<S> {
+///     type Error = S::Error;
+///
+///     fn publish(&self, value: u64) -> Result<(), S::Error> {
+///         if value < *self.0.read() {
+///             return Ok(());
+///         }
+///         self.0.publish(value)
+///     }
+/// }
+///
+/// let offset = Movetex::new(10);
+/// let sink = Monotonic(&offset);
+/// sink.publish(7).unwrap();
+/// sink.publish(12).unwrap();
+/// assert_eq!(*offset.read(), 12);
+/// ```
+pub trait StateSink<T> {
+    /// The error returned when a value cannot be published.
+    type Error;
+
+    /// Publishes `value` in place of the current value.
+    fn publish(&self, value: T) -> Result<(), Self::Error>;
+}
+
+impl<T: Clone> StateSource<T> for Movetex<T> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        Movetex::read(self)
+    }
+}
+
+impl<T: Clone> StateSink<T> for Movetex<T> {
+    type Error = WriteError;
+
+    /// Publishes through [`Movetex::try_write`], so it fails instead of waiting for another
+    /// writer.
+    fn publish(&self, value: T) -> Result<(), WriteError> {
+        self.try_write(|current| *current = value)
+    }
+}
+
+impl<T, S: StateSource<T> + ?Sized> StateSource<T> for &S {
+    fn read(&self) -> ReadGuard<'_, T> {
+        (**self).read()
+    }
+}
+
+impl<T, S: StateSink<T> + ?Sized> StateSink<T> for &S {
+    type Error = S::Error;
+
+    fn publish(&self, value: T) -> Result<(), S::Error> {
+        (**self).publish(value)
+    }
+}
+
+impl<T, S: StateSource<T> + ?Sized> StateSource<T> for Arc<S> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        (**self).read()
+    }
+}
+
+impl<T, S: StateSink<T> + ?Sized> StateSink<T> for Arc<S> {
+    type Error = S::Error;
+
+    fn publish(&self, value: T) -> Result<(), S::Error> {
+        (**self).publish(value)
+    }
+}
@@ -0,0 +1,238 @@
+//! Retrying writes that fail because of contention or validation.
+
+use std::thread;
+use std::time::Duration;
+
+use crate::state::State;
+use crate::{Movetex, WriteError};
+
+/// How often and how patiently [`Movetex::write_retrying`] retries a failed write.
+///
+/// Writes are retried when another write is in progress ([`WriteError::Busy`]) or the
+/// validator rejected the value ([`WriteError::Rejected`]); any other error ends the retries
+/// right away. Between attempts the writer sleeps for the backoff, which doubles after every
+/// attempt up to its maximum, or yields the thread if no backoff is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    timeout: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Makes at most `max_attempts` attempts, without sleeping in between.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            timeout: None,
+        }
+    }
+
+    /// Sleeps `initial` after the first failed attempt, doubling the sleep after every further
+    /// one up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Stops retrying once `timeout` has elapsed since the first attempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// How [`Movetex::write_with_backoff`] waits for a write in progress to finish.
+///
+/// The writer first spins, which is cheapest when the other write is about to finish, then
+/// yields its thread, and finally sleeps, doubling the sleep after every further attempt up to
+/// its maximum. The defaults spin 64 times, yield 16 times, and sleep from 1µs up to 1ms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    spins: u32,
+    yields: u32,
+    sleep: Duration,
+    max_sleep: Duration,
+}
+
+impl Backoff {
+    /// Creates a backoff with the default steps.
+    pub fn new() -> Self {
+        Self {
+            spins: 64,
+            yields: 16,
+            sleep: Duration::from_micros(1),
+            max_sleep: Duration::from_millis(1),
+        }
+    }
+
+    /// Spins `spins` times before yielding.
+    pub fn spins(mut self, spins: u32) -> Self {
+        self.spins = spins;
+        self
+    }
+
+    /// Yields the thread `yields` times before sleeping.
+    pub fn yields(mut self, yields: u32) -> Self {
+        self.yields = yields;
+        self
+    }
+
+    /// Sleeps `initial` first, doubling the sleep after every further attempt up to `max`.
+    pub fn sleep(mut self, initial: Duration, max: Duration) -> Self {
+        self.sleep = initial;
+        self.max_sleep = max.max(initial);
+        self
+    }
+
+    /// Waits before the attempt following the `failed` attempts made so far.
+    fn wait(&self, failed: u32) {
+        if failed <= self.spins {
+            std::hint::spin_loop();
+        } else if failed <= self.spins + self.yields {
+            thread::yield_now();
+        } else {
+            let doublings = (failed - self.spins - self.yields - 1).min(31);
+            thread::sleep(
+                self.sleep
+                    .saturating_mul(1 << doublings)
+                    .min(self.max_sleep),
+            );
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Context passed to the closure of [`Movetex::write_retrying`] on every attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Attempt {
+    /// The number of the attempt, starting at 1.
+    pub number: u32,
+    /// Time elapsed since the first attempt started.
+    pub elapsed: Duration,
+}
+
+impl Attempt {
+    /// Returns `true` for the first attempt.
+    pub fn is_first(&self) -> bool {
+        self.number == 1
+    }
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Same as [`Movetex::try_write`], but waits according to `backoff` while another write is
+    /// in progress instead of failing with [`WriteError::Busy`].
+    ///
+    /// Unlike [`Movetex::write_blocking`], which parks the thread until the writer copy is
+    /// released, the waiting strategy can be tuned to the expected length of concurrent
+    /// writes. `f` runs once, after the writer copy is acquired. Fails right away if the
+    /// instance is sealed, poisoned or not initialized, and like `try_write` if the modified
+    /// value is not published.
+    ///
+    /// ```rust
+    /// use movetex::{Backoff, Movetex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let counter = Movetex::new(0);
+    /// let backoff = Backoff::new().spins(16).sleep(Duration::from_micros(10), Duration::from_millis(1));
+    /// thread::scope(|s| {
+    ///     for _ in 0..4 {
+    ///         s.spawn(|| {
+    ///             for _ in 0..100 {
+    ///                 counter.write_with_backoff(|c| *c += 1, backoff).unwrap();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(*counter.read(), 400);
+    /// ```
+    pub fn write_with_backoff(
+        &self,
+        f: impl FnOnce(&mut T),
+        backoff: Backoff,
+    ) -> Result<(), WriteError> {
+        let mut failed = 0;
+        let acquired = loop {
+            match self.state.acquire_resting(State::Writing) {
+                Ok(acquired) => break acquired,
+                Err(State::Writing) => {
+                    failed += 1;
+                    backoff.wait(failed);
+                }
+                Err(state) => return Err(self.refuse_write(state)),
+            }
+        };
+        self.write_acquired(acquired, f, |_| Ok(()), None)
+    }
+
+    /// Same as [`Movetex::try_write`], but retries according to `policy` while the write fails
+    /// because another write is in progress or the validator rejects the value.
+    ///
+    /// The closure receives the [`Attempt`] it runs in, so it can adapt, for example by
+    /// making a smaller change after repeated rejections, without tracking the attempts
+    /// itself. Returns the error of the last attempt if none succeeded.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, RetryPolicy, ValidationError};
+    ///
+    /// let batch = Movetex::builder(Vec::new())
+    ///     .validator(|v: &Vec<u32>| match v.len() {
+    ///         0..=2 => Ok(()),
+    ///         _ => Err(ValidationError::new("batch too large")),
+    ///     })
+    ///     .build();
+    ///
+    /// // Halve the number of items after every rejection.
+    /// batch
+    ///     .write_retrying(RetryPolicy::new(4), |v, attempt| {
+    ///         v.extend(0..8 >> attempt.number);
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(*batch.read(), [0, 1]);
+    /// ```
+    pub fn write_retrying(
+        &self,
+        policy: RetryPolicy,
+        mut f: impl FnMut(&mut T, Attempt),
+    ) -> Result<(), WriteError> {
+        let clock = self.activity.clock();
+        let started = clock.now();
+        let mut backoff = policy.backoff;
+        let mut number = 1;
+        loop {
+            let attempt = Attempt {
+                number,
+                elapsed: clock.now().duration_since(started),
+            };
+            let error = match self.try_write(|value| f(value, attempt)) {
+                Ok(()) => return Ok(()),
+                Err(e @ (WriteError::Busy | WriteError::Rejected(_))) => e,
+                Err(e) => return Err(e),
+            };
+            let out_of_time = policy
+                .timeout
+                .is_some_and(|timeout| clock.now().duration_since(started) + backoff >= timeout);
+            if number >= policy.max_attempts || out_of_time {
+                return Err(error);
+            }
+            if backoff.is_zero() {
+                thread::yield_now();
+            } else {
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(policy.max_backoff);
+            }
+            number += 1;
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Per-thread partial buffers folded into a `Movetex` on merge.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use crate::{Movetex, ReadGuard, WriteError};
+
+// One bucket per bit of a thread number, see `ScatterGather::partial`
+const BUCKETS: usize = usize::BITS as usize;
+
+/// A `Movetex` whose writers record into per-thread partials of type `P` instead of the
+/// shared value, folded into it by a periodic [`ScatterGather::merge`].
+///
+/// Suited to telemetry aggregation, where many threads each add a little to the published value:
+/// instead of contending for the writer copy on every update, each thread updates a partial
+/// only it writes to, and a single merge folds all partials into the value and publishes it
+/// once. Readers see updates once they are merged.
+///
+/// Updating a partial takes no lock: the thread takes it out of its slot with an atomic swap,
+/// updates it and puts it back, and a merge swaps every slot empty. A merge that runs while a
+/// thread updates its partial finds the slot empty and leaves the partial for the next merge.
+/// A thread allocates a fresh partial on its first update after a merge.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::ScatterGather;
+/// use std::collections::HashMap;
+/// use std::thread;
+///
+/// let hits: ScatterGather<HashMap<&str, u64>, HashMap<&str, u64>> =
+///     ScatterGather::new(HashMap::new());
+///
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             for _ in 0..100 {
+///                 hits.scatter(|partial| *partial.entry("/").or_default() += 1);
+///             }
+///         });
+///     }
+/// });
+///
+/// hits.merge(|total, partial| {
+///     for (path, count) in partial {
+///         *total.entry(path).or_default() += count;
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(hits.read()["/"], 400);
+/// ```
+pub struct ScatterGather<T: Clone, P> {
+    movetex: Movetex<T>,
+    // Bucket `i` holds the slots of the `2^i` threads numbered from `2^i - 1` on, allocated by
+    // the first of them to scatter.
+    buckets: [AtomicPtr<Partial<P>>; BUCKETS],
+    // Partials move from the scattering threads to the merging one but are never shared, like
+    // the value of a mutex.
+    _partials: PhantomData<std::sync::Mutex<P>>,
+}
+
+// The slot of one thread, on its own cache line; null until written after the last merge.
+#[repr(align(128))]
+struct Partial<P>(AtomicPtr<P>);
+
+impl<T: Clone, P: Default> ScatterGather<T, P> {
+    /// Creates an instance publishing `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            movetex: Movetex::new(value),
+            buckets: std::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            _partials: PhantomData,
+        }
+    }
+
+    /// Applies `f` to the partial of the calling thread, starting from `P::default()` after a
+    /// merge.
+    ///
+    /// The partial is out of reach of merges while `f` runs, so `f` should be short for
+    /// merges to pick up its updates promptly. A panic in `f` discards the partial.
+    pub fn scatter(&self, f: impl FnOnce(&mut P)) {
+        let slot = &self.partial().0;
+        let taken = slot.swap(ptr::null_mut(), Ordering::Acquire);
+        let mut partial = if taken.is_null() {
+            Box::default()
+        } else {
+            unsafe { Box::from_raw(taken) }
+        };
+        f(&mut partial);
+        // Only this thread fills its slot, so it is still empty.
+        slot.store(Box::into_raw(partial), Ordering::Release);
+    }
+
+    /// Folds every partial written since the last merge into the value with `f`, and publishes
+    /// the result.
+    ///
+    /// Waits for a write in progress like [`Movetex::write_blocking`]. The partials are only
+    /// taken once the writer copy is owned, so they are kept for the next merge if the write
+    /// cannot start; they are lost if the validator rejects the merged value.
+    pub fn merge(&self, mut f: impl FnMut(&mut T, P)) -> Result<(), WriteError> {
+        self.movetex.write_blocking(|value| {
+            for slots in self.allocated() {
+                for slot in slots {
+                    let taken = slot.0.swap(ptr::null_mut(), Ordering::AcqRel);
+                    if !taken.is_null() {
+                        f(value, *unsafe { Box::from_raw(taken) });
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns the slot of the calling thread, allocating its bucket on first use.
+    fn partial(&self) -> &Partial<P> {
+        let (bucket, index) = bucket_of(thread_number());
+        let len = 1 << bucket;
+        let mut slots = self.buckets[bucket].load(Ordering::Acquire);
+        if slots.is_null() {
+            let allocated: Box<[Partial<P>]> = (0..len)
+                .map(|_| Partial(AtomicPtr::new(ptr::null_mut())))
+                .collect();
+            let allocated = Box::into_raw(allocated).cast::<Partial<P>>();
+            slots = match self.buckets[bucket].compare_exchange(
+                ptr::null_mut(),
+                allocated,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => allocated,
+                Err(current) => {
+                    // Another thread of the same bucket allocated it first.
+                    drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(allocated, len)) });
+                    current
+                }
+            };
+        }
+        unsafe { &*slots.add(index) }
+    }
+}
+
+impl<T: Clone, P> ScatterGather<T, P> {
+    /// Returns the value published by the last merge, see [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+
+    /// The wrapped instance.
+    pub fn movetex(&self) -> &Movetex<T> {
+        &self.movetex
+    }
+
+    /// The slots of the buckets allocated so far.
+    fn allocated(&self) -> impl Iterator<Item = &[Partial<P>]> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter_map(|(bucket, slots)| {
+                let slots = slots.load(Ordering::Acquire);
+                (!slots.is_null())
+                    .then(|| unsafe { &*ptr::slice_from_raw_parts(slots, 1 << bucket) })
+            })
+    }
+}
+
+impl<T: Clone, P> Drop for ScatterGather<T, P> {
+    fn drop(&mut self) {
+        for (bucket, slots) in self.buckets.iter_mut().enumerate() {
+            let slots = *slots.get_mut();
+            if !slots.is_null() {
+                drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(slots, 1 << bucket)) });
+            }
+        }
+    }
+}
+
+impl<P> Drop for Partial<P> {
+    fn drop(&mut self) {
+        let partial = *self.0.get_mut();
+        if !partial.is_null() {
+            drop(unsafe { Box::from_raw(partial) });
+        }
+    }
+}
+
+/// Returns the bucket holding the slot of thread `number`, and the index of the slot in it.
+fn bucket_of(number: usize) -> (usize, usize) {
+    let position = number + 1;
+    let bucket = (usize::BITS - 1 - position.leading_zeros()) as usize;
+    (bucket, position - (1 << bucket))
+}
+
+/// A number identifying the calling thread among the running ones.
+///
+/// Numbers of exited threads are reused, lowest first, so the numbers in use stay close to
+/// the number of running threads and the buckets of an instance stay small. A thread that
+/// inherits a number also inherits the partial its predecessor left behind, which the next
+/// merge picks up all the same.
+fn thread_number() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    static FREE: std::sync::Mutex<BinaryHeap<Reverse<usize>>> =
+        std::sync::Mutex::new(BinaryHeap::new());
+
+    struct Number(usize);
+
+    impl Drop for Number {
+        fn drop(&mut self) {
+            FREE.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(Reverse(self.0));
+        }
+    }
+
+    thread_local! {
+        static NUMBER: Number = Number(
+            FREE.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .pop()
+                .map_or_else(|| NEXT.fetch_add(1, Ordering::Relaxed), |Reverse(number)| number),
+        );
+    }
+    NUMBER.with(|number| number.0)
+}
@@ -0,0 +1,26 @@
+//! `Serialize` and `Deserialize` for `Movetex` (feature `serde`).
+//!
+//! A `Movetex` serializes as its published value, so a configuration struct holding
+//! `Movetex` fields has the same serialized form as one holding the plain values.
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Error, Serialize, Serializer};
+
+use crate::Movetex;
+
+impl<T: Clone + Serialize> Serialize for Movetex<T> {
+    /// Serializes the published value; fails if the instance has not been initialized.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.try_read() {
+            Ok(value) => value.serialize(serializer),
+            Err(e) => Err(S::Error::custom(e)),
+        }
+    }
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for Movetex<T> {
+    /// Deserializes a value and creates a fresh instance publishing it.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Movetex::new)
+    }
+}
@@ -0,0 +1,478 @@
+//! A hash map split across several `Movetex` instances by key hash.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::state::State;
+use crate::sync::Mutex;
+use crate::{Movetex, PendingWrite, ReadGuard, WriteError, BLOCKING_SPINS};
+
+/// A concurrent hash map whose entries are spread over independently published
+/// `Movetex<HashMap<K, V>>` shards.
+///
+/// Publishing a whole map through a single `Movetex` clones every entry on every insert, which
+/// rules it out for large maps. Here a write clones only the shard holding the key, about
+/// `1 / shards` of the map, and writes to different shards neither contend nor wait for each
+/// other. Reads stay lock-free like on any `Movetex`.
+///
+/// Each shard is published on its own, so a reader can see an insert into one shard before an
+/// earlier insert into another; [`ShardedMap::len`] adds up the shards one after the other.
+///
+/// The number of shards is chosen with a [`ShardCount`], and keys are routed with a
+/// user-supplied `BuildHasher`. When [`ShardedMap::shard_sizes`] shows the keys piling up in a
+/// few shards, or the number of cores changed, [`ShardedMap::reshard`] moves them to a new
+/// shard set while the map stays in use, and [`ShardedMap::rebalance`] does the same with
+/// exclusive access and without cloning.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::ShardedMap;
+///
+/// let sessions = ShardedMap::new();
+/// sessions.insert("alice", 1u32).unwrap();
+/// sessions.insert("bob", 2).unwrap();
+///
+/// assert_eq!(sessions.get("alice").as_deref(), Some(&1));
+/// assert_eq!(sessions.remove("bob"), Ok(Some(2)));
+/// assert_eq!(sessions.len(), 1);
+/// ```
+pub struct ShardedMap<K: Clone, V: Clone, S: Clone = RandomState> {
+    // The shard set in use, one of `tables`
+    table: AtomicPtr<Table<K, V, S>>,
+    // The shard set a reshard in progress copies into, null otherwise
+    migration: AtomicPtr<Table<K, V, S>>,
+    // Every shard set created since the map was last borrowed mutably. Guards may still borrow
+    // the replaced ones, so they are emptied by a reshard but freed only with `&mut self`.
+    // Locked for the whole of a reshard, so reshards run one at a time.
+    tables: Mutex<Vec<Arc<Table<K, V, S>>>>,
+}
+
+/// One shard of a [`ShardedMap`].
+type Shard<K, V, S> = Movetex<HashMap<K, V, S>>;
+
+/// A write in progress on one shard, returned by `ShardedMap::begin`.
+struct ShardWrite<'a, K: Clone, V: Clone, S: Clone> {
+    shard: &'a Shard<K, V, S>,
+    pending: PendingWrite<'a, HashMap<K, V, S>>,
+}
+
+/// A set of shards, and the hasher routing keys to them.
+struct Table<K: Clone, V: Clone, S: Clone> {
+    shards: Box<[Shard<K, V, S>]>,
+    // Each shard hashes with a clone of it
+    hasher: S,
+}
+
+/// How many shards a [`ShardedMap`] is split into.
+///
+/// A plain `usize` converts into [`ShardCount::Fixed`]. Every strategy yields at least one
+/// shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardCount {
+    /// Exactly this many shards.
+    Fixed(usize),
+    /// At least this many shards, rounded up to a power of two, so keys are routed by masking
+    /// their hash instead of dividing it.
+    PowerOfTwo(usize),
+    /// This many shards per available core, so contention stays alike across machines.
+    PerCpu(usize),
+}
+
+impl ShardCount {
+    /// The number of shards the strategy yields on this machine.
+    pub fn resolve(self) -> usize {
+        match self {
+            ShardCount::Fixed(shards) => shards.max(1),
+            ShardCount::PowerOfTwo(shards) => shards.max(1).next_power_of_two(),
+            ShardCount::PerCpu(per_core) => {
+                let cores = thread::available_parallelism().map_or(1, |n| n.get());
+                (per_core * cores).max(1)
+            }
+        }
+    }
+}
+
+impl From<usize> for ShardCount {
+    fn from(shards: usize) -> Self {
+        ShardCount::Fixed(shards)
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> ShardedMap<K, V> {
+    /// Creates an empty map with four shards per available core.
+    pub fn new() -> Self {
+        Self::with_shards(ShardCount::PerCpu(4))
+    }
+
+    /// Same as [`ShardedMap::new`], but with the number of shards `shards` yields.
+    ///
+    /// ```rust
+    /// use movetex::{ShardCount, ShardedMap};
+    ///
+    /// assert_eq!(ShardedMap::<u32, u32>::with_shards(6).shards(), 6);
+    /// assert_eq!(ShardedMap::<u32, u32>::with_shards(ShardCount::PowerOfTwo(6)).shards(), 8);
+    /// ```
+    pub fn with_shards(shards: impl Into<ShardCount>) -> Self {
+        Self::with_shards_and_hasher(shards, RandomState::new())
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for ShardedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, S: Clone + BuildHasher> ShardedMap<K, V, S> {
+    /// Same as [`ShardedMap::with_shards`], but hashes keys with `hasher`.
+    pub fn with_shards_and_hasher(shards: impl Into<ShardCount>, hasher: S) -> Self {
+        Self::from_table(Table::new(shards.into().resolve(), hasher))
+    }
+
+    fn from_table(table: Table<K, V, S>) -> Self {
+        let table = Arc::new(table);
+        Self {
+            table: AtomicPtr::new(Arc::as_ptr(&table).cast_mut()),
+            migration: AtomicPtr::new(ptr::null_mut()),
+            tables: Mutex::new(vec![table]),
+        }
+    }
+
+    /// Moves every entry into a new set of shards, as many as `shards` yields, routed and
+    /// hashed with `hasher`, while the map stays in use.
+    ///
+    /// The new set is filled in the background of regular traffic: each shard of the current
+    /// set is copied while its writer copy is held, so writes to it wait for the copy, and
+    /// every write made from then on is published to both sets. Once every shard is copied,
+    /// the new set replaces the current one in a single atomic store; the replaced shards are
+    /// emptied after that, and freed when the map is dropped or rebalanced. Reads never wait,
+    /// and see the entries of one set or the other.
+    ///
+    /// Writes made directly through [`ShardedMap::shard`] while a reshard runs may be lost.
+    /// Reshards run one at a time.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Movetex::write_blocking`] if a shard is poisoned, in which case the map
+    /// keeps its current shard set.
+    ///
+    /// ```rust
+    /// use movetex::{ShardCount, ShardedMap};
+    /// use std::collections::hash_map::RandomState;
+    /// use std::thread;
+    ///
+    /// let map = ShardedMap::with_shards(2);
+    /// thread::scope(|s| {
+    ///     s.spawn(|| {
+    ///         for key in 0..1000u32 {
+    ///             map.insert(key, key).unwrap();
+    ///         }
+    ///     });
+    ///     map.reshard(ShardCount::PowerOfTwo(16), RandomState::new()).unwrap();
+    /// });
+    /// assert_eq!(map.shards(), 16);
+    /// assert_eq!(map.len(), 1000);
+    /// ```
+    pub fn reshard(&self, shards: impl Into<ShardCount>, hasher: S) -> Result<(), WriteError> {
+        let mut tables = self.tables.lock();
+        let current = self.table();
+        let next = Arc::new(Table::new(shards.into().resolve(), hasher));
+        // Kept from here on, since writers may reach it through `migration`.
+        tables.push(Arc::clone(&next));
+        self.migration
+            .store(Arc::as_ptr(&next).cast_mut(), Ordering::Release);
+
+        let copied = current
+            .shards
+            .iter()
+            .try_for_each(|shard| next.copy_from(shard));
+        if copied.is_ok() {
+            self.table
+                .store(Arc::as_ptr(&next).cast_mut(), Ordering::Release);
+            for shard in current.shards.iter() {
+                // Waits for the writes that took the shard before the switch, which publish to
+                // both sets. Writes taking it afterwards see the switch and move on to the new
+                // set. A poisoned shard keeps its entries.
+                let _ = shard.write_blocking(HashMap::clear);
+            }
+        }
+        self.migration.store(ptr::null_mut(), Ordering::Release);
+        copied
+    }
+
+    /// Same as [`ShardedMap::reshard`], but with exclusive access: entries are moved without
+    /// cloning, and the shard sets replaced by earlier reshards are freed.
+    ///
+    /// Pass a freshly seeded hasher when an adversarial key distribution has piled keys up in
+    /// a few shards, or a new count when the number of cores changed. The versions of the new
+    /// shards start over at 0.
+    ///
+    /// ```rust
+    /// use movetex::{ShardCount, ShardedMap};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut map = ShardedMap::with_shards(2);
+    /// for key in 0..100u32 {
+    ///     map.insert(key, key).unwrap();
+    /// }
+    /// map.rebalance(ShardCount::PowerOfTwo(5), RandomState::new());
+    /// assert_eq!(map.shards(), 8);
+    /// assert_eq!(map.shard_sizes().iter().sum::<usize>(), 100);
+    /// assert_eq!(map.get(&42).as_deref(), Some(&42));
+    /// ```
+    pub fn rebalance(&mut self, shards: impl Into<ShardCount>, hasher: S) {
+        let shards = shards.into().resolve();
+        let mut maps: Vec<_> = (0..shards)
+            .map(|_| HashMap::with_hasher(hasher.clone()))
+            .collect();
+        let current = *self.table.get_mut();
+        // The replaced shard sets are dropped with the rest of the list.
+        let tables = std::mem::take(&mut *self.tables.lock());
+        let table = tables
+            .into_iter()
+            .find(|table| ptr::eq(Arc::as_ptr(table), current))
+            .and_then(Arc::into_inner)
+            .expect("the current shard set is listed and no longer shared");
+        for shard in table.shards.into_vec() {
+            for (key, value) in shard.into_inner() {
+                maps[index(&hasher, shards, &key)].insert(key, value);
+            }
+        }
+        *self = Self::from_table(Table {
+            shards: maps.into_iter().map(Movetex::new).collect(),
+            hasher,
+        });
+    }
+
+    /// Returns the published value of `key`, keeping the snapshot of its shard alive while the
+    /// guard lives.
+    pub fn get<Q>(&self, key: &Q) -> Option<ReadGuard<'_, V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.read(|table| ReadGuard::filter_map(table.shard(key).read(), |map| map.get(key)).ok())
+    }
+
+    /// Returns `true` if a value is published for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.read(|table| table.shard(key).read_map(|map| map.contains_key(key)))
+    }
+
+    /// Publishes `value` for `key` and returns the value it replaced.
+    ///
+    /// Waits for a write in progress on the same shard like [`Movetex::write_blocking`], and
+    /// fails like it if the shard is poisoned by a panic in a previous write.
+    pub fn insert(&self, key: K, value: V) -> Result<Option<V>, WriteError> {
+        let mut write = self.begin(&key, |map| {
+            map.insert(key.clone(), value.clone());
+        })?;
+        let previous = write.map().insert(key, value);
+        write.publish()?;
+        Ok(previous)
+    }
+
+    /// Removes `key` and returns its value; waits and fails like [`ShardedMap::insert`].
+    ///
+    /// A key that is not present costs a read, not a write.
+    pub fn remove<Q>(&self, key: &Q) -> Result<Option<V>, WriteError>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return Ok(None);
+        }
+        let mut write = self.begin(key, |map| {
+            map.remove(key);
+        })?;
+        let removed = write.map().remove(key);
+        write.publish()?;
+        Ok(removed)
+    }
+
+    /// The number of entries, summed over the shards one after the other.
+    pub fn len(&self) -> usize {
+        self.read(|table| {
+            table
+                .shards
+                .iter()
+                .map(|shard| shard.read_map(HashMap::len))
+                .sum()
+        })
+    }
+
+    /// Returns `true` if no shard holds an entry.
+    pub fn is_empty(&self) -> bool {
+        self.read(|table| {
+            table
+                .shards
+                .iter()
+                .all(|shard| shard.read_map(HashMap::is_empty))
+        })
+    }
+
+    /// The number of shards.
+    pub fn shards(&self) -> usize {
+        self.table().shards.len()
+    }
+
+    /// The number of entries of each shard, read one after the other; uneven sizes suggest
+    /// a [`ShardedMap::reshard`].
+    pub fn shard_sizes(&self) -> Vec<usize> {
+        self.read(|table| {
+            table
+                .shards
+                .iter()
+                .map(|shard| shard.read_map(HashMap::len))
+                .collect()
+        })
+    }
+
+    /// The shard holding `key`, for reading or writing several of its entries at once.
+    ///
+    /// The shard belongs to the current shard set; once a reshard replaces the set, it is
+    /// emptied and no longer part of the map.
+    pub fn shard<Q>(&self, key: &Q) -> &Movetex<HashMap<K, V, S>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        self.table().shard(key)
+    }
+
+    /// The shard set in use.
+    fn table(&self) -> &Table<K, V, S> {
+        // Shard sets are only freed with `&mut self`.
+        unsafe { &*self.table.load(Ordering::Acquire) }
+    }
+
+    /// Runs `read` on the shard set in use, again if a reshard replaced the set meanwhile,
+    /// since `read` may then have seen its shards emptied.
+    fn read<'a, R>(&'a self, read: impl Fn(&'a Table<K, V, S>) -> R) -> R {
+        loop {
+            let table = self.table();
+            let result = read(table);
+            // An emptied shard was cleared after the switch, so a read that saw it sees the
+            // switch here.
+            if ptr::eq(self.table(), table) {
+                return result;
+            }
+        }
+    }
+
+    /// Takes the writer copy of the shard holding `key` in the shard set in use, waiting for a
+    /// write in progress.
+    ///
+    /// While a reshard runs, `mirror` is applied to the shard holding `key` in the new set
+    /// before this returns, so the new set sees the write no matter whether the shard was
+    /// copied yet.
+    fn begin<Q>(
+        &self,
+        key: &Q,
+        mirror: impl FnOnce(&mut HashMap<K, V, S>),
+    ) -> Result<ShardWrite<'_, K, V, S>, WriteError>
+    where
+        K: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        loop {
+            let table = self.table();
+            let shard = table.shard(key);
+            let acquired = shard
+                .state
+                .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, None)
+                .map_err(|state| shard.refuse_write(state))?;
+            let pending = shard.begin_write_acquired(acquired)?;
+            // A reshard empties a replaced shard while holding it, so holding it is enough to
+            // see whether this set is still in use.
+            if !ptr::eq(self.table(), table) {
+                shard.restore_write(pending);
+                continue;
+            }
+            // A reshard copying this shard holds it, so it either copied it before the write
+            // began, and the write must reach the new set too, or copies it after. Once the
+            // new set is in use, the write already goes to it.
+            let migration = self.migration.load(Ordering::Acquire);
+            let next = unsafe { migration.as_ref() }.filter(|next| !ptr::eq(*next, table));
+            if let Some(next) = next {
+                if let Err(e) = next.shard(key).write_blocking(mirror) {
+                    shard.restore_write(pending);
+                    return Err(e);
+                }
+            }
+            return Ok(ShardWrite { shard, pending });
+        }
+    }
+}
+
+impl<K: Clone, V: Clone, S: Clone> ShardWrite<'_, K, V, S> {
+    fn map(&mut self) -> &mut HashMap<K, V, S> {
+        self.pending.slot.make_mut()
+    }
+
+    fn publish(self) -> Result<(), WriteError> {
+        self.shard.finish_write(self.pending, |_| Ok(()), None)
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, S: Clone + BuildHasher> Table<K, V, S> {
+    fn new(shards: usize, hasher: S) -> Self {
+        Self {
+            shards: (0..shards)
+                .map(|_| Movetex::new(HashMap::with_hasher(hasher.clone())))
+                .collect(),
+            hasher,
+        }
+    }
+
+    fn shard<Q: Hash + ?Sized>(&self, key: &Q) -> &Shard<K, V, S> {
+        &self.shards[index(&self.hasher, self.shards.len(), key)]
+    }
+
+    /// Copies the entries of `shard` into this set, holding the writer copy of `shard` so that
+    /// no write to it is missed.
+    fn copy_from(&self, shard: &Shard<K, V, S>) -> Result<(), WriteError> {
+        let acquired = shard
+            .state
+            .acquire_resting_blocking(State::Writing, BLOCKING_SPINS, None)
+            .map_err(|state| shard.refuse_write(state))?;
+        // Cloning the entries can panic.
+        let held = shard.state.poison_on_unwind(acquired);
+        let mut batches: Vec<Vec<(K, V)>> = self.shards.iter().map(|_| Vec::new()).collect();
+        for (key, value) in shard.read().iter() {
+            batches[index(&self.hasher, self.shards.len(), key)].push((key.clone(), value.clone()));
+        }
+        let copied = self
+            .shards
+            .iter()
+            .zip(batches)
+            .filter(|(_, batch)| !batch.is_empty())
+            .try_for_each(|(target, batch)| target.write_blocking(|map| map.extend(batch)));
+        held.restore();
+        copied
+    }
+}
+
+/// The index of the shard holding `key` among `shards` shards routed with `hasher`.
+fn index<Q: Hash + ?Sized>(hasher: &impl BuildHasher, shards: usize, key: &Q) -> usize {
+    // The shards hash with the same state, so routing on the low bits would leave each shard
+    // with keys whose bucket indices all share them.
+    let hash = hasher.hash_one(key) >> 32;
+    let shards = shards as u64;
+    if shards.is_power_of_two() {
+        (hash & (shards - 1)) as usize
+    } else {
+        (hash % shards) as usize
+    }
+}
@@ -0,0 +1,130 @@
+//! A slot map with stable integer keys, published through a single `Movetex`.
+
+use crate::{Movetex, ReadGuard, WriteError};
+
+/// A table of values addressed by stable integer keys, such as connection or session tables.
+///
+/// Keys are indices into a slot vector: they never change while the value is present, and the
+/// key of a removed value is reused by a later insert. Lookups read the published table without
+/// locking. Modifications are applied to the writer copy and published together, so a batch of
+/// inserts and removals becomes visible to readers at once.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::MovetexSlab;
+///
+/// let sessions = MovetexSlab::new();
+///
+/// let (alice, bob) = sessions
+///     .batch(|batch| (batch.insert("alice"), batch.insert("bob")))
+///     .unwrap();
+/// assert_eq!(sessions.get(alice).as_deref(), Some(&"alice"));
+///
+/// sessions.remove(alice).unwrap();
+/// assert!(sessions.get(alice).is_none());
+/// assert_eq!(sessions.get(bob).as_deref(), Some(&"bob"));
+/// ```
+pub struct MovetexSlab<T: Clone> {
+    movetex: Movetex<Slab<T>>,
+}
+
+#[derive(Clone)]
+struct Slab<T> {
+    entries: Vec<Option<T>>,
+    // Indices of vacant entries, reused by `insert`
+    vacant: Vec<usize>,
+    len: usize,
+}
+
+/// Modifications of a [`MovetexSlab`] that are published together, see [`MovetexSlab::batch`].
+pub struct SlabBatch<'a, T> {
+    slab: &'a mut Slab<T>,
+}
+
+impl<T> SlabBatch<'_, T> {
+    /// Stores `value` and returns its key.
+    pub fn insert(&mut self, value: T) -> usize {
+        self.slab.len += 1;
+        match self.slab.vacant.pop() {
+            Some(key) => {
+                self.slab.entries[key] = Some(value);
+                key
+            }
+            None => {
+                self.slab.entries.push(Some(value));
+                self.slab.entries.len() - 1
+            }
+        }
+    }
+
+    /// Removes and returns the value stored under `key`, if any.
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        let value = self.slab.entries.get_mut(key)?.take()?;
+        self.slab.vacant.push(key);
+        self.slab.len -= 1;
+        Some(value)
+    }
+
+    /// Returns the value stored under `key`, including modifications made in this batch.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slab.entries.get_mut(key)?.as_mut()
+    }
+}
+
+impl<T: Clone> MovetexSlab<T> {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self {
+            movetex: Movetex::new(Slab {
+                entries: Vec::new(),
+                vacant: Vec::new(),
+                len: 0,
+            }),
+        }
+    }
+
+    /// Returns the published value stored under `key`.
+    pub fn get(&self, key: usize) -> Option<ReadGuard<'_, T>> {
+        ReadGuard::filter_map(self.movetex.read(), |slab| slab.entries.get(key)?.as_ref()).ok()
+    }
+
+    /// Returns `true` if a value is published under `key`.
+    pub fn contains_key(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The number of published values.
+    pub fn len(&self) -> usize {
+        self.movetex.read().len
+    }
+
+    /// Returns `true` if no values are published.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Applies the modifications made by `f` and publishes them together.
+    ///
+    /// Fails like [`Movetex::try_write`], in which case none of the modifications are
+    /// published.
+    pub fn batch<R>(&self, f: impl FnOnce(&mut SlabBatch<'_, T>) -> R) -> Result<R, WriteError> {
+        self.movetex
+            .try_write_with(|slab| f(&mut SlabBatch { slab }))
+    }
+
+    /// Stores `value` under a new key and publishes it.
+    pub fn insert(&self, value: T) -> Result<usize, WriteError> {
+        self.batch(|batch| batch.insert(value))
+    }
+
+    /// Removes the value stored under `key` and publishes the removal.
+    pub fn remove(&self, key: usize) -> Result<Option<T>, WriteError> {
+        self.batch(|batch| batch.remove(key))
+    }
+}
+
+impl<T: Clone> Default for MovetexSlab<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,137 @@
+//! Published snapshots and the metadata stored alongside them.
+
+use std::mem::MaybeUninit;
+use std::time::SystemTime;
+
+use crate::checksum::Checksum;
+use crate::invariant::{self, InvariantViolation};
+use crate::spill::Slot;
+use crate::sync::atomic::{AtomicUsize, Ordering};
+
+/// Metadata of a published snapshot, returned together with its value by
+/// [`Movetex::read_meta`](crate::Movetex::read_meta).
+///
+/// The metadata is stored in the same allocation as the value, so both always describe the
+/// same publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Meta {
+    /// The number of publishes up to and including this one; `0` for the value the instance
+    /// was constructed with.
+    pub version: u64,
+    /// Wall-clock time of the publish.
+    pub published_at: SystemTime,
+    /// The label passed to [`Movetex::write_as`](crate::Movetex::write_as), if any.
+    pub writer_label: Option<String>,
+}
+
+impl Meta {
+    pub(crate) fn new(version: u64, label: Option<&str>) -> Self {
+        Self {
+            version,
+            published_at: SystemTime::now(),
+            writer_label: label.map(str::to_owned),
+        }
+    }
+}
+
+/// The number of read guards holding a snapshot.
+pub(crate) struct Holds(AtomicUsize);
+
+impl Holds {
+    fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// Takes a hold; the caller must have pinned the epoch the snapshot was loaded in.
+    pub(crate) fn hold(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Releases a hold; a publish frees the snapshot once none is left.
+    pub(crate) fn release(&self) {
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+
+    /// Returns `true` while a guard holds the snapshot.
+    pub(crate) fn is_held(&self) -> bool {
+        self.count() != 0
+    }
+
+    /// The number of guards holding the snapshot.
+    pub(crate) fn count(&self) -> usize {
+        // Pairs with the release in `release`, so the reads through dropped guards are done.
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// A published slot together with its metadata and the checksum it had when it was published.
+pub(crate) struct Snapshot<T> {
+    slot: Slot<T>,
+    meta: Meta,
+    checksum: u64,
+    holds: Holds,
+}
+
+impl<T> Snapshot<T> {
+    pub(crate) fn new(slot: Slot<T>, meta: Meta, checksum: Option<Checksum<T>>) -> Self {
+        let checksum = checksum.map_or(0, |checksum| checksum(&slot));
+        Self {
+            slot,
+            meta,
+            checksum,
+            holds: Holds::new(),
+        }
+    }
+
+    /// The published slot, without verification.
+    pub(crate) fn slot(&self) -> &Slot<T> {
+        &self.slot
+    }
+
+    /// The published slot, for modifications made while no reader can see it; the caller
+    /// must [`Snapshot::reseal`] it afterwards.
+    pub(crate) fn slot_mut(&mut self) -> &mut Slot<T> {
+        &mut self.slot
+    }
+
+    /// Recomputes the checksum without reporting a mismatch.
+    pub(crate) fn is_intact(&self, checksum: Option<Checksum<T>>) -> bool {
+        checksum.is_none_or(|checksum| checksum(&self.slot) == self.checksum)
+    }
+
+    /// Recomputes the checksum after the slot was modified in place.
+    pub(crate) fn reseal(&mut self, checksum: Option<Checksum<T>>) {
+        self.checksum = checksum.map_or(0, |checksum| checksum(&self.slot));
+    }
+
+    /// The guards holding the snapshot.
+    pub(crate) fn holds(&self) -> &Holds {
+        &self.holds
+    }
+
+    pub(crate) fn into_slot(self) -> Slot<T> {
+        self.slot
+    }
+
+    pub(crate) fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    /// Recomputes the checksum and reports a mismatch; returns `false` on a mismatch.
+    pub(crate) fn verify(&self, checksum: Option<Checksum<T>>) -> bool {
+        let intact = self.is_intact(checksum);
+        if !intact {
+            invariant::report(InvariantViolation::ChecksumMismatch);
+        }
+        intact
+    }
+}
+
+/// Moves the value out of `boxed`, keeping its allocation for the next value to be written to.
+pub(crate) fn unbox<T>(boxed: Box<T>) -> (T, Box<MaybeUninit<T>>) {
+    let raw = Box::into_raw(boxed);
+    // The value is read out exactly once, and `MaybeUninit` frees the allocation without
+    // dropping it again.
+    unsafe { (raw.read(), Box::from_raw(raw.cast())) }
+}
@@ -0,0 +1,193 @@
+//! Spill policy for oversized payloads.
+//!
+//! By default `Movetex` keeps two independent copies of the value: one for readers and one for
+//! the writer. For small values this is the cheapest layout, but once a payload grows large
+//! (a configuration map that keeps accumulating entries, a big buffer) every copy is a deep
+//! clone. A [`SpillPolicy`] lets `Movetex` switch such payloads to an `Arc`-backed layout
+//! automatically: the reader and writer copies then share one allocation, publishing becomes a
+//! reference-count bump, and the deep copy is deferred to `Arc::make_mut` at the start of the
+//! next write.
+
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::CloneCost;
+
+/// Decides when a payload is large enough to be moved behind an `Arc`.
+///
+/// The policy is evaluated after every successful `write`, so a value that grows past the
+/// threshold is spilled on the next publish and a value that shrinks back below it is stored
+/// inline again. The size is obtained from a user-supplied estimator, since `size_of` only
+/// reports the stack size of a type (24 bytes for any `String`) and says nothing about heap
+/// data.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, SpillPolicy};
+///
+/// let movetex = Movetex::builder(String::new())
+///     .spill(SpillPolicy::new(1024, |s: &String| s.len()))
+///     .build();
+///
+/// movetex.write(|s| s.push_str(&"x".repeat(4096)));
+/// assert!(movetex.is_spilled());
+/// ```
+pub struct SpillPolicy<T> {
+    threshold: usize,
+    estimate: fn(&T) -> usize,
+}
+
+impl<T> SpillPolicy<T> {
+    /// Creates a policy that spills values whose estimated size exceeds `threshold` bytes.
+    pub fn new(threshold: usize, estimate: fn(&T) -> usize) -> Self {
+        Self {
+            threshold,
+            estimate,
+        }
+    }
+
+    /// Creates a policy that spills values whose [`CloneCost`] exceeds `threshold` bytes.
+    ///
+    /// ```rust
+    /// use movetex::{Movetex, SpillPolicy};
+    ///
+    /// let movetex = Movetex::builder(vec![String::new()])
+    ///     .spill(SpillPolicy::by_clone_cost(16 * 1024))
+    ///     .build();
+    ///
+    /// movetex.write(|v| v.push("x".repeat(64 * 1024)));
+    /// assert!(movetex.is_spilled());
+    /// ```
+    pub fn by_clone_cost(threshold: usize) -> Self
+    where
+        T: CloneCost,
+    {
+        Self::new(threshold, T::clone_cost)
+    }
+
+    /// A policy that never spills, keeping the original two-copy layout.
+    pub fn disabled() -> Self {
+        Self {
+            threshold: usize::MAX,
+            estimate: mem::size_of_val,
+        }
+    }
+
+    /// A policy that always spills, so the reader and writer copies permanently share one `Arc`.
+    pub fn always() -> Self {
+        Self {
+            threshold: 0,
+            estimate: |_| 1,
+        }
+    }
+
+    /// The configured threshold in bytes.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub(crate) fn should_spill(&self, value: &T) -> bool {
+        self.threshold != usize::MAX && (self.estimate)(value) > self.threshold
+    }
+}
+
+impl<T> Default for SpillPolicy<T> {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl<T> Clone for SpillPolicy<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SpillPolicy<T> {}
+
+impl<T> fmt::Debug for SpillPolicy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpillPolicy")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// Internal storage of a single copy: either the value itself or a shared `Arc`.
+pub(crate) enum Slot<T> {
+    Inline(T),
+    Spilled(Arc<T>),
+}
+
+impl<T: Clone> Slot<T> {
+    /// Returns a mutable reference, deep-cloning a spilled value only if it is still shared.
+    pub(crate) fn make_mut(&mut self) -> &mut T {
+        match self {
+            Slot::Inline(value) => value,
+            Slot::Spilled(arc) => Arc::make_mut(arc),
+        }
+    }
+
+    /// Applies the policy to a freshly written value, returning the `(reader, writer)` copies.
+    ///
+    /// Moving a value into an `Arc` costs no clone at all, and both copies of a spilled value
+    /// share the same allocation.
+    pub(crate) fn publish(self, policy: &SpillPolicy<T>) -> (Slot<T>, Slot<T>) {
+        let slot = self.apply(policy);
+        (slot.clone(), slot)
+    }
+
+    /// Applies the policy to a freshly written value without producing a second copy.
+    ///
+    /// Zero-sized values are never spilled, since the `Arc` would be the only allocation.
+    pub(crate) fn apply(self, policy: &SpillPolicy<T>) -> Slot<T> {
+        let spill = mem::size_of::<T>() != 0 && policy.should_spill(&self);
+        match (self, spill) {
+            (Slot::Inline(value), true) => Slot::Spilled(Arc::new(value)),
+            (Slot::Spilled(arc), false) => Slot::Inline(Arc::unwrap_or_clone(arc)),
+            (slot, _) => slot,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        match self {
+            Slot::Inline(value) => value,
+            Slot::Spilled(arc) => Arc::unwrap_or_clone(arc),
+        }
+    }
+
+    /// Returns the value without cloning, or `None` if it is spilled and still shared.
+    pub(crate) fn into_unshared(self) -> Option<T> {
+        match self {
+            Slot::Inline(value) => Some(value),
+            Slot::Spilled(arc) => Arc::try_unwrap(arc).ok(),
+        }
+    }
+
+    pub(crate) fn is_spilled(&self) -> bool {
+        matches!(self, Slot::Spilled(_))
+    }
+}
+
+impl<T: Clone> Clone for Slot<T> {
+    /// Clones an inline value deeply; a spilled value only gets its reference count bumped.
+    fn clone(&self) -> Self {
+        match self {
+            Slot::Inline(value) => Slot::Inline(value.clone()),
+            Slot::Spilled(arc) => Slot::Spilled(Arc::clone(arc)),
+        }
+    }
+}
+
+impl<T> Deref for Slot<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Slot::Inline(value) => value,
+            Slot::Spilled(arc) => arc,
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! The state machine of the writer copy.
+//!
+//! Every operation that touches the writer copy (`ptr_w`) first moves this state machine out of
+//! a resting state, and puts it back into one when it is done. Features such as sealing,
+//! poisoning, and dirty-tracking are states of the same machine rather than ad-hoc checks on
+//! pointer values, so they compose: a sealed instance rejects `swap` exactly like it rejects
+//! `write`, and a `swap` on a dirty instance keeps it dirty.
+//!
+//! The state is packed into one `AtomicUsize` together with a generation counter:
+//!
+//! ```text
+//! | generation | state (3 bits) |
+//! ```
+//!
+//! Transitions are compare-and-swaps from an observed word, and every release bumps the
+//! generation, so an acquisition can never succeed against a word observed before another owner
+//! came and went.
+//!
+//! Writers that would rather wait than fail park on a condition variable next to the word, or
+//! register the waker of their task; every release wakes them if any are registered, so the
+//! uncontended path never locks.
+//!
+//! ```text
+//!                 initialize                write / swap            publish
+//! Uninitialized ─────────────► Idle ◄──────────────────────► Writing ────────► Idle
+//!                               │  ▲                         Swapping ───────► Dirty
+//!                               │  └──────── write ─────── Dirty
+//!                               └─ seal ─► Sealed        panic in write ─► Poisoned
+//! ```
+
+use std::mem;
+use std::task::{ready, Context, Poll, Waker};
+
+use crate::sync::atomic::{self, AtomicUsize, Ordering};
+
+use crate::clock::Deadline;
+use crate::sync::{Condvar, Mutex};
+use crate::{MemoryOrdering, WriteError};
+
+const STATE_BITS: u32 = 3;
+const STATE_MASK: usize = (1 << STATE_BITS) - 1;
+const GENERATION: usize = 1 << STATE_BITS;
+
+/// The state of the writer copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum State {
+    /// Created with `Movetex::uninit` and not initialized yet; there is no writer copy.
+    Uninitialized = 0,
+    /// The writer copy equals the published value.
+    Idle = 1,
+    /// A `swap` replaced the writer copy; readers still see the previously published value.
+    Dirty = 2,
+    /// A `write` owns the writer copy and may run an arbitrary closure.
+    Writing = 3,
+    /// A `swap` owns the writer copy for a pointer exchange.
+    Swapping = 4,
+    /// No further writes are accepted; readers keep the last published value.
+    Sealed = 5,
+    /// A writer panicked while owning the writer copy.
+    Poisoned = 6,
+}
+
+impl State {
+    fn from_bits(bits: usize) -> State {
+        match bits & STATE_MASK {
+            0 => State::Uninitialized,
+            1 => State::Idle,
+            2 => State::Dirty,
+            3 => State::Writing,
+            4 => State::Swapping,
+            5 => State::Sealed,
+            _ => State::Poisoned,
+        }
+    }
+
+    /// States in which nobody owns the writer copy and it can be acquired.
+    pub(crate) fn is_resting(self) -> bool {
+        matches!(self, State::Idle | State::Dirty)
+    }
+
+    /// The error reported to a writer that found the writer copy in this state.
+    pub(crate) fn write_error(self) -> WriteError {
+        match self {
+            State::Uninitialized => WriteError::NotInitialized,
+            State::Sealed => WriteError::Sealed,
+            State::Poisoned => WriteError::Poisoned,
+            State::Idle | State::Dirty | State::Writing | State::Swapping => WriteError::Busy,
+        }
+    }
+}
+
+pub(crate) struct WriterState {
+    word: AtomicUsize,
+    // Orderings of the accesses to `word`
+    ordering: MemoryOrdering,
+    // Number of threads parked in `park_while` plus registered wakers
+    parked: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+}
+
+/// Proof of ownership of the writer copy, returned by [`WriterState::acquire`].
+#[must_use]
+pub(crate) struct Acquired {
+    word: usize,
+}
+
+impl Acquired {
+    /// The state the writer copy was acquired from.
+    pub(crate) fn previous(&self) -> State {
+        State::from_bits(self.word)
+    }
+}
+
+/// Ownership of the writer copy that poisons it if dropped without being released, which
+/// only happens while a panic unwinds through a write.
+pub(crate) struct PoisonOnUnwind<'a> {
+    state: &'a WriterState,
+    acquired: Option<Acquired>,
+}
+
+impl PoisonOnUnwind<'_> {
+    pub(crate) fn release(mut self, to: State) {
+        if let Some(acquired) = self.acquired.take() {
+            self.state.release(acquired, to);
+        }
+    }
+
+    /// The state the writer copy was acquired from.
+    pub(crate) fn previous(&self) -> State {
+        self.acquired
+            .as_ref()
+            .map_or(State::Poisoned, Acquired::previous)
+    }
+
+    /// Same as [`WriterState::restore`].
+    pub(crate) fn restore(mut self) {
+        if let Some(acquired) = self.acquired.take() {
+            self.state.restore(acquired);
+        }
+    }
+}
+
+impl Drop for PoisonOnUnwind<'_> {
+    fn drop(&mut self) {
+        if let Some(acquired) = self.acquired.take() {
+            self.state.release(acquired, State::Poisoned);
+        }
+    }
+}
+
+impl WriterState {
+    pub(crate) fn new(initial: State, ordering: MemoryOrdering) -> Self {
+        Self {
+            word: AtomicUsize::new(initial as usize),
+            ordering,
+            parked: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn get(&self) -> State {
+        State::from_bits(self.word.load(self.ordering.writer_acquire()))
+    }
+
+    /// Moves from a state accepted by `from` into `to`.
+    ///
+    /// A `Swapping` owner holds the writer copy for a few instructions only, so it is waited
+    /// out. Any other state rejected by `from` is returned as the error.
+    pub(crate) fn acquire(
+        &self,
+        from: impl Fn(State) -> bool,
+        to: State,
+    ) -> Result<Acquired, State> {
+        let mut current = self.word.load(self.ordering.writer_acquire());
+        loop {
+            let state = State::from_bits(current);
+            if state == State::Swapping && !from(state) {
+                crate::sync::spin_loop();
+                current = self.word.load(self.ordering.writer_acquire());
+                continue;
+            }
+            if !from(state) {
+                return Err(state);
+            }
+            let next = (current & !STATE_MASK) | to as usize;
+            match self.word.compare_exchange_weak(
+                current,
+                next,
+                self.ordering.writer_acquire(),
+                self.ordering.writer_acquire(),
+            ) {
+                Ok(_) => return Ok(Acquired { word: current }),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Takes ownership of the writer copy for `access`, from `Idle` or `Dirty`.
+    pub(crate) fn acquire_resting(&self, access: State) -> Result<Acquired, State> {
+        self.acquire(State::is_resting, access)
+    }
+
+    /// Gives up ownership, entering `to` and starting the next generation.
+    pub(crate) fn release(&self, acquired: Acquired, to: State) {
+        let next = ((acquired.word & !STATE_MASK) + GENERATION) | to as usize;
+        self.word.store(next, self.ordering.writer_release());
+        // Pairs with the fences in `park_while` and `poll_while`: either the waiter sees the
+        // new state, or this load sees it registered.
+        atomic::fence(Ordering::SeqCst);
+        if self.parked.load(Ordering::Relaxed) != 0 {
+            // Taking the lock orders the notification after the waiter's last check.
+            let wakers = mem::take(&mut *self.wakers.lock());
+            self.parked.fetch_sub(wakers.len(), Ordering::Relaxed);
+            self.condvar.notify_all();
+            wakers.into_iter().for_each(Waker::wake);
+        }
+    }
+
+    /// Guards `acquired` against panics in code that runs while it is held.
+    pub(crate) fn poison_on_unwind(&self, acquired: Acquired) -> PoisonOnUnwind<'_> {
+        PoisonOnUnwind {
+            state: self,
+            acquired: Some(acquired),
+        }
+    }
+
+    /// Gives up ownership without having touched the writer copy, returning to the state it
+    /// was acquired from.
+    pub(crate) fn restore(&self, acquired: Acquired) {
+        let previous = acquired.previous();
+        self.release(acquired, previous);
+    }
+
+    /// Same as [`WriterState::acquire_resting`], but waits while a write owns the writer copy:
+    /// first spinning for `spins` iterations, then parked until the owner releases it.
+    ///
+    /// Gives up with `Err(State::Writing)` once `deadline` has passed, if one is set.
+    pub(crate) fn acquire_resting_blocking(
+        &self,
+        access: State,
+        spins: u32,
+        deadline: Option<Deadline<'_>>,
+    ) -> Result<Acquired, State> {
+        for _ in 0..spins {
+            match self.acquire_resting(access) {
+                Err(State::Writing) => crate::sync::spin_loop(),
+                result => return result,
+            }
+        }
+        loop {
+            match self.acquire_resting(access) {
+                Err(State::Writing) if self.park_while(State::Writing, deadline) => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Blocks the calling thread until the state is no longer `state`; returns `false` if
+    /// `deadline` passed first.
+    fn park_while(&self, state: State, deadline: Option<Deadline<'_>>) -> bool {
+        self.parked.fetch_add(1, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        let mut guard = self.wakers.lock();
+        let mut changed = true;
+        while self.get() == state {
+            match deadline {
+                None => guard = self.condvar.wait(guard),
+                Some(deadline) => {
+                    let Some(sleep) = deadline.sleep() else {
+                        changed = false;
+                        break;
+                    };
+                    guard = self.condvar.wait_timeout(guard, sleep).0;
+                }
+            }
+        }
+        drop(guard);
+        self.parked.fetch_sub(1, Ordering::Relaxed);
+        changed
+    }
+
+    /// Same as [`WriterState::acquire_resting`], but waits while a write owns the writer copy,
+    /// registering the waker of `cx` for its release.
+    pub(crate) fn poll_acquire_resting(
+        &self,
+        access: State,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Acquired, State>> {
+        loop {
+            match self.acquire_resting(access) {
+                Err(State::Writing) => ready!(self.poll_while(State::Writing, cx)),
+                result => return Poll::Ready(result),
+            }
+        }
+    }
+
+    /// Same as `park_while` for a task: ready once the state is no longer `state`, otherwise
+    /// registers the waker of `cx` for the next release.
+    pub(crate) fn poll_while(&self, state: State, cx: &mut Context<'_>) -> Poll<()> {
+        if self.get() != state {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.wakers.lock();
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+            self.parked.fetch_add(1, Ordering::Relaxed);
+        }
+        drop(wakers);
+        atomic::fence(Ordering::SeqCst);
+        // A release between the first check and the registration did not see the waker.
+        if self.get() != state {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,75 @@
+//! Operation counters (feature `stats`).
+//!
+//! The counters are compiled out without the feature, so the recording calls on the read and
+//! write paths cost nothing in default builds.
+
+#[cfg(feature = "stats")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts of the operations on a `Movetex` since construction, returned by
+/// [`Movetex::stats`](crate::Movetex::stats).
+///
+/// A high share of failed writes means writers often find the writer copy taken, or have
+/// their values rejected; with [`Movetex::write`](crate::Movetex::write) this is how starved
+/// writers show up, since the failures are only visible to the callers.
+#[cfg(feature = "stats")]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct MovetexStats {
+    /// Reads that found a published value.
+    pub reads: u64,
+    /// Publishes, including those of `swap_and_publish`, `publish` and `initialize`.
+    pub writes: u64,
+    /// Writes and swaps that returned without publishing or swapping: because another write
+    /// was in progress, the instance was sealed, poisoned or not initialized, or the value was
+    /// rejected, timed out, or conflicted with another publish. Waiting writes count once, if
+    /// they give up.
+    pub failed_writes: u64,
+    /// Successful swaps of the writer copy.
+    pub swaps: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct Counters {
+    #[cfg(feature = "stats")]
+    reads: AtomicU64,
+    #[cfg(feature = "stats")]
+    writes: AtomicU64,
+    #[cfg(feature = "stats")]
+    failed_writes: AtomicU64,
+    #[cfg(feature = "stats")]
+    swaps: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn read(&self) {
+        #[cfg(feature = "stats")]
+        self.reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn write(&self) {
+        #[cfg(feature = "stats")]
+        self.writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn write_failed(&self) {
+        #[cfg(feature = "stats")]
+        self.failed_writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn swap(&self) {
+        #[cfg(feature = "stats")]
+        self.swaps.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn report(&self) -> MovetexStats {
+        MovetexStats {
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            failed_writes: self.failed_writes.load(Ordering::Relaxed),
+            swaps: self.swaps.load(Ordering::Relaxed),
+        }
+    }
+}
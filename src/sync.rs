@@ -0,0 +1,162 @@
+//! Internal locking primitives used by the blocking and notification features.
+//!
+//! With the `parking_lot` feature enabled these wrap `parking_lot::{Mutex, Condvar, RwLock}`,
+//! otherwise they fall back to `std::sync`. Both backends expose the same poison-free API: a
+//! panic while a lock is held never makes the lock unusable for other threads, since every
+//! critical section in this crate leaves the protected data in a valid state.
+//!
+//! Built with `--cfg loom`, the locks and the atomics of the publish protocol come from `loom`
+//! instead, so the model checker explores their interleavings; see `tests/loom.rs`. The
+//! `RwLock` of process-wide statics is the exception.
+
+use std::time::Duration;
+
+#[cfg(loom)]
+use loom::sync as backend;
+#[cfg(not(any(feature = "parking_lot", loom)))]
+use std::sync as backend;
+
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic;
+#[cfg(not(loom))]
+pub(crate) use std::sync::atomic;
+
+#[cfg(any(not(feature = "parking_lot"), loom))]
+pub(crate) use backend::MutexGuard;
+#[cfg(all(feature = "parking_lot", not(loom)))]
+pub(crate) use parking_lot::MutexGuard;
+
+#[cfg(feature = "parking_lot")]
+pub(crate) use parking_lot::{RwLockReadGuard, RwLockWriteGuard};
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+/// Signals a spin-wait for another thread; under loom, this lets the other thread run.
+pub(crate) fn spin_loop() {
+    #[cfg(loom)]
+    loom::hint::spin_loop();
+    #[cfg(not(loom))]
+    std::hint::spin_loop();
+}
+
+#[derive(Default)]
+pub(crate) struct Mutex<T>(
+    #[cfg(all(feature = "parking_lot", not(loom)))] parking_lot::Mutex<T>,
+    #[cfg(any(not(feature = "parking_lot"), loom))] backend::Mutex<T>,
+);
+
+impl<T> Mutex<T> {
+    pub(crate) fn new(value: T) -> Self {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        return Self(parking_lot::Mutex::new(value));
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        return Self(backend::Mutex::new(value));
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        return self.0.lock();
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        return self.0.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    #[cfg(any(feature = "arc-swap", feature = "hdrhistogram"))]
+    pub(crate) fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        return self.0.try_lock();
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        return match self.0.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        };
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        return self.0.into_inner();
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        return self.0.into_inner().unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Condvar(
+    #[cfg(all(feature = "parking_lot", not(loom)))] parking_lot::Condvar,
+    #[cfg(any(not(feature = "parking_lot"), loom))] backend::Condvar,
+);
+
+impl Condvar {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        {
+            let mut guard = guard;
+            self.0.wait(&mut guard);
+            guard
+        }
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        self.0.wait(guard).unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits for a notification or until `timeout` elapses; returns `true` on timeout.
+    pub(crate) fn wait_timeout<'a, T>(
+        &self,
+        guard: MutexGuard<'a, T>,
+        timeout: Duration,
+    ) -> (MutexGuard<'a, T>, bool) {
+        #[cfg(all(feature = "parking_lot", not(loom)))]
+        {
+            let mut guard = guard;
+            let timed_out = self.0.wait_for(&mut guard, timeout).timed_out();
+            (guard, timed_out)
+        }
+        #[cfg(any(not(feature = "parking_lot"), loom))]
+        {
+            let (guard, result) = self
+                .0
+                .wait_timeout(guard, timeout)
+                .unwrap_or_else(|e| e.into_inner());
+            (guard, result.timed_out())
+        }
+    }
+
+    pub(crate) fn notify_all(&self) {
+        self.0.notify_all();
+    }
+}
+
+/// A reader-writer lock for process-wide statics.
+///
+/// Unlike [`Mutex`] it is never backed by loom, whose locks cannot be built in a `static`; the
+/// statics it guards are configuration outside the publish protocol the models check.
+pub(crate) struct RwLock<T>(
+    #[cfg(feature = "parking_lot")] parking_lot::RwLock<T>,
+    #[cfg(not(feature = "parking_lot"))] std::sync::RwLock<T>,
+);
+
+impl<T> RwLock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        #[cfg(feature = "parking_lot")]
+        return Self(parking_lot::RwLock::new(value));
+        #[cfg(not(feature = "parking_lot"))]
+        return Self(std::sync::RwLock::new(value));
+    }
+
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, T> {
+        #[cfg(feature = "parking_lot")]
+        return self.0.read();
+        #[cfg(not(feature = "parking_lot"))]
+        return self.0.read().unwrap_or_else(|e| e.into_inner());
+    }
+
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, T> {
+        #[cfg(feature = "parking_lot")]
+        return self.0.write();
+        #[cfg(not(feature = "parking_lot"))]
+        return self.0.write().unwrap_or_else(|e| e.into_inner());
+    }
+}
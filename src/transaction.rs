@@ -0,0 +1,238 @@
+//! Writes that span several steps, holding the writer copy between them.
+
+use std::future;
+use std::ops::{Deref, DerefMut};
+use std::thread;
+
+use crate::state::State;
+use crate::{Movetex, PendingWrite, WriteError};
+
+/// A write in progress, returned by [`Movetex::begin_write`].
+///
+/// The transaction owns the writer copy until it is committed or dropped, so other writes fail
+/// with [`WriteError::Busy`] in the meantime, while readers keep seeing the published value.
+/// [`WriteTransaction::commit`] publishes the modified value like a `write` would; dropping
+/// the transaction without committing discards the modification and restores the writer copy
+/// from the published value. A panic while the transaction is open poisons the instance, like
+/// a panic in the closure of a `write`.
+pub struct WriteTransaction<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+    // Taken by `commit`
+    pending: Option<PendingWrite<'a, T>>,
+}
+
+/// Mutable access to the writer copy that publishes when dropped, returned by
+/// [`Movetex::try_write_guard`].
+///
+/// The guard owns the writer copy while it lives, like a [`WriteTransaction`], and publishes
+/// the value when it goes out of scope, including on an early return or `?`. A value the
+/// validator rejects is discarded like the modification of a rejected `write`; use
+/// [`WriteGuard::publish`] to learn whether the value was published. A panic while the guard is
+/// held poisons the instance instead of publishing.
+pub struct WriteGuard<'a, T: Clone> {
+    transaction: WriteTransaction<'a, T>,
+}
+
+impl<T: Clone> Movetex<T> {
+    /// Starts a write that gives mutable access to the writer copy until it is committed or
+    /// dropped, for modifications spanning several steps that may each fail.
+    ///
+    /// Returns `None` if another write is in progress, or if the instance is sealed, poisoned
+    /// or not initialized.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// fn apply(movetex: &Movetex<Vec<u32>>, input: &[&str]) -> Result<(), std::num::ParseIntError> {
+    ///     let mut tx = movetex.begin_write().expect("no concurrent writer");
+    ///     for item in input {
+    ///         // An early return drops `tx`, discarding the items pushed so far.
+    ///         tx.push(item.parse()?);
+    ///     }
+    ///     tx.commit().unwrap();
+    ///     Ok(())
+    /// }
+    ///
+    /// let movetex = Movetex::new(vec![]);
+    /// assert!(apply(&movetex, &["1", "x"]).is_err());
+    /// assert!(movetex.read().is_empty());
+    ///
+    /// apply(&movetex, &["1", "2"]).unwrap();
+    /// assert_eq!(*movetex.read(), [1, 2]);
+    /// ```
+    pub fn begin_write(&self) -> Option<WriteTransaction<'_, T>> {
+        let acquired = self
+            .state
+            .acquire_resting(State::Writing)
+            .map_err(|state| self.refuse_write(state))
+            .ok()?;
+        let pending = self.begin_write_acquired(acquired).ok()?;
+        Some(WriteTransaction::new(self, pending))
+    }
+
+    /// Same as [`Movetex::begin_write`], but waits for a write in progress to finish instead
+    /// of failing, without blocking the thread.
+    ///
+    /// The task is woken when the write owning the writer copy releases it, so awaiting this
+    /// in an async service takes no thread away from other tasks. Fails like
+    /// [`Movetex::try_write`] if the instance is sealed, poisoned or not initialized. Use
+    /// [`begin_write_all!`](crate::begin_write_all) to start writes on several instances at
+    /// once.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    /// # use std::future::Future;
+    /// # use std::pin::pin;
+    /// # use std::task::{Context, Poll, Waker};
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let mut future = pin!(future);
+    /// #     let mut cx = Context::from_waker(Waker::noop());
+    /// #     loop {
+    /// #         if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let movetex = Movetex::new(vec![1]);
+    /// block_on(async {
+    ///     let mut tx = movetex.begin_write_async().await.unwrap();
+    ///     tx.push(2);
+    ///     tx.commit().unwrap();
+    /// });
+    /// assert_eq!(*movetex.read(), [1, 2]);
+    /// ```
+    pub async fn begin_write_async(&self) -> Result<WriteTransaction<'_, T>, WriteError> {
+        let acquired = future::poll_fn(|cx| self.state.poll_acquire_resting(State::Writing, cx))
+            .await
+            .map_err(|state| self.refuse_write(state))?;
+        let pending = self.begin_write_acquired(acquired)?;
+        Ok(WriteTransaction::new(self, pending))
+    }
+
+    /// Returns a guard giving mutable access to the writer copy, which publishes the value when
+    /// dropped.
+    ///
+    /// Returns `None` if another write is in progress, or if the instance is sealed, poisoned
+    /// or not initialized.
+    ///
+    /// ```rust
+    /// use movetex::Movetex;
+    ///
+    /// fn normalize(list: &mut Vec<String>) {
+    ///     list.sort();
+    ///     list.dedup();
+    /// }
+    ///
+    /// let hosts = Movetex::new(vec![String::from("b"), String::from("a")]);
+    /// {
+    ///     let mut guard = hosts.try_write_guard().unwrap();
+    ///     guard.push(String::from("a"));
+    ///     normalize(&mut guard);
+    /// }
+    /// assert_eq!(*hosts.read(), ["a", "b"]);
+    /// ```
+    pub fn try_write_guard(&self) -> Option<WriteGuard<'_, T>> {
+        let transaction = self.begin_write()?;
+        Some(WriteGuard { transaction })
+    }
+}
+
+impl<'a, T: Clone> WriteTransaction<'a, T> {
+    pub(crate) fn new(movetex: &'a Movetex<T>, pending: PendingWrite<'a, T>) -> Self {
+        Self {
+            movetex,
+            pending: Some(pending),
+        }
+    }
+
+    /// Takes the write out of the transaction, for publishing it together with others.
+    pub(crate) fn into_parts(mut self) -> (&'a Movetex<T>, PendingWrite<'a, T>) {
+        let pending = self
+            .pending
+            .take()
+            .expect("a transaction is committed once");
+        (self.movetex, pending)
+    }
+
+    /// Publishes the modified value.
+    ///
+    /// Fails like [`Movetex::try_write`] if the validator rejects the value or the transaction
+    /// stayed open longer than the configured maximum write duration; the modification is
+    /// discarded in that case.
+    pub fn commit(mut self) -> Result<(), WriteError> {
+        self.publish()
+    }
+
+    /// Discards the modification; same as dropping the transaction.
+    pub fn abort(self) {}
+
+    fn publish(&mut self) -> Result<(), WriteError> {
+        let pending = self
+            .pending
+            .take()
+            .expect("a transaction is committed once");
+        self.movetex.finish_write(pending, |_| Ok(()), None)
+    }
+}
+
+impl<T: Clone> Deref for WriteTransaction<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.pending.as_ref().expect("transaction is open").slot
+    }
+}
+
+impl<T: Clone> DerefMut for WriteTransaction<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.pending
+            .as_mut()
+            .expect("transaction is open")
+            .slot
+            .make_mut()
+    }
+}
+
+impl<T: Clone> Drop for WriteTransaction<'_, T> {
+    fn drop(&mut self) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        // While unwinding, dropping the write poisons the instance.
+        if !thread::panicking() {
+            self.movetex.discard_write(pending);
+        }
+    }
+}
+
+impl<T: Clone> WriteGuard<'_, T> {
+    /// Publishes the value now, reporting why it was not published like
+    /// [`Movetex::try_write`].
+    pub fn publish(mut self) -> Result<(), WriteError> {
+        self.transaction.publish()
+    }
+}
+
+impl<T: Clone> Deref for WriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.transaction
+    }
+}
+
+impl<T: Clone> DerefMut for WriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.transaction
+    }
+}
+
+impl<T: Clone> Drop for WriteGuard<'_, T> {
+    fn drop(&mut self) {
+        // Already published by `WriteGuard::publish`, or poisoned when unwinding.
+        if self.transaction.pending.is_some() && !thread::panicking() {
+            let _ = self.transaction.publish();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Validation of candidate snapshots before they are published.
+
+use std::error::Error;
+use std::fmt;
+
+/// Checks a candidate value before `Movetex` publishes it to readers.
+///
+/// A validator is attached through [`MovetexBuilder::validator`](crate::MovetexBuilder::validator)
+/// and runs on every write after the closure has modified the private writer copy. If it
+/// returns an error, the modification is discarded: the writer copy is restored from the
+/// currently published value and readers never observe the rejected state.
+///
+/// Any `Fn(&T) -> Result<(), ValidationError>` closure implements this trait.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, ValidationError, WriteError};
+///
+/// let movetex = Movetex::builder(1u32)
+///     .validator(|v: &u32| {
+///         if *v == 0 {
+///             return Err(ValidationError::new("value must be non-zero"));
+///         }
+///         Ok(())
+///     })
+///     .build();
+///
+/// assert!(matches!(movetex.try_write(|v| *v = 0), Err(WriteError::Rejected(_))));
+/// assert_eq!(*movetex.read(), 1);
+/// ```
+pub trait Validator<T>: Send + Sync {
+    /// Returns an error if `candidate` must not be published.
+    fn validate(&self, candidate: &T) -> Result<(), ValidationError>;
+}
+
+impl<T, F> Validator<T> for F
+where
+    F: Fn(&T) -> Result<(), ValidationError> + Send + Sync,
+{
+    fn validate(&self, candidate: &T) -> Result<(), ValidationError> {
+        self(candidate)
+    }
+}
+
+/// The reason a [`Validator`] rejected a candidate value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    message: String,
+}
+
+impl ValidationError {
+    /// Creates an error with a human-readable description of the violation.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// The description of the violation.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "validation failed: {}", self.message)
+    }
+}
+
+impl Error for ValidationError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ValidationError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.message);
+    }
+}
+
+/// Validates the JSON form of candidate values against a JSON Schema (feature `jsonschema`).
+///
+/// Every candidate is serialized with `serde_json` and checked against the compiled schema, so
+/// invalid control-plane pushes are rejected before any reader can observe them.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{JsonSchemaValidator, Movetex};
+/// use serde_json::json;
+///
+/// let schema = json!({ "type": "integer", "minimum": 1 });
+/// let movetex = Movetex::builder(5)
+///     .validator(JsonSchemaValidator::new(&schema).unwrap())
+///     .build();
+///
+/// assert!(!movetex.write(|v| *v = 0));
+/// assert!(movetex.write(|v| *v = 7));
+/// assert_eq!(*movetex.read(), 7);
+/// ```
+#[cfg(feature = "jsonschema")]
+pub struct JsonSchemaValidator {
+    schema: jsonschema::Validator,
+}
+
+#[cfg(feature = "jsonschema")]
+impl JsonSchemaValidator {
+    /// Compiles `schema`, returning an error if it is not a valid JSON Schema.
+    pub fn new(schema: &serde_json::Value) -> Result<Self, ValidationError> {
+        jsonschema::validator_for(schema)
+            .map(|schema| Self { schema })
+            .map_err(|e| ValidationError::new(e.to_string()))
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+impl<T: serde::Serialize> Validator<T> for JsonSchemaValidator {
+    fn validate(&self, candidate: &T) -> Result<(), ValidationError> {
+        let instance =
+            serde_json::to_value(candidate).map_err(|e| ValidationError::new(e.to_string()))?;
+        self.schema
+            .validate(&instance)
+            .map_err(|e| ValidationError::new(e.to_string()))
+    }
+}
@@ -0,0 +1,590 @@
+//! Change notification for readers that wait for the next publish.
+//!
+//! Every publish notifies the [`PublishWatch`] of its instance after the new snapshot is
+//! stored. Subscribers remember the version of the snapshot they saw last and compare it with
+//! the published one, so a publish is never missed, and several publishes made while a
+//! subscriber was busy are observed as one change, like `tokio::sync::watch`.
+//!
+//! The versions seen by live subscriptions are also registered with the watch, so that
+//! [`Movetex::publish_with_backpressure`](crate::Movetex::publish_with_backpressure), and every
+//! publish while a subscription asks for [`Delivery::Block`], can hold a publish back until
+//! subscribers have caught up. Subscriptions asking for [`Delivery::DropOldest`] register a
+//! queue instead, into which every publish puts a hold on its snapshot.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use crate::clock::Deadline;
+use crate::diff::Diff;
+use crate::guard::SnapshotHold;
+use crate::snapshot::Snapshot;
+use crate::sync::atomic::{self, AtomicU64, AtomicUsize, Ordering};
+use crate::sync::{Condvar, Mutex};
+use crate::{Movetex, NotInitialized, ReadGuard};
+
+/// Wakes subscribers waiting for a publish.
+///
+/// The counter of registered waiters keeps publishing lock-free while nobody waits.
+pub(crate) struct PublishWatch {
+    // Number of parked threads plus registered wakers
+    waiting: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+    condvar: Condvar,
+    // Version last seen by each live subscription
+    subscribers: Mutex<Vec<Arc<Seen>>>,
+    // Number of live subscriptions with `Delivery::Block`
+    blocking: AtomicUsize,
+    // Number of writers waiting in `wait_caught_up`
+    pressing: AtomicUsize,
+    caught_up: Condvar,
+}
+
+/// The version a subscription has seen last.
+struct Seen {
+    version: AtomicU64,
+    // Holds every publish back until the subscription has caught up
+    blocks: bool,
+}
+
+impl PublishWatch {
+    pub(crate) fn new() -> Self {
+        Self {
+            waiting: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+            condvar: Condvar::new(),
+            subscribers: Mutex::new(Vec::new()),
+            blocking: AtomicUsize::new(0),
+            pressing: AtomicUsize::new(0),
+            caught_up: Condvar::new(),
+        }
+    }
+
+    /// Wakes every waiter; called after a new snapshot has been stored.
+    pub(crate) fn notify(&self) {
+        // Pairs with the fences in `wait_until` and `poll`: either the waiter sees the new
+        // snapshot, or this load sees it registered.
+        atomic::fence(Ordering::SeqCst);
+        if self.waiting.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        let wakers = std::mem::take(&mut *self.wakers.lock());
+        self.waiting.fetch_sub(wakers.len(), Ordering::Relaxed);
+        self.condvar.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Blocks until `changed` returns `true` or `deadline` passes; returns the last result.
+    fn wait_until(&self, changed: impl Fn() -> bool, deadline: Option<Deadline<'_>>) -> bool {
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        let mut wakers = self.wakers.lock();
+        let mut result = true;
+        while !changed() {
+            match deadline {
+                None => wakers = self.condvar.wait(wakers),
+                Some(deadline) => {
+                    let Some(sleep) = deadline.sleep() else {
+                        result = false;
+                        break;
+                    };
+                    wakers = self.condvar.wait_timeout(wakers, sleep).0;
+                }
+            }
+        }
+        drop(wakers);
+        self.waiting.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+
+    fn poll(&self, changed: impl Fn() -> bool, cx: &mut Context<'_>) -> Poll<()> {
+        if changed() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.wakers.lock();
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+            self.waiting.fetch_add(1, Ordering::Relaxed);
+        }
+        drop(wakers);
+        atomic::fence(Ordering::SeqCst);
+        // A publish between the first check and the registration did not see the waker.
+        if changed() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    /// Registers a subscription that has seen `version`.
+    fn subscribe(&self, version: u64, blocks: bool) -> Arc<Seen> {
+        let seen = Arc::new(Seen {
+            version: AtomicU64::new(version),
+            blocks,
+        });
+        self.subscribers.lock().push(Arc::clone(&seen));
+        if blocks {
+            self.blocking.fetch_add(1, Ordering::Relaxed);
+        }
+        seen
+    }
+
+    fn unsubscribe(&self, seen: &Arc<Seen>) {
+        self.subscribers.lock().retain(|s| !Arc::ptr_eq(s, seen));
+        if seen.blocks {
+            self.blocking.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.wake_pressing();
+    }
+
+    /// Records that a subscription has seen `version`.
+    fn mark_seen(&self, seen: &Seen, version: u64) {
+        seen.version.store(version, Ordering::Relaxed);
+        self.wake_pressing();
+    }
+
+    fn wake_pressing(&self) {
+        // Pairs with the fence in `wait_caught_up`: either the writer sees the new version, or
+        // this load sees it waiting.
+        atomic::fence(Ordering::SeqCst);
+        if self.pressing.load(Ordering::Relaxed) > 0 {
+            let _subscribers = self.subscribers.lock();
+            self.caught_up.notify_all();
+        }
+    }
+
+    /// The number of live subscriptions that have not seen `version` yet.
+    pub(crate) fn lagging(&self, version: u64) -> usize {
+        let subscribers = self.subscribers.lock();
+        count_lagging(&subscribers, version, false)
+    }
+
+    /// Blocks until every live subscription has seen `version` or `deadline` passes; returns
+    /// `true` if they have.
+    pub(crate) fn wait_caught_up(&self, version: u64, deadline: Option<Deadline<'_>>) -> bool {
+        self.wait_seen(version, deadline, false)
+    }
+
+    /// Blocks until every live subscription with [`Delivery::Block`] has seen `version`; called
+    /// before every publish.
+    pub(crate) fn wait_blocking(&self, version: u64) {
+        if self.blocking.load(Ordering::Relaxed) > 0 {
+            self.wait_seen(version, None, true);
+        }
+    }
+
+    fn wait_seen(&self, version: u64, deadline: Option<Deadline<'_>>, blocking: bool) -> bool {
+        self.pressing.fetch_add(1, Ordering::Relaxed);
+        atomic::fence(Ordering::SeqCst);
+        let mut subscribers = self.subscribers.lock();
+        let mut result = true;
+        while count_lagging(&subscribers, version, blocking) > 0 {
+            match deadline {
+                None => subscribers = self.caught_up.wait(subscribers),
+                Some(deadline) => {
+                    let Some(sleep) = deadline.sleep() else {
+                        result = false;
+                        break;
+                    };
+                    subscribers = self.caught_up.wait_timeout(subscribers, sleep).0;
+                }
+            }
+        }
+        drop(subscribers);
+        self.pressing.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+}
+
+/// Counts the subscriptions that have not seen `version`, only those with
+/// [`Delivery::Block`] if `blocking` is set.
+fn count_lagging(subscribers: &[Arc<Seen>], version: u64, blocking: bool) -> usize {
+    subscribers
+        .iter()
+        .filter(|seen| (seen.blocks || !blocking) && seen.version.load(Ordering::Relaxed) < version)
+        .count()
+}
+
+/// The queues of the subscriptions with [`Delivery::DropOldest`] of an instance.
+pub(crate) struct Deliveries<T> {
+    queues: Mutex<Vec<Arc<Queue<T>>>>,
+    // Number of registered queues, so publishes skip the lock while there are none
+    count: AtomicUsize,
+}
+
+impl<T> Deliveries<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            queues: Mutex::new(Vec::new()),
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Queues `snapshot` for every subscription with a queue, taking a hold on it for each;
+    /// the caller must own the writer copy of the instance that just published it, so it
+    /// cannot be retired meanwhile.
+    pub(crate) fn deliver(&self, snapshot: &Snapshot<T>) {
+        if self.count.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        for queue in self.queues.lock().iter() {
+            queue.push(snapshot);
+        }
+    }
+
+    fn register(&self, capacity: usize) -> Arc<Queue<T>> {
+        let queue = Arc::new(Queue {
+            capacity: capacity.max(1),
+            snapshots: Mutex::new(VecDeque::new()),
+            dropped: AtomicU64::new(0),
+        });
+        self.queues.lock().push(Arc::clone(&queue));
+        self.count.fetch_add(1, Ordering::Relaxed);
+        queue
+    }
+
+    fn unregister(&self, queue: &Arc<Queue<T>>) {
+        self.queues.lock().retain(|q| !Arc::ptr_eq(q, queue));
+        self.count.fetch_sub(1, Ordering::Relaxed);
+        queue.clear();
+    }
+}
+
+/// Published snapshots a subscription has not taken yet, oldest first.
+struct Queue<T> {
+    capacity: usize,
+    // Each with a hold taken for the queue, which keeps it alive
+    snapshots: Mutex<VecDeque<NonNull<Snapshot<T>>>>,
+    dropped: AtomicU64,
+}
+
+// The queue only hands its snapshots out as `&T`, through guards.
+unsafe impl<T: Send + Sync> Send for Queue<T> {}
+unsafe impl<T: Send + Sync> Sync for Queue<T> {}
+
+impl<T> Queue<T> {
+    fn push(&self, snapshot: &Snapshot<T>) {
+        snapshot.holds().hold();
+        let mut snapshots = self.snapshots.lock();
+        snapshots.push_back(NonNull::from(snapshot));
+        if snapshots.len() > self.capacity {
+            let oldest = snapshots.pop_front().expect("the queue is over capacity");
+            unsafe { oldest.as_ref() }.holds().release();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Takes the oldest snapshot newer than `seen`, along with the hold taken for it.
+    fn pop(&self, seen: u64) -> Option<NonNull<Snapshot<T>>> {
+        let mut snapshots = self.snapshots.lock();
+        while let Some(snapshot) = snapshots.pop_front() {
+            if unsafe { snapshot.as_ref() }.meta().version > seen {
+                return Some(snapshot);
+            }
+            unsafe { snapshot.as_ref() }.holds().release();
+        }
+        None
+    }
+
+    /// Releases the holds on every queued snapshot.
+    fn clear(&self) {
+        for snapshot in self.snapshots.lock().drain(..) {
+            unsafe { snapshot.as_ref() }.holds().release();
+        }
+    }
+}
+
+/// How a [`Subscription`] receives publishes made faster than it looks at them, set with
+/// [`Movetex::subscribe_with`](crate::Movetex::subscribe_with).
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delivery {
+    /// Observe the publishes made since the subscription looked last as a single change to
+    /// the latest value; writers never wait for the subscription.
+    #[default]
+    Coalesce,
+    /// Hold every publish back until the subscription has seen the published value, so it
+    /// observes every value in order.
+    ///
+    /// Writers wait while owning the writer copy, like with [`Backpressure::Wait`]: other
+    /// writes fail with [`WriteError::Busy`](crate::WriteError::Busy) meanwhile, and a thread
+    /// that writes to the instance while its own blocking subscription lags behind never
+    /// returns.
+    Block,
+    /// Queue up to the given number of published values, at least one, dropping the oldest
+    /// once the queue is full; writers never wait for the subscription.
+    ///
+    /// Queued values are not cloned: the queue keeps their snapshots alive like a
+    /// [`ReadGuard`] would, so each costs the memory of one published value until it is taken.
+    /// [`Subscription::dropped`] counts the values dropped from the queue.
+    DropOldest(usize),
+}
+
+/// What [`Movetex::publish_with_backpressure`](crate::Movetex::publish_with_backpressure) does
+/// about subscriptions that have not seen the published value yet.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Block until every subscription has seen the published value, then publish.
+    Wait,
+    /// Same as [`Backpressure::Wait`], but fail with
+    /// [`WriteError::Lagging`](crate::WriteError::Lagging) if subscriptions still lag behind
+    /// after the timeout.
+    WaitTimeout(Duration),
+    /// Publish anyway; lagging subscriptions skip the published value, like with a plain
+    /// write.
+    Skip,
+    /// Fail with [`WriteError::Lagging`](crate::WriteError::Lagging) if any subscription lags
+    /// behind.
+    Fail,
+}
+
+/// A receiver of the publishes of one instance, returned by
+/// [`Movetex::subscribe`](crate::Movetex::subscribe).
+///
+/// The subscription tracks the version it has seen last. [`Subscription::has_changed`] reports
+/// whether a newer value was published since, and [`Subscription::wait`] or
+/// [`Subscription::changed`] sleep until one is, instead of polling `read` in a loop. Several
+/// publishes made before the subscription looks again are observed as a single change to the
+/// latest value.
+///
+/// ```rust
+/// use movetex::Movetex;
+/// use std::thread;
+///
+/// let config = Movetex::new(1);
+/// let mut updates = config.subscribe();
+///
+/// thread::scope(|s| {
+///     s.spawn(|| config.write(|v| *v = 2));
+///     assert_eq!(*updates.wait(), 2);
+/// });
+/// assert!(!updates.has_changed());
+/// ```
+pub struct Subscription<'a, T: Clone> {
+    movetex: &'a Movetex<T>,
+    seen: Arc<Seen>,
+    // Registered for `Delivery::DropOldest`
+    queue: Option<Arc<Queue<T>>>,
+}
+
+impl<'a, T: Clone> Subscription<'a, T> {
+    pub(crate) fn new(movetex: &'a Movetex<T>, delivery: Delivery) -> Self {
+        // Registered first, so the queue misses no publish the version below does not cover.
+        let queue = match delivery {
+            Delivery::DropOldest(capacity) => Some(movetex.deliveries.register(capacity)),
+            Delivery::Coalesce | Delivery::Block => None,
+        };
+        let blocks = delivery == Delivery::Block;
+        Self {
+            movetex,
+            seen: movetex.watch.subscribe(movetex.version(), blocks),
+            queue,
+        }
+    }
+
+    /// Creates a subscription with [`Delivery::Coalesce`] that has seen `version`.
+    fn seen_at(movetex: &'a Movetex<T>, version: u64) -> Self {
+        Self {
+            movetex,
+            seen: movetex.watch.subscribe(version, false),
+            queue: None,
+        }
+    }
+
+    /// Returns `true` if a value was published since this subscription last marked one as
+    /// seen.
+    pub fn has_changed(&self) -> bool {
+        self.movetex.version() != self.seen_version()
+    }
+
+    /// The version of the value this subscription marked as seen last, its sequence number
+    /// in the publishes of the instance.
+    pub fn seen_version(&self) -> u64 {
+        self.seen.version.load(Ordering::Relaxed)
+    }
+
+    /// The number of publishes this subscription has not seen yet.
+    ///
+    /// With [`Delivery::DropOldest`] this includes the values still queued and those dropped
+    /// from the queue.
+    pub fn lag(&self) -> u64 {
+        self.movetex.version().saturating_sub(self.seen_version())
+    }
+
+    /// The number of values dropped from the queue of a subscription with
+    /// [`Delivery::DropOldest`] because it was full; always 0 with the other policies.
+    pub fn dropped(&self) -> u64 {
+        self.queue
+            .as_ref()
+            .map_or(0, |queue| queue.dropped.load(Ordering::Relaxed))
+    }
+
+    /// Reads the published value and marks it as seen.
+    ///
+    /// With [`Delivery::DropOldest`], returns the oldest queued value instead, if any, and marks
+    /// that one as seen.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`Movetex::read`] if the instance has not been initialized and no fallback
+    /// is configured.
+    pub fn borrow_and_update(&mut self) -> ReadGuard<'a, T> {
+        if let Some(snapshot) = self.queue.as_ref().and_then(|q| q.pop(self.seen_version())) {
+            // The hold taken by the queue moves into the guard.
+            let snapshot: &'a Snapshot<T> = unsafe { snapshot.as_ref() };
+            let hold = SnapshotHold::new(Some(snapshot.holds()));
+            self.movetex
+                .watch
+                .mark_seen(&self.seen, snapshot.meta().version);
+            return ReadGuard::new(snapshot.slot(), hold);
+        }
+        let (version, value) = self.movetex.read_versioned();
+        self.movetex.watch.mark_seen(&self.seen, version);
+        value
+    }
+
+    /// Blocks until a value newer than the last one seen is published, then reads it and marks
+    /// it as seen.
+    ///
+    /// Returns immediately if such a value is already published.
+    pub fn wait(&mut self) -> ReadGuard<'a, T> {
+        self.movetex.watch.wait_until(|| self.has_changed(), None);
+        self.borrow_and_update()
+    }
+
+    /// Same as [`Subscription::wait`], but returns `None` if nothing new was published within
+    /// `timeout`.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Option<ReadGuard<'a, T>> {
+        let deadline = Deadline::after(self.movetex.activity.clock(), timeout);
+        self.movetex
+            .watch
+            .wait_until(|| self.has_changed(), Some(deadline))
+            .then(|| self.borrow_and_update())
+    }
+
+    /// Returns a future that resolves once a value newer than the last one seen is published.
+    ///
+    /// The value is not marked as seen; call [`Subscription::borrow_and_update`] to read it.
+    pub fn changed(&self) -> Changed<'_, 'a, T> {
+        Changed { subscription: self }
+    }
+}
+
+impl<T: Clone> Drop for Subscription<'_, T> {
+    fn drop(&mut self) {
+        if let Some(queue) = &self.queue {
+            self.movetex.deliveries.unregister(queue);
+        }
+        self.movetex.watch.unsubscribe(&self.seen);
+    }
+}
+
+/// A [`Subscription`] that hands out what changed instead of the published value, returned by
+/// [`Movetex::subscribe_diffs`](crate::Movetex::subscribe_diffs).
+///
+/// The subscription keeps a guard on the value it saw last, and each update returns the
+/// [`Diff`] from that value to the published one. Several publishes made before the
+/// subscription looks again are observed as a single diff to the latest value. Keeping the
+/// guard costs the memory of one published value, which stays alive until the next update.
+///
+/// ```rust
+/// use movetex::{Diff, MapDiff, Movetex};
+/// use std::collections::BTreeMap;
+///
+/// let routes = Movetex::new(BTreeMap::from([("/a", 1), ("/b", 2)]));
+/// let mut updates = routes.subscribe_diffs().unwrap();
+/// let mut replica = routes.read().clone();
+///
+/// routes.write(|map| {
+///     map.insert("/a", 10);
+///     map.remove("/b");
+/// });
+/// let diff = updates.diff_and_update().unwrap();
+/// assert_eq!(diff, MapDiff { changed: vec![("/a", 10)], removed: vec!["/b"] });
+///
+/// replica.apply_diff(diff);
+/// assert_eq!(replica, *routes.read());
+/// assert!(updates.diff_and_update().is_none());
+/// ```
+pub struct DiffSubscription<'a, T: Clone + Diff> {
+    subscription: Subscription<'a, T>,
+    base: ReadGuard<'a, T>,
+}
+
+impl<'a, T: Clone + Diff> DiffSubscription<'a, T> {
+    pub(crate) fn new(movetex: &'a Movetex<T>) -> Result<Self, NotInitialized> {
+        let (version, base) = movetex.try_read_versioned()?;
+        Ok(Self {
+            subscription: Subscription::seen_at(movetex, version),
+            base,
+        })
+    }
+
+    /// Returns `true` if a value was published since this subscription last took a diff.
+    pub fn has_changed(&self) -> bool {
+        self.subscription.has_changed()
+    }
+
+    /// Returns the diff from the value seen last to the published one and marks the published
+    /// one as seen, or `None` if nothing was published since.
+    pub fn diff_and_update(&mut self) -> Option<T::Diff> {
+        self.has_changed().then(|| self.update())
+    }
+
+    /// Blocks until a value newer than the last one seen is published, then returns the diff
+    /// to it and marks it as seen.
+    ///
+    /// Returns immediately if such a value is already published.
+    pub fn wait(&mut self) -> T::Diff {
+        let movetex = self.subscription.movetex;
+        movetex.watch.wait_until(|| self.has_changed(), None);
+        self.update()
+    }
+
+    /// Same as [`DiffSubscription::wait`], but returns `None` if nothing new was published
+    /// within `timeout`.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Option<T::Diff> {
+        let movetex = self.subscription.movetex;
+        let deadline = Deadline::after(movetex.activity.clock(), timeout);
+        movetex
+            .watch
+            .wait_until(|| self.has_changed(), Some(deadline))
+            .then(|| self.update())
+    }
+
+    /// Returns a future that resolves once a value newer than the last one seen is published.
+    ///
+    /// The value is not marked as seen; call [`DiffSubscription::diff_and_update`] to take the
+    /// diff.
+    pub fn changed(&self) -> Changed<'_, 'a, T> {
+        self.subscription.changed()
+    }
+
+    fn update(&mut self) -> T::Diff {
+        let next = self.subscription.borrow_and_update();
+        let diff = self.base.diff(&next);
+        self.base = next;
+        diff
+    }
+}
+
+/// Future returned by [`Subscription::changed`].
+pub struct Changed<'s, 'a, T: Clone> {
+    subscription: &'s Subscription<'a, T>,
+}
+
+impl<T: Clone> Future for Changed<'_, '_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let subscription = self.subscription;
+        subscription
+            .movetex
+            .watch
+            .poll(|| subscription.has_changed(), cx)
+    }
+}
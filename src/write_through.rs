@@ -0,0 +1,209 @@
+//! A `Movetex` whose publishes are also persisted to an external store.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::sync::Mutex;
+use crate::{Movetex, ReadGuard, StateSink, StateSource, ValidationError, WriteError};
+
+/// An external store that every publish of a [`WriteThrough`] is written to, such as a
+/// database table or a file.
+///
+/// Stores backed by an async client can implement `persist` by blocking on their runtime, or by
+/// handing the value to a task that persists it and reports failures out of band.
+pub trait Store<T> {
+    /// The error reported when a value could not be persisted.
+    type Error;
+
+    /// Persists `value`. Calls are serialized by the [`WriteThrough`] that owns the store.
+    fn persist(&mut self, value: &T) -> Result<(), Self::Error>;
+}
+
+impl<T, E, F: FnMut(&T) -> Result<(), E>> Store<T> for F {
+    type Error = E;
+
+    fn persist(&mut self, value: &T) -> Result<(), E> {
+        self(value)
+    }
+}
+
+/// When a [`WriteThrough`] persists a value relative to publishing it.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PersistOrder {
+    /// Persist the modified value first, and publish it once it is stored. Readers never see
+    /// a value the store does not have.
+    #[default]
+    PersistThenPublish,
+    /// Publish first and persist afterwards. Readers see new values without waiting for the
+    /// store; a failure to persist cannot withdraw the published value. The store receives a
+    /// copy of the value the write published, even if another one is published before it is
+    /// persisted.
+    PublishThenPersist,
+}
+
+/// What a [`WriteThrough`] with [`PersistOrder::PersistThenPublish`] does when persisting fails.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PersistFailure {
+    /// Discard the modified value; readers keep the published one.
+    #[default]
+    Reject,
+    /// Publish the modified value anyway, and report the error.
+    Publish,
+}
+
+/// The reason a [`WriteThrough::write`] did not complete.
+#[derive(Debug)]
+pub enum WriteThroughError<E> {
+    /// The value was not published; see [`WriteError`].
+    Write(WriteError),
+    /// The store failed to persist the value. Whether it was published depends on the
+    /// [`PersistOrder`] and [`PersistFailure`] policy.
+    Persist(E),
+}
+
+impl<E: fmt::Display> fmt::Display for WriteThroughError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteThroughError::Write(e) => e.fmt(f),
+            WriteThroughError::Persist(e) => write!(f, "failed to persist value: {e}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for WriteThroughError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteThroughError::Write(e) => Some(e),
+            WriteThroughError::Persist(e) => Some(e),
+        }
+    }
+}
+
+/// A `Movetex` used as a cache in front of an external [`Store`]: every successful write is
+/// also persisted.
+///
+/// Writes are serialized through the store, so they never fail with [`WriteError::Busy`]
+/// against each other. Reads go to the `Movetex` and never touch the store.
+///
+/// ### Usage Example:
+/// ```rust
+/// use movetex::{Movetex, WriteThrough};
+///
+/// let settings = WriteThrough::new(Movetex::new(10u32), |value: &u32| {
+///     println!("UPDATE settings SET limit = {value}");
+///     Ok::<(), std::io::Error>(())
+/// });
+///
+/// settings.write(|limit| *limit = 20).unwrap();
+/// assert_eq!(*settings.read(), 20);
+/// ```
+pub struct WriteThrough<T: Clone, S> {
+    movetex: Movetex<T>,
+    store: Mutex<S>,
+    order: PersistOrder,
+    on_failure: PersistFailure,
+}
+
+impl<T: Clone, S: Store<T>> WriteThrough<T, S> {
+    /// Wraps `movetex`, persisting every value written through [`WriteThrough::write`] to
+    /// `store` before publishing it.
+    pub fn new(movetex: Movetex<T>, store: S) -> Self {
+        Self {
+            movetex,
+            store: Mutex::new(store),
+            order: PersistOrder::default(),
+            on_failure: PersistFailure::default(),
+        }
+    }
+
+    /// Sets whether values are persisted before or after they are published.
+    pub fn order(mut self, order: PersistOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets what happens when persisting fails before publishing.
+    pub fn on_failure(mut self, on_failure: PersistFailure) -> Self {
+        self.on_failure = on_failure;
+        self
+    }
+
+    /// Modifies the value like [`Movetex::try_write`], and persists the result.
+    pub fn write(&self, f: impl FnOnce(&mut T)) -> Result<(), WriteThroughError<S::Error>> {
+        let mut store = self.store.lock();
+        match self.order {
+            PersistOrder::PersistThenPublish => {
+                let mut failed = None;
+                let written = self.movetex.write_checked(
+                    f,
+                    |value| match store.persist(value) {
+                        Ok(()) => Ok(()),
+                        Err(e) => {
+                            failed = Some(e);
+                            match self.on_failure {
+                                PersistFailure::Reject => Err(WriteError::Rejected(
+                                    ValidationError::new("failed to persist value"),
+                                )),
+                                PersistFailure::Publish => Ok(()),
+                            }
+                        }
+                    },
+                    None,
+                );
+                match (written, failed) {
+                    (_, Some(e)) => Err(WriteThroughError::Persist(e)),
+                    (Err(e), None) => Err(WriteThroughError::Write(e)),
+                    (Ok(()), None) => Ok(()),
+                }
+            }
+            PersistOrder::PublishThenPersist => {
+                // A copy of the value this write publishes: a write made directly on the
+                // instance may publish another one before it is persisted.
+                let mut written = None;
+                self.movetex
+                    .write_checked(
+                        f,
+                        |value| {
+                            written = Some(value.clone());
+                            Ok(())
+                        },
+                        None,
+                    )
+                    .map_err(WriteThroughError::Write)?;
+                let written = written.expect("a successful write passes its check");
+                store.persist(&written).map_err(WriteThroughError::Persist)
+            }
+        }
+    }
+
+    /// Returns the published value, see [`Movetex::read`].
+    pub fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+
+    /// The wrapped instance. Values written to it directly are not persisted.
+    pub fn movetex(&self) -> &Movetex<T> {
+        &self.movetex
+    }
+
+    /// Returns the wrapped instance and the store.
+    pub fn into_inner(self) -> (Movetex<T>, S) {
+        (self.movetex, self.store.into_inner())
+    }
+}
+
+impl<T: Clone, S> StateSource<T> for WriteThrough<T, S> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.movetex.read()
+    }
+}
+
+impl<T: Clone, S: Store<T>> StateSink<T> for WriteThrough<T, S> {
+    type Error = WriteThroughError<S::Error>;
+
+    fn publish(&self, value: T) -> Result<(), Self::Error> {
+        self.write(|current| *current = value)
+    }
+}
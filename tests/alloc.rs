@@ -0,0 +1,50 @@
+use movetex::Movetex;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+mod alloc_tests {
+    use super::*;
+
+    /// Once an instance recycles snapshots, a write reuses the allocations of the writer copy
+    /// and of the snapshot it replaced, and allocates nothing.
+    #[test]
+    fn test_t_0() {
+        if cfg!(feature = "single-copy") {
+            return;
+        }
+        let movetex = Movetex::new([0u64; 4]);
+        for _ in 0..2 {
+            assert!(movetex.write(|v| v[0] += 1));
+        }
+        let before = allocations();
+        for _ in 0..100 {
+            assert!(movetex.write(|v| v[0] += 1));
+        }
+        assert_eq!(allocations(), before);
+        assert_eq!(movetex.read()[0], 102);
+    }
+}
@@ -0,0 +1,52 @@
+use movetex::{MovetexArc, WriteError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// Deliberately not `Clone`.
+struct Counter(AtomicUsize);
+
+mod movetex_arc_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = MovetexArc::new(Counter(AtomicUsize::new(1)));
+        let old = movetex.load();
+        movetex
+            .update(|c| Counter(AtomicUsize::new(c.0.load(Ordering::Relaxed) + 1)))
+            .unwrap();
+
+        // The loaded pointer still refers to the replaced value.
+        assert_eq!(old.0.load(Ordering::Relaxed), 1);
+        assert_eq!(movetex.read().0.load(Ordering::Relaxed), 2);
+
+        let shared = Arc::new(Counter(AtomicUsize::new(7)));
+        movetex.store_arc(Arc::clone(&shared)).unwrap();
+        assert!(Arc::ptr_eq(&movetex.load(), &shared));
+        assert!(Arc::ptr_eq(&movetex.into_inner(), &shared));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = MovetexArc::new(Counter(AtomicUsize::new(0)));
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..50 {
+                        movetex
+                            .rcu(|c| Counter(AtomicUsize::new(c.0.load(Ordering::Relaxed) + 1)))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(movetex.read().0.load(Ordering::Relaxed), 200);
+
+        assert!(movetex.movetex().seal());
+        assert_eq!(
+            movetex.store(Counter(AtomicUsize::new(0))),
+            Err(WriteError::Sealed)
+        );
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "arc-swap")]
+
+use arc_swap::ArcSwap;
+use movetex::{ArcSwapMirror, Movetex};
+use std::sync::Arc;
+
+mod conversion_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::from(ArcSwap::from_pointee(String::from("42")));
+        assert_eq!(*movetex.read(), "42");
+        assert!(movetex.write(|v| v.push('!')));
+
+        let arc_swap = movetex.into_arc_swap();
+        assert_eq!(**arc_swap.load(), "42!");
+    }
+}
+
+mod mirror_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let legacy = Arc::new(ArcSwap::from_pointee(vec![1, 2, 3]));
+        let mirror = ArcSwapMirror::new(Arc::clone(&legacy));
+
+        assert!(mirror.write(|v| v.push(4)));
+        assert_eq!(**legacy.load(), vec![1, 2, 3, 4]);
+        assert!(mirror.is_consistent());
+
+        legacy.store(Arc::new(vec![0]));
+        assert!(!mirror.is_consistent());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let legacy = Arc::new(ArcSwap::from_pointee(0));
+        let mirror = Arc::new(ArcSwapMirror::new(Arc::clone(&legacy)));
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let m = Arc::clone(&mirror);
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        while !m.write(|v| *v += 1) {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(**legacy.load(), 400);
+        assert!(mirror.is_consistent());
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "bench")]
+
+use movetex::bench::{self, Profile};
+use std::time::Duration;
+
+mod bench_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let profile = Profile::new(0u64, |v| *v += 1)
+            .readers(2)
+            .writers(0)
+            .duration(Duration::from_millis(20));
+        let report = bench::run(&profile);
+        for measurement in [&report.movetex, &report.mutex, &report.rw_lock] {
+            assert!(measurement.reads > 0);
+            assert_eq!(measurement.writes, 0);
+            assert!(measurement.read_latency.percentile(0.5) <= measurement.read_latency.max());
+        }
+    }
+
+    #[test]
+    fn test_t_1() {
+        let profile = Profile::new(vec![0u8; 64], |v| v[0] = v[0].wrapping_add(1))
+            .readers(0)
+            .writers(1)
+            .duration(Duration::from_millis(20));
+        let report = bench::run(&profile);
+        assert!(report.movetex.writes > 0);
+        assert!(report.mutex.write_throughput() > 0.0);
+        assert!(report.rw_lock.write_latency.count() > 0);
+    }
+}
@@ -0,0 +1,146 @@
+use movetex::{MockClock, Movetex, WriteError};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+mod write_blocking_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Arc::new(Movetex::new(0));
+        let (started, wait_started) = mpsc::channel();
+        let (finish, wait_finish) = mpsc::channel::<()>();
+
+        let slow = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                movetex.write(|v| {
+                    started.send(()).unwrap();
+                    wait_finish.recv().unwrap();
+                    *v += 1;
+                })
+            })
+        };
+        wait_started.recv().unwrap();
+        assert_eq!(movetex.try_write(|v| *v += 10), Err(WriteError::Busy));
+
+        let blocked = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || movetex.write_blocking(|v| *v += 10))
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!blocked.is_finished());
+
+        finish.send(()).unwrap();
+        assert!(slow.join().unwrap());
+        assert_eq!(blocked.join().unwrap(), Ok(()));
+        assert_eq!(*movetex.read(), 11);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        assert!(movetex.seal());
+        assert_eq!(movetex.write_blocking(|v| *v += 1), Err(WriteError::Sealed));
+
+        let uninit = Movetex::<u32>::uninit();
+        assert_eq!(
+            uninit.write_blocking(|v| *v += 1),
+            Err(WriteError::NotInitialized)
+        );
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(Vec::new());
+        thread::scope(|s| {
+            for i in 0..8 {
+                let movetex = &movetex;
+                s.spawn(move || {
+                    for j in 0..250 {
+                        movetex.write_blocking(|v| v.push(i * 250 + j)).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(movetex.read().len(), 2_000);
+    }
+}
+
+mod write_timeout_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Arc::new(Movetex::new(0));
+        let (started, wait_started) = mpsc::channel();
+        let (finish, wait_finish) = mpsc::channel::<()>();
+
+        let slow = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                movetex.write(|v| {
+                    started.send(()).unwrap();
+                    wait_finish.recv().unwrap();
+                    *v += 1;
+                })
+            })
+        };
+        wait_started.recv().unwrap();
+        assert!(!movetex.write_timeout(|_| unreachable!(), Duration::from_millis(20)));
+
+        let waiting = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || movetex.write_timeout(|v| *v += 10, Duration::from_secs(10)))
+        };
+        finish.send(()).unwrap();
+        assert!(slow.join().unwrap());
+        assert!(waiting.join().unwrap());
+        assert_eq!(*movetex.read(), 11);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        movetex.seal();
+        assert!(!movetex.write_timeout(|v| *v += 1, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_t_2() {
+        let clock = MockClock::new();
+        let movetex = Arc::new(Movetex::builder(0).clock(clock.clone()).build());
+        let (started, wait_started) = mpsc::channel();
+        let (finish, wait_finish) = mpsc::channel::<()>();
+
+        let slow = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                movetex.write(|v| {
+                    started.send(()).unwrap();
+                    wait_finish.recv().unwrap();
+                    *v += 1;
+                })
+            })
+        };
+        wait_started.recv().unwrap();
+
+        // The timeout is measured on the clock of the instance, so it ends once the clock is
+        // advanced past it rather than after an hour of real time.
+        let waiting = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                movetex.write_timeout(|_| unreachable!(), Duration::from_secs(3600))
+            })
+        };
+        thread::sleep(Duration::from_millis(20));
+        assert!(!waiting.is_finished());
+        clock.advance(Duration::from_secs(3601));
+        assert!(!waiting.join().unwrap());
+
+        finish.send(()).unwrap();
+        assert!(slow.join().unwrap());
+        assert_eq!(*movetex.read(), 1);
+    }
+}
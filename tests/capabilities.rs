@@ -0,0 +1,26 @@
+use movetex::{capabilities, Reclamation};
+
+mod capabilities_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let caps = capabilities();
+        assert_eq!(caps.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(caps.reclamation, Reclamation::EpochPins);
+        assert_eq!(caps.writer_copy, !cfg!(feature = "single-copy"));
+        assert_eq!(caps.async_runtime.is_some(), cfg!(feature = "tokio"));
+        assert_eq!(
+            caps.features.contains(&"single-copy"),
+            cfg!(feature = "single-copy")
+        );
+        assert!(caps.atomic_width >= 8);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let report = capabilities().to_string();
+        assert!(report.starts_with(&format!("movetex {}", env!("CARGO_PKG_VERSION"))));
+        assert!(report.contains("reclamation: EpochPins"));
+    }
+}
@@ -0,0 +1,150 @@
+use movetex::{CompareAndSwapError, Movetex, ValidationError, WriteError};
+use std::thread;
+
+mod compare_and_swap_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1u32);
+        assert_eq!(movetex.compare_and_swap(&1, 2), Ok(1));
+        assert_eq!(
+            movetex.compare_and_swap(&1, 3),
+            Err(CompareAndSwapError::Mismatch(3))
+        );
+        assert_eq!(*movetex.read(), 2);
+        assert_eq!(movetex.version(), 1);
+
+        let movetex = Movetex::<u32>::uninit();
+        assert_eq!(
+            movetex.compare_and_swap(&0, 1),
+            Err(CompareAndSwapError::Write(1, WriteError::NotInitialized))
+        );
+
+        let movetex = Movetex::new(1u32);
+        assert!(movetex.seal());
+        assert_eq!(
+            movetex.compare_and_swap(&1, 2),
+            Err(CompareAndSwapError::Write(2, WriteError::Sealed))
+        );
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(1u32)
+            .validator(|v: &u32| match v % 2 {
+                0 => Err(ValidationError::new("odd values only")),
+                _ => Ok(()),
+            })
+            .build();
+        let error = movetex.compare_and_swap(&1, 4).unwrap_err();
+        assert!(matches!(
+            error,
+            CompareAndSwapError::Write(4, WriteError::Rejected(_))
+        ));
+        assert_eq!(movetex.compare_and_swap(&1, 5), Ok(1));
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0u64);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..250 {
+                        loop {
+                            let seen = *movetex.read();
+                            if movetex.compare_and_swap(&seen, seen + 1).is_ok() {
+                                break;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(*movetex.read(), 1000);
+    }
+
+    #[test]
+    fn test_t_3() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        // A validator that is not deterministic rejects every other call.
+        let movetex = Movetex::builder(1u32)
+            .validator(|_: &u32| match CALLS.fetch_add(1, Ordering::Relaxed) % 2 {
+                0 => Ok(()),
+                _ => Err(ValidationError::new("flaky")),
+            })
+            .build();
+        assert_eq!(movetex.compare_and_swap(&1, 2), Ok(1));
+        assert_eq!(
+            movetex.compare_and_swap(&2, 3).map_err(|e| e.into_value()),
+            Err(3)
+        );
+        assert_eq!(*movetex.read(), 2);
+    }
+
+    #[test]
+    fn test_t_4() {
+        // A value swapped in is neither compared against nor replaced.
+        let movetex = Movetex::new(2u32);
+        assert_eq!(movetex.swap(7), Ok(2));
+        assert_eq!(
+            movetex.compare_and_swap(&2, 4),
+            Err(CompareAndSwapError::Pending(4))
+        );
+        assert_eq!(
+            movetex.compare_and_swap(&7, 4),
+            Err(CompareAndSwapError::Pending(4))
+        );
+        assert!(movetex.is_dirty());
+        assert!(movetex.publish());
+        assert_eq!(*movetex.read(), 7);
+    }
+}
+
+mod rcu_tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![0u32]);
+        thread::scope(|s| {
+            for i in 1..=4 {
+                let movetex = &movetex;
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        movetex
+                            .rcu(|v| v.iter().copied().chain([i]).collect())
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(movetex.read().len(), 401);
+        assert_eq!(movetex.version(), 400);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(1u32);
+        let calls = Cell::new(0);
+        movetex
+            .rcu(|v| {
+                calls.set(calls.get() + 1);
+                if calls.get() == 1 {
+                    // A publish between the read and the swap forces a retry.
+                    assert!(movetex.write(|v| *v = 10));
+                }
+                v * 2
+            })
+            .unwrap();
+        assert_eq!(calls.get(), 2);
+        assert_eq!(*movetex.read(), 20);
+
+        assert!(movetex.seal());
+        assert!(movetex.rcu(|v| v + 1).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use movetex::Movetex;
+
+mod checksum_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        movetex::set_invariant_handler(|violation| panic!("unexpected violation: {violation}"));
+        let movetex = Movetex::builder(vec![1, 2])
+            .checksum(|value| value.iter().sum())
+            .build();
+        assert_eq!(movetex.read().len(), 2);
+        assert!(movetex.write(|value| value.push(3)));
+        assert_eq!(*movetex.read(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<Vec<u64>>::uninit_builder()
+            .checksum(|value| value.iter().sum())
+            .build();
+        assert!(movetex.try_read().is_err());
+        movetex.initialize(vec![4]).unwrap();
+        assert_eq!(*movetex.read(), vec![4]);
+    }
+}
@@ -0,0 +1,118 @@
+use movetex::MovetexCopy;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+mod copy_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let counter = MovetexCopy::new(0u64);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..1_000 {
+                        while !counter.write(|v| *v += 1) {}
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.read(), 4_000);
+        assert_eq!(counter.swap(1), 4_000);
+        assert_eq!(counter.into_inner(), 1);
+    }
+
+    /// Readers never see a 16-byte value torn between two writes.
+    #[test]
+    fn test_t_1() {
+        let pair = MovetexCopy::new(0u128);
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..=10_000u128 {
+                    pair.store(i << 64 | i);
+                }
+            });
+            for _ in 0..2 {
+                s.spawn(|| {
+                    for _ in 0..10_000 {
+                        let value = pair.read();
+                        assert_eq!(value >> 64, value & u64::MAX as u128);
+                    }
+                });
+            }
+        });
+        assert_eq!(pair.read(), 10_000 << 64 | 10_000);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let signed = MovetexCopy::new(-5i8);
+        assert!(signed.write(|v| *v -= 1));
+        assert_eq!(signed.read(), -6);
+
+        let float = MovetexCopy::new(1.5f64);
+        float.store(-0.25);
+        assert_eq!(float.read(), -0.25);
+
+        let wide = MovetexCopy::new(i128::MIN);
+        assert_eq!(wide.swap(-1), i128::MIN);
+        assert_eq!(wide.read(), -1);
+
+        let letter = MovetexCopy::<char>::default();
+        letter.store('é');
+        assert_eq!(format!("{letter:?}"), "MovetexCopy('é')");
+    }
+
+    /// A panic during a 16-byte write leaves the previous value readable and writable.
+    #[test]
+    fn test_t_3() {
+        let wide = MovetexCopy::new(1u128);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            wide.write(|_| panic!("failed halfway"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(wide.read(), 1);
+        assert!(wide.write(|v| *v += 1));
+        assert_eq!(wide.read(), 2);
+    }
+
+    /// Each type is stored in an atomic integer of its own size.
+    #[test]
+    fn test_t_4() {
+        assert_eq!(mem::size_of::<MovetexCopy<u8>>(), 1);
+        assert_eq!(mem::size_of::<MovetexCopy<bool>>(), 1);
+        assert_eq!(mem::size_of::<MovetexCopy<i16>>(), 2);
+        assert_eq!(mem::size_of::<MovetexCopy<char>>(), 4);
+        assert_eq!(mem::size_of::<MovetexCopy<f64>>(), 8);
+        assert_eq!(
+            mem::size_of::<MovetexCopy<usize>>(),
+            mem::size_of::<usize>()
+        );
+        assert_eq!(
+            mem::size_of::<MovetexCopy<u128>>(),
+            16 + mem::size_of::<usize>()
+        );
+
+        let flag = MovetexCopy::new(false);
+        assert!(flag.write(|v| *v = !*v));
+        assert!(flag.read());
+        let small = MovetexCopy::new(u8::MAX);
+        assert!(small.write(|v| *v = v.wrapping_add(1)));
+        assert_eq!((small.read(), small.swap(7)), (0, 0));
+    }
+
+    /// A 16-byte write runs its closure before taking the sequence counter, so reads made
+    /// meanwhile do not wait for it, and a write landing in between makes it fail.
+    #[test]
+    fn test_t_5() {
+        let wide = MovetexCopy::new(1u128);
+        let published = wide.write(|v| {
+            assert_eq!(wide.read(), 1);
+            wide.store(5);
+            *v = 9;
+        });
+        assert!(!published);
+        assert_eq!(wide.read(), 5);
+    }
+}
@@ -0,0 +1,46 @@
+use movetex::{impl_clone_cost, CloneCost, Movetex, SpillPolicy};
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+struct Catalog {
+    items: HashMap<u32, String>,
+    shared: Arc<Vec<u8>>,
+    revision: u64,
+}
+
+impl_clone_cost!(Catalog {
+    items,
+    shared,
+    revision
+});
+
+mod clone_cost_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        assert_eq!(7u32.clone_cost(), 4);
+        assert_eq!(String::from("abc").clone_cost(), size_of::<String>() + 3);
+        assert_eq!(vec![1u64, 2].clone_cost(), size_of::<Vec<u64>>() + 16);
+        assert_eq!(
+            Arc::new(vec![0u8; 1024]).clone_cost(),
+            size_of::<Arc<Vec<u8>>>()
+        );
+        assert_eq!((1u8, 2u16).clone_cost(), 3);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(Catalog::default())
+            .spill(SpillPolicy::by_clone_cost(4096))
+            .build();
+        movetex.write(|c| c.shared = Arc::new(vec![0; 1 << 20]));
+        assert!(!movetex.is_spilled());
+
+        movetex.write(|c| c.items.extend((0..100).map(|i| (i, "x".repeat(64)))));
+        assert!(movetex.is_spilled());
+        assert_eq!(movetex.read().revision, 0);
+    }
+}
@@ -0,0 +1,49 @@
+use movetex::{Movetex, WriteError};
+
+mod cow_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1, 2]);
+        let mut view = movetex.read_cow();
+        assert!(!view.is_owned());
+        assert_eq!(*view, [1, 2]);
+
+        view.push(3);
+        assert!(view.is_owned());
+        assert_eq!(*movetex.read(), [1, 2]);
+        assert_eq!(view.commit(), Ok(true));
+        assert_eq!(*movetex.read(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        let view = movetex.read_cow();
+        assert_eq!(view.commit(), Ok(false));
+        assert_eq!(movetex.version(), 0);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0);
+        let mut view = movetex.read_cow();
+        *view.to_mut() += 10;
+        assert!(movetex.write(|v| *v += 1));
+
+        assert_eq!(view.commit(), Err(WriteError::Conflict));
+        assert_eq!(*movetex.read(), 1);
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(*movetex.read(), 2);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let movetex = Movetex::new(0);
+        let mut view = movetex.read_cow();
+        *view = 5;
+        movetex.seal();
+        assert_eq!(view.commit(), Err(WriteError::Sealed));
+    }
+}
@@ -0,0 +1,63 @@
+use movetex::{Journal, Movetex, WriteThroughError};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Event {
+    Add(i64),
+    Reset,
+}
+
+fn apply(total: &mut i64, event: Event) {
+    match event {
+        Event::Add(n) => *total += n,
+        Event::Reset => *total = 0,
+    }
+}
+
+/// Accepts a fixed number of events, then reports the log as full.
+struct Bounded {
+    events: Vec<Event>,
+    capacity: usize,
+}
+
+impl Journal<Event> for Bounded {
+    type Error = &'static str;
+
+    fn append(&mut self, event: &Event) -> Result<(), &'static str> {
+        if self.events.len() == self.capacity {
+            return Err("log full");
+        }
+        self.events.push(event.clone());
+        Ok(())
+    }
+}
+
+mod event_sourced_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let cell = Movetex::from_events(0, [Event::Add(3), Event::Reset, Event::Add(4)], apply);
+        assert_eq!(*cell.read(), 4);
+
+        cell.append_event(Event::Add(1)).unwrap();
+        cell.append_event(Event::Add(2)).unwrap();
+        let (movetex, journal) = cell.into_inner();
+        assert_eq!(*movetex.read(), 7);
+        assert_eq!(journal, [Event::Add(1), Event::Add(2)]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let cell = Movetex::from_events(10, [], apply).with_journal(Bounded {
+            events: Vec::new(),
+            capacity: 1,
+        });
+        cell.append_event(Event::Add(1)).unwrap();
+        assert!(matches!(
+            cell.append_event(Event::Add(1)),
+            Err(WriteThroughError::Persist("log full"))
+        ));
+        assert_eq!(*cell.read(), 11);
+        assert_eq!(cell.into_inner().1.events, [Event::Add(1)]);
+    }
+}
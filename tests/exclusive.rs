@@ -0,0 +1,51 @@
+use movetex::{Movetex, SpillPolicy};
+
+mod get_mut_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let mut movetex = Movetex::builder(vec![1u64])
+            .checksum(|value| value.iter().sum())
+            .build();
+        movetex.get_mut().push(2);
+        assert!(movetex.verify_published());
+
+        // The next write starts from the modified value.
+        assert!(movetex.write(|v| v.push(3)));
+        assert_eq!(*movetex.read(), [1, 2, 3]);
+        assert_eq!(movetex.version(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let mut movetex = Movetex::new(1);
+        assert_eq!(movetex.swap(10), Ok(1));
+        *movetex.get_mut() = 2;
+        assert_eq!(*movetex.read(), 2);
+
+        // A pending swap is kept.
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(*movetex.read(), 11);
+    }
+}
+
+mod into_inner_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1u32])
+            .spill(SpillPolicy::always())
+            .clone_ahead()
+            .build();
+        assert!(movetex.write(|v| v.push(2)));
+        assert_eq!(movetex.into_inner(), [1, 2]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit_builder().fallback(7).build();
+        assert_eq!(movetex.into_inner(), 7);
+    }
+}
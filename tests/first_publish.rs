@@ -0,0 +1,60 @@
+use movetex::Movetex;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+use std::time::Duration;
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+mod first_publish_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1);
+        assert!(movetex.is_published());
+        assert!(movetex.wait_first_publish(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::<Option<u32>>::empty());
+        assert!(!movetex.is_published());
+        assert_eq!(*movetex.read(), None);
+
+        std::thread::scope(|s| {
+            let m = Arc::clone(&movetex);
+            s.spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                assert!(m.write(|v| *v = Some(42)));
+            });
+
+            assert!(movetex.wait_first_publish(Duration::from_secs(10)));
+        });
+        assert_eq!(*movetex.read(), Some(42));
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::<Option<u32>>::empty();
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = pin!(movetex.first_publish());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+
+        assert!(movetex.write(|v| *v = Some(1)));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(future.as_mut().poll(&mut cx).is_ready());
+    }
+}
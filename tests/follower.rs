@@ -0,0 +1,104 @@
+#![cfg(feature = "follower")]
+
+use futures_core::Stream;
+use movetex::{Follower, WriteError};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Yields `Pending` once before every item, like a network stream would.
+struct Remote<T> {
+    items: VecDeque<T>,
+    ready: bool,
+}
+
+impl<T: Unpin> Stream for Remote<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        Poll::Ready(self.items.pop_front())
+    }
+}
+
+mod follower_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let follower = Follower::new(String::from("local"));
+        let remote = Remote {
+            items: VecDeque::from([String::from("v1"), String::from("v2")]),
+            ready: false,
+        };
+
+        assert_eq!(*follower.read(), "local");
+        assert_eq!(block_on(follower.follow(remote)), Ok(2));
+        assert_eq!(*follower.read(), "v2");
+    }
+
+    static ARMED: AtomicBool = AtomicBool::new(true);
+
+    #[derive(Clone)]
+    struct Fragile(bool);
+
+    impl Drop for Fragile {
+        fn drop(&mut self) {
+            if self.0 && ARMED.swap(false, Ordering::Relaxed) {
+                panic!("fragile value dropped");
+            }
+        }
+    }
+
+    #[test]
+    fn test_t_1() {
+        let follower = Follower::new(Fragile(false));
+        let first = Remote {
+            items: VecDeque::from([Fragile(true)]),
+            ready: false,
+        };
+        assert_eq!(block_on(follower.follow(first)), Ok(1));
+
+        // Replacing the fragile value panics and poisons the follower.
+        let second = Remote {
+            items: VecDeque::from([Fragile(false)]),
+            ready: false,
+        };
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            block_on(follower.follow(second))
+        }));
+        assert!(panicked.is_err());
+
+        // Later items end the future with the error instead of spinning in `poll`.
+        let third = Remote {
+            items: VecDeque::from([Fragile(false)]),
+            ready: false,
+        };
+        assert_eq!(block_on(follower.follow(third)), Err(WriteError::Poisoned));
+    }
+}
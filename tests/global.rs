@@ -0,0 +1,24 @@
+use movetex::global;
+use std::thread;
+
+#[derive(Clone, Default, Debug, PartialEq)]
+struct Counter(u64);
+
+#[derive(Clone, Default, Debug, PartialEq)]
+struct Name(String);
+
+mod global_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        assert!(global::<Counter>().write(|c| c.0 += 1));
+        assert!(std::ptr::eq(global::<Counter>(), global::<Counter>()));
+        assert_eq!(*global::<Name>().read(), Name::default());
+
+        let seen = thread::spawn(|| global::<Counter>().read().0)
+            .join()
+            .unwrap();
+        assert_eq!(seen, 1);
+    }
+}
@@ -0,0 +1,211 @@
+use movetex::{Movetex, OwnedReadGuard, ReadGuard};
+use std::sync::Arc;
+use std::sync::Mutex;
+
+static RETIRED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+mod guard_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(1u32)
+            .on_retire(|value| RETIRED.lock().unwrap().push(value))
+            .build();
+
+        let first = movetex.read();
+        assert!(movetex.write(|v| *v = 2));
+        let second = movetex.read();
+        assert!(movetex.write(|v| *v = 3));
+
+        // Both replaced values are still visible through a guard.
+        assert_eq!((*first, *second), (1, 2));
+        assert!(RETIRED.lock().unwrap().is_empty());
+
+        // Dropping a guard reclaims nothing; the next publish reclaims each value whose last
+        // guard is gone, regardless of the other guard.
+        drop(first);
+        assert!(RETIRED.lock().unwrap().is_empty());
+        assert!(movetex.write(|v| *v = 4));
+        assert_eq!(*RETIRED.lock().unwrap(), [1, 3]);
+        drop(second);
+        assert!(movetex.write(|v| *v = 5));
+        assert_eq!(*RETIRED.lock().unwrap(), [1, 3, 2, 4]);
+        assert_eq!(*movetex.read(), 5);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(vec![(1, "a"), (2, "b")]);
+        let name = ReadGuard::map(movetex.read(), |pairs| pairs[1].1);
+        assert_eq!(&*name, "b");
+
+        let missing = ReadGuard::filter_map(movetex.read(), |pairs| pairs.get(5));
+        let guard = missing.unwrap_err();
+        assert_eq!(guard.len(), 2);
+        assert_eq!(movetex.active_readers(), 2);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(String::from("a"));
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..1_000 {
+                    while !movetex.write(|v| v.push('a')) {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..1_000 {
+                        let value = movetex.read();
+                        assert!(value.bytes().all(|b| b == b'a'));
+                    }
+                });
+            }
+        });
+        assert_eq!(movetex.read().len(), 1_001);
+        assert_eq!(movetex.active_readers(), 0);
+    }
+}
+
+mod reclaim_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    static RECLAIMED: AtomicUsize = AtomicUsize::new(0);
+    static SHARED_RECLAIMED: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(0u32)
+            .on_retire(|_| {
+                RECLAIMED.fetch_add(1, Ordering::Relaxed);
+            })
+            .build();
+
+        // Every read starts before the previous guard is dropped, so a reader is always active.
+        let mut guard = movetex.read();
+        for i in 1..=1_000 {
+            assert!(movetex.write(|v| *v = i));
+            guard = movetex.read();
+        }
+        assert_eq!(*guard, 1_000);
+        // Only the value behind the last replaced guard may still be waiting.
+        assert!(RECLAIMED.load(Ordering::Relaxed) >= 999);
+        assert_eq!(movetex.active_readers(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(0u64)
+            .on_retire(|_| {
+                SHARED_RECLAIMED.fetch_add(1, Ordering::Relaxed);
+            })
+            .build();
+        let leaked = movetex.read();
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    let mut guard = movetex.read();
+                    while !done.load(Ordering::Relaxed) {
+                        let next = movetex.read();
+                        assert!(*next >= *guard);
+                        guard = next;
+                    }
+                });
+            }
+            for i in 1..=10_000 {
+                while !movetex.write(|v| *v = i) {
+                    std::thread::yield_now();
+                }
+            }
+            // Reclamation goes on while readers overlap and a guard is held throughout.
+            let reclaimed = SHARED_RECLAIMED.load(Ordering::Relaxed);
+            done.store(true, Ordering::Relaxed);
+            assert!(reclaimed > 0);
+        });
+        // Once the readers are gone, the next publish reclaims every replaced value except the
+        // one the remaining guard holds.
+        assert!(movetex.write(|v| *v = 10_001));
+        assert_eq!(SHARED_RECLAIMED.load(Ordering::Relaxed), 10_000);
+        assert_eq!(*leaked, 0);
+    }
+}
+
+mod owned_guard_tests {
+    use super::*;
+
+    fn assert_send<T: Send + 'static>(_: &T) {}
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Arc::new(Movetex::new(String::from("a")));
+        let guard = movetex.read_owned();
+        assert_send(&guard);
+        movetex.write(|v| v.push('b'));
+
+        let value = std::thread::spawn(move || guard.clone()).join().unwrap();
+        assert_eq!(value, "a");
+        assert_eq!(movetex.active_readers(), 0);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::new(1u32));
+        let guard = movetex.read_owned();
+        assert!(Arc::ptr_eq(OwnedReadGuard::movetex(&guard), &movetex));
+        assert_eq!(movetex.active_readers(), 1);
+        drop(movetex);
+        assert_eq!(*guard, 1);
+    }
+}
+
+mod snapshot_tests {
+    use super::*;
+    use movetex::SpillPolicy;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(String::from("a"));
+        let snapshot = movetex.snapshot();
+        movetex.write(|v| v.push('b'));
+        let len = thread::spawn(move || snapshot.len()).join().unwrap();
+        assert_eq!(len, 1);
+
+        let arc = movetex.snapshot_arc();
+        assert_eq!(*arc, "ab");
+        assert!(!Arc::ptr_eq(&arc, &movetex.snapshot_arc()));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(vec![1])
+            .spill(SpillPolicy::always())
+            .build();
+        let before = movetex.snapshot_arc();
+        assert!(Arc::ptr_eq(&before, &movetex.snapshot_arc()));
+        movetex.write(|v| v.push(2));
+        assert_eq!(*before, [1]);
+        assert_eq!(*movetex.snapshot_arc(), [1, 2]);
+    }
+}
+
+mod read_map_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new((String::from("api"), vec![1, 2, 3]));
+        assert_eq!(movetex.read_map(|(_, ports)| ports.len()), 3);
+        let name = movetex.readers().read_map(|(name, _)| name.clone());
+        assert_eq!(name, "api");
+        // The snapshot is released once the closure returns.
+        assert_eq!(movetex.active_readers(), 0);
+    }
+}
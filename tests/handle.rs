@@ -0,0 +1,132 @@
+use movetex::{Movetex, ReadOnly, StateSink, StateSource, WriteError, WriteOnly};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static SALT: AtomicU64 = AtomicU64::new(0);
+
+fn load(writer: WriteOnly<'_, Vec<u32>>, values: &[u32]) -> Result<(), WriteError> {
+    writer.write(|v| v.extend_from_slice(values))
+}
+
+fn total(readers: ReadOnly<'_, Vec<u32>>) -> u32 {
+    readers.read().iter().sum()
+}
+
+mod handle_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1]);
+        let (readers, writer) = (movetex.readers(), movetex.writer());
+        load(writer, &[2, 3]).unwrap();
+        assert_eq!(total(readers), 6);
+        assert_eq!(readers.version(), 1);
+
+        assert_eq!(writer.write_with(|v| v.pop()), Ok(Some(3)));
+        assert_eq!(writer.swap(vec![7]), Ok(vec![1, 2]));
+        assert!(writer.publish());
+        assert_eq!(*readers.read(), [7]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0u32);
+        let readers = movetex.readers();
+        let mut subscription = readers.subscribe();
+        StateSink::publish(&movetex.writer(), 5).unwrap();
+        assert!(subscription.has_changed());
+        assert_eq!(*StateSource::read(&readers), 5);
+        assert_eq!(*subscription.borrow_and_update(), 5);
+    }
+}
+
+mod split_tests {
+    use super::*;
+    use movetex::ValidationError;
+    use std::thread;
+
+    #[test]
+    fn test_t_0() {
+        let (reader, mut writer) = Movetex::new(vec![1]).split();
+        let other = writer.reader();
+        writer.write(|v| v.push(2));
+        assert_eq!(*reader.read(), [1, 2]);
+        assert_eq!(other.version(), 1);
+        assert_eq!(total(reader.readers()), 3);
+
+        assert_eq!(writer.swap(vec![5]), Ok(vec![1, 2]));
+        assert!(writer.publish());
+        assert_eq!(*StateSource::read(&other), [5]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let (reader, mut writer) = Movetex::new(0u32).split();
+        thread::scope(|s| {
+            for _ in 0..4 {
+                let reader = reader.clone();
+                s.spawn(move || {
+                    let mut last = 0;
+                    for _ in 0..1_000 {
+                        let current = *reader.read();
+                        assert!(current >= last);
+                        last = current;
+                    }
+                });
+            }
+            for _ in 0..1_000 {
+                writer.try_write(|v| *v += 1).unwrap();
+            }
+        });
+        assert_eq!(*reader.read(), 1_000);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::builder(0u32)
+            .validator(|v: &u32| match v {
+                0..=9 => Ok(()),
+                _ => Err(ValidationError::new("too large")),
+            })
+            .build();
+        let (reader, mut writer) = movetex.split();
+        writer.write(|v| *v = 10);
+        assert_eq!(*reader.read(), 0);
+        assert!(matches!(
+            writer.try_write(|v| *v = 10),
+            Err(WriteError::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_t_3() {
+        movetex::set_invariant_handler(|_| {});
+        let movetex = Movetex::builder(0u64)
+            .checksum(|value| value ^ SALT.load(Ordering::SeqCst))
+            .repair(|value| Some(*value))
+            .build();
+        let (reader, mut writer) = movetex.split();
+        let done = AtomicBool::new(false);
+        let results: Vec<_> = thread::scope(|s| {
+            for _ in 0..4 {
+                let (reader, done) = (reader.clone(), &done);
+                s.spawn(move || {
+                    while !done.load(Ordering::SeqCst) {
+                        let _ = *reader.read();
+                    }
+                });
+            }
+            // Each new salt makes the published checksum stale, so readers leave repairs for
+            // the writer to apply while they keep reading.
+            let results = (0..10_000)
+                .map(|_| {
+                    SALT.fetch_add(1, Ordering::SeqCst);
+                    writer.try_write(|v| *v += 1)
+                })
+                .collect();
+            done.store(true, Ordering::SeqCst);
+            results
+        });
+        assert!(results.iter().all(Result::is_ok));
+    }
+}
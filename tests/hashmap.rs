@@ -15,7 +15,7 @@ fn generate_random_string() -> String {
 
 #[test]
 fn test_movetex_parallel_write_consistency() {
-    // Эталонный результат с последовательной загрузкой
+    // Reference result, filled sequentially
     let mut reference_map = HashMap::new();
     let mut prefilled_values = Vec::new();
 
@@ -28,7 +28,7 @@ fn test_movetex_parallel_write_consistency() {
         }
     }
 
-    // Movetex для параллельной загрузки
+    // Movetex filled in parallel
     let movetex_map = Arc::new(Movetex::new(HashMap::new()));
 
     let movetex_map_writer = Arc::clone(&movetex_map);
@@ -37,7 +37,7 @@ fn test_movetex_parallel_write_consistency() {
     // Barrier to synchronize threads
     let barrier = Arc::new(Barrier::new(11)); // 10 writer threads + 1 main thread
 
-    // Создаем 10 потоков, каждый записывает по 1000 элементов
+    // Spawn 10 threads, each writing 1000 entries
     thread::scope(|s| {
         for i in 0..10 {
             let m = Arc::clone(&movetex_map_writer);
@@ -63,12 +63,12 @@ fn test_movetex_parallel_write_consistency() {
         println!("All writes to movetex are completed.");
     });
 
-    // Чтение через 5 секунд и проверка идентичности
+    // Read after 5 seconds and check that both maps are identical
     let start_time = Instant::now();
     let time_limit = Duration::from_secs(60);
 
     let reader_thread = thread::spawn(move || {
-        thread::sleep(Duration::from_secs(5)); // Задержка перед началом чтения
+        thread::sleep(Duration::from_secs(5)); // Delay before reading
         loop {
             let elapsed = start_time.elapsed();
             if elapsed > time_limit {
@@ -0,0 +1,90 @@
+use movetex::{HealthCheck, HealthPolicy, MockClock, Movetex};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod health_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::<Option<u32>>::empty();
+        let health = movetex.health(&HealthPolicy::default());
+        assert_eq!(health.last_publish_age, None);
+        assert!(!health.is_healthy());
+
+        movetex.write(|v| *v = Some(1));
+        assert!(movetex.health(&HealthPolicy::default()).is_healthy());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let clock = MockClock::new();
+        let movetex = Movetex::builder(1).clock(clock.clone()).build();
+        let policy = HealthPolicy {
+            max_publish_age: Some(Duration::from_millis(10)),
+            ..HealthPolicy::default()
+        };
+
+        clock.advance(Duration::from_millis(20));
+        let health = movetex.health(&policy);
+        assert!(health.stale);
+        assert!(!health.is_healthy());
+
+        movetex.write(|v| *v = 2);
+        assert!(movetex.health(&policy).is_healthy());
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Arc::new(Movetex::new(1));
+        let policy = HealthPolicy {
+            max_write_hold: Duration::from_millis(10),
+            ..HealthPolicy::default()
+        };
+
+        std::thread::scope(|s| {
+            let m = Arc::clone(&movetex);
+            s.spawn(move || {
+                m.write(|_| std::thread::sleep(Duration::from_millis(300)));
+            });
+
+            std::thread::sleep(Duration::from_millis(100));
+            let health = movetex.health(&policy);
+            assert!(health.write_held_for.is_some());
+            assert!(health.writer_stuck);
+        });
+
+        assert!(!movetex.health(&policy).writer_stuck);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let movetex = Movetex::new(1);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            movetex.write(|_| panic!("update failed halfway"));
+        }));
+        assert!(result.is_err());
+
+        let health = movetex.health(&HealthPolicy::default());
+        assert!(health.poisoned);
+        assert!(!health.is_healthy());
+
+        assert!(movetex.clear_poison());
+        assert!(movetex.health(&HealthPolicy::default()).is_healthy());
+    }
+
+    #[test]
+    fn test_t_4() {
+        let movetex = Movetex::new(1);
+        let mut subscription = movetex.subscribe();
+        movetex.write(|v| *v = 2);
+
+        let health = movetex.health(&HealthPolicy::default());
+        assert_eq!(health.subscriber_lag, 1);
+        assert!(health.is_healthy());
+
+        assert_eq!(*subscription.borrow_and_update(), 2);
+        assert_eq!(movetex.health(&HealthPolicy::default()).subscriber_lag, 0);
+    }
+}
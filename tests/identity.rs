@@ -0,0 +1,108 @@
+use movetex::Movetex;
+use std::sync::Arc;
+use std::thread;
+
+mod identity_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(0);
+        assert!(movetex.write_as("ignored", |v| *v = 1));
+        assert_eq!(movetex.last_writer(), None);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::builder(0).track_writers(true).build());
+        assert_eq!(movetex.last_writer(), None);
+
+        let m = Arc::clone(&movetex);
+        thread::Builder::new()
+            .name(String::from("reloader"))
+            .spawn(move || assert!(m.write_as("config-push", |v| *v = 1)))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        let writer = movetex.last_writer().unwrap();
+        assert_eq!(writer.thread_name.as_deref(), Some("reloader"));
+        assert_eq!(writer.label.as_deref(), Some("config-push"));
+        assert_ne!(writer.thread, thread::current().id());
+
+        assert!(movetex.write(|v| *v = 2));
+        let writer = movetex.last_writer().unwrap();
+        assert_eq!(writer.thread, thread::current().id());
+        assert_eq!(writer.label, None);
+    }
+}
+
+mod read_meta_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(0);
+        let (value, meta) = movetex.read_meta();
+        assert_eq!(
+            (*value, meta.version, meta.writer_label.clone()),
+            (0, 0, None)
+        );
+
+        movetex.write(|v| *v = 1);
+        movetex.write_as("reload", |v| *v = 2);
+        let (value, meta) = movetex.read_meta();
+        assert_eq!(*value, 2);
+        assert_eq!(meta.version, 2);
+        assert_eq!(meta.writer_label.as_deref(), Some("reload"));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit();
+        movetex.initialize(5).unwrap();
+        let (value, meta) = movetex.read_meta();
+        assert_eq!((*value, meta.version), (5, 1));
+        assert!(meta.published_at <= std::time::SystemTime::now());
+    }
+}
+
+mod versioned_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(String::from("a"));
+        let (version, value) = movetex.read_versioned();
+        assert_eq!((version, value.as_str()), (0, "a"));
+        assert_eq!(movetex.version(), 0);
+
+        movetex.write(|v| v.push('b'));
+        movetex.write(|v| v.push('c'));
+        let (version, value) = movetex.read_versioned();
+        assert_eq!((version, value.as_str()), (2, "abc"));
+        assert_eq!(movetex.version(), 2);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::new(0u64));
+        let writer = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    movetex.write(|v| *v += 1);
+                }
+            })
+        };
+        let mut last = 0;
+        while !writer.is_finished() {
+            let (version, value) = movetex.read_versioned();
+            assert!(version >= last);
+            assert_eq!(*value, version);
+            last = version;
+        }
+        writer.join().unwrap();
+        assert_eq!(movetex.version(), 1000);
+    }
+}
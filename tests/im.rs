@@ -0,0 +1,49 @@
+#![cfg(feature = "im")]
+
+use movetex::{CloneCost, MovetexIm};
+use std::thread;
+
+mod movetex_im_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let map = MovetexIm::new();
+        assert!(map.is_empty());
+        for key in 0..1000u32 {
+            assert_eq!(map.insert(key, key * 2), Ok(None));
+        }
+        assert_eq!(map.insert(7, 0), Ok(Some(14)));
+        let before = map.snapshot();
+
+        assert_eq!(map.remove(&7), Ok(Some(0)));
+        assert_eq!(map.remove(&7), Ok(None));
+        assert!(!map.contains_key(&7));
+        assert_eq!(map.get(&8).as_deref(), Some(&16));
+        assert_eq!((map.len(), before.len()), (999, 1000));
+
+        map.update(|m| m.retain(|key, _| key % 2 == 0)).unwrap();
+        assert_eq!(map.len(), 500);
+        assert_eq!(map.movetex().version(), 1003);
+
+        // The published map is shared with its clones instead of being copied.
+        assert!(map.movetex().read().clone_cost() < 64);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let map: MovetexIm<String, usize> = MovetexIm::new();
+        thread::scope(|s| {
+            for t in 0..8 {
+                let map = &map;
+                s.spawn(move || {
+                    for i in 0..250 {
+                        map.insert(format!("{t}-{i}"), i).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(map.len(), 2000);
+        assert_eq!(map.get("3-249").as_deref(), Some(&249));
+    }
+}
@@ -0,0 +1,39 @@
+use movetex::{InternPool, Movetex};
+use std::sync::Arc;
+
+mod intern_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let pool = Arc::new(InternPool::new());
+        let a = Movetex::builder(vec![1u32, 2]).intern(&pool).build();
+        let b = Movetex::builder(vec![1u32, 2]).intern(&pool).build();
+        assert_eq!(pool.len(), 1);
+        assert!(std::ptr::eq(&*a.read(), &*b.read()));
+
+        assert!(b.write(|v| v.push(3)));
+        assert!(!std::ptr::eq(&*a.read(), &*b.read()));
+        assert_eq!(pool.len(), 2);
+
+        // Converging values share the allocation again, and the old one is freed.
+        assert!(a.write(|v| v.push(3)));
+        assert!(std::ptr::eq(&*a.read(), &*b.read()));
+        assert_eq!(*a.read(), [1, 2, 3]);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let pool = Arc::new(InternPool::new());
+        let movetex = Movetex::<String>::uninit_builder().intern(&pool).build();
+        assert!(pool.is_empty());
+        movetex.initialize(String::from("a")).unwrap();
+        assert_eq!(pool.len(), 1);
+
+        drop(movetex);
+        assert!(pool.is_empty());
+        pool.purge();
+        assert!(pool.is_empty());
+    }
+}
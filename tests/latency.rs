@@ -0,0 +1,28 @@
+#![cfg(feature = "hdrhistogram")]
+
+use movetex::Movetex;
+
+mod latency_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![0u8; 128]).record_latencies().build();
+        for _ in 0..10 {
+            movetex.write(|v| v[0] += 1);
+            movetex.read();
+        }
+        let report = movetex.latency_report().unwrap();
+        assert_eq!(report.write.len(), 10);
+        assert_eq!(report.publish.len(), 10);
+        assert_eq!(report.read.len(), 10);
+        assert!(report.publish.max() <= report.write.max());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        movetex.write(|v| *v += 1);
+        assert!(movetex.latency_report().is_none());
+    }
+}
@@ -0,0 +1,25 @@
+use movetex::Movetex;
+use std::mem::size_of;
+
+mod layout_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        const LAYOUT: movetex::MovetexLayout = Movetex::<[u64; 32]>::LAYOUT;
+        assert_eq!(LAYOUT.size, size_of::<Movetex<[u64; 32]>>());
+        assert!(LAYOUT.snapshot_size >= size_of::<[u64; 32]>());
+        #[cfg(not(feature = "single-copy"))]
+        assert!(LAYOUT.writer_copy_size >= size_of::<[u64; 32]>());
+        #[cfg(feature = "single-copy")]
+        assert_eq!(LAYOUT.writer_copy_size, 0);
+        assert_eq!(
+            LAYOUT.snapshot_overhead,
+            LAYOUT.snapshot_size - size_of::<[u64; 32]>()
+        );
+        assert_eq!(
+            LAYOUT.total_size(),
+            LAYOUT.size + LAYOUT.snapshot_size + LAYOUT.writer_copy_size
+        );
+    }
+}
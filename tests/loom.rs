@@ -0,0 +1,158 @@
+//! Model checks of the publish protocol under loom.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+#![cfg(loom)]
+
+use loom::model::Builder;
+use loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+use movetex::Movetex;
+
+/// A value that records being dropped, so a model can check that no guard outlives it.
+struct Tracked {
+    value: u32,
+    dropped: Arc<AtomicBool>,
+    live: Arc<AtomicUsize>,
+}
+
+impl Tracked {
+    fn new(value: u32, live: &Arc<AtomicUsize>) -> Self {
+        live.fetch_add(1, Ordering::SeqCst);
+        Self {
+            value,
+            dropped: Arc::new(AtomicBool::new(false)),
+            live: Arc::clone(live),
+        }
+    }
+}
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        Self::new(self.value, &self.live)
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+        self.live.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Explores interleavings with up to three preemptions per execution, which covers every
+/// ordering of the few atomic steps these models race while keeping them fast.
+fn model(f: impl Fn() + Sync + Send + 'static) {
+    let mut builder = Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(f);
+}
+
+/// Reads `movetex` and checks that the value stays alive while the guard is held.
+fn read_alive(movetex: &Movetex<Tracked>) -> u32 {
+    let guard = movetex.read();
+    let dropped = Arc::clone(&guard.dropped);
+    let value = guard.value;
+    thread::yield_now();
+    assert!(
+        !dropped.load(Ordering::SeqCst),
+        "value dropped under a guard"
+    );
+    drop(guard);
+    value
+}
+
+mod loom_tests {
+    use super::*;
+
+    /// A reader racing a write sees the old or the new value, and the value it holds is not
+    /// reclaimed until its guard is dropped.
+    #[test]
+    fn test_t_0() {
+        model(|| {
+            let live = Arc::new(AtomicUsize::new(0));
+            let movetex = Arc::new(Movetex::new(Tracked::new(1, &live)));
+
+            let reader = {
+                let movetex = Arc::clone(&movetex);
+                thread::spawn(move || read_alive(&movetex))
+            };
+            assert!(movetex.write(|v| v.value = 2));
+
+            let seen = reader.join().unwrap();
+            assert!(seen == 1 || seen == 2, "read {seen}");
+            assert_eq!(read_alive(&movetex), 2);
+            drop(movetex);
+            assert_eq!(live.load(Ordering::SeqCst), 0);
+        });
+    }
+
+    /// Two readers racing two writes never see a reclaimed value, and every replaced value is
+    /// reclaimed once the instance is dropped.
+    #[test]
+    fn test_t_1() {
+        model(|| {
+            let live = Arc::new(AtomicUsize::new(0));
+            let movetex = Arc::new(Movetex::new(Tracked::new(0, &live)));
+
+            let readers: Vec<_> = (0..2)
+                .map(|_| {
+                    let movetex = Arc::clone(&movetex);
+                    thread::spawn(move || read_alive(&movetex))
+                })
+                .collect();
+            assert!(movetex.write(|v| v.value = 1));
+            assert!(movetex.write(|v| v.value = 2));
+
+            for reader in readers {
+                assert!(reader.join().unwrap() <= 2);
+            }
+            drop(movetex);
+            assert_eq!(live.load(Ordering::SeqCst), 0);
+        });
+    }
+
+    /// Concurrent writes never both publish from the same writer copy: the published value
+    /// reflects exactly the writes that reported success.
+    #[test]
+    fn test_t_2() {
+        model(|| {
+            let movetex = Arc::new(Movetex::new(0u32));
+
+            let other = {
+                let movetex = Arc::clone(&movetex);
+                thread::spawn(move || movetex.write(|v| *v += 1))
+            };
+            let mine = movetex.write(|v| *v += 2);
+            let theirs = other.join().unwrap();
+
+            assert!(mine || theirs);
+            let expected = u32::from(theirs) + 2 * u32::from(mine);
+            assert_eq!(*movetex.read(), expected);
+        });
+    }
+
+    /// A swap racing a write either lands before it, and is published by it, or after it, and
+    /// stays pending, or fails while the write owns the writer copy.
+    #[test]
+    fn test_t_3() {
+        model(|| {
+            let movetex = Arc::new(Movetex::new(1u32));
+
+            let swapper = {
+                let movetex = Arc::clone(&movetex);
+                thread::spawn(move || movetex.swap(5))
+            };
+            assert!(movetex.write(|v| *v += 10));
+            let swapped = swapper.join().unwrap();
+
+            let published = *movetex.read();
+            match swapped {
+                Ok(1) => assert_eq!((published, movetex.is_dirty()), (15, false)),
+                Ok(11) => assert_eq!((published, movetex.is_dirty()), (11, true)),
+                Err(5) => assert_eq!((published, movetex.is_dirty()), (11, false)),
+                other => panic!("swap returned {other:?}"),
+            }
+        });
+    }
+}
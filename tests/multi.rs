@@ -0,0 +1,240 @@
+use movetex::{
+    read_n, read_n_reported, read_pair, read_pair_reported, transaction, write_pair,
+    ConsistencyReport, Movetex, ValidationError, WriteError,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod read_pair_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let host = Movetex::new(String::from("localhost"));
+        let port = Movetex::new(8080u16);
+
+        let (h, p) = read_pair(&host, &port);
+        assert_eq!((h.as_str(), *p), ("localhost", 8080));
+
+        assert!(port.write(|p| *p = 9090));
+        let (h, p) = read_pair(&host, &port);
+        assert_eq!((h.as_str(), *p), ("localhost", 9090));
+    }
+}
+
+mod consistency_report_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let a = Movetex::new(0u64);
+        let b = Movetex::new(0u64);
+        let ((_, _), report) = read_pair_reported(&a, &b);
+        assert_eq!(report, ConsistencyReport::default());
+
+        // A writer keeping both equal makes some reads retry; every returned pair is consistent
+        // and a retried read saw at least one publish of skew.
+        let done = AtomicBool::new(false);
+        let retried = thread::scope(|s| {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    write_pair(&a, &b, |a, b| {
+                        *a += 1;
+                        *b += 1;
+                    })
+                    .unwrap();
+                }
+            });
+            let deadline = Instant::now() + Duration::from_secs(10);
+            let mut retried = false;
+            while !retried && Instant::now() < deadline {
+                let ((x, y, z), report) = read_n_reported!(&a, &b, &a);
+                assert_eq!((*x, *y), (*z, *z));
+                assert_eq!(report.retries > 0, report.max_skew > 0);
+                retried = report.retries > 0;
+            }
+            done.store(true, Ordering::Relaxed);
+            retried
+        });
+        assert!(retried, "no read overlapped a write");
+    }
+}
+
+mod read_n_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let a = Movetex::new(1);
+        let b = Movetex::new("two");
+        let c = Movetex::new(vec![3]);
+
+        let (a_val, b_val, c_val) = read_n!(&a, &b, &c);
+        assert_eq!((*a_val, *b_val, c_val.as_slice()), (1, "two", &[3][..]));
+
+        let (only,) = read_n!(&a);
+        assert_eq!(*only, 1);
+    }
+}
+
+mod write_all_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let a = Movetex::new(vec![1u32]);
+        let b = Movetex::new(vec![2u32]);
+
+        Movetex::write_all(&[&a, &b], |[a, b]| {
+            a.push(10);
+            b.push(20);
+        })
+        .unwrap();
+        assert_eq!(
+            (a.read().as_slice(), b.read().as_slice()),
+            (&[1, 10][..], &[2, 20][..])
+        );
+        assert_eq!((a.version(), b.version()), (1, 1));
+
+        // An instance that cannot be written leaves the others untouched and `f` uncalled.
+        let tx = b.begin_write().unwrap();
+        let result = Movetex::write_all(&[&a, &b], |_| unreachable!());
+        assert_eq!(result, Err(WriteError::Busy));
+        drop(tx);
+        assert!(a.write(|a| a.push(11)));
+        assert_eq!(*a.read(), [1, 10, 11]);
+
+        assert_eq!(
+            Movetex::write_all(&[&a, &a], |_| unreachable!()),
+            Err(WriteError::Busy)
+        );
+    }
+
+    #[test]
+    fn test_t_1() {
+        let a = Movetex::new(vec![1u32]);
+        let b = Movetex::builder(vec![2u32])
+            .validator(|v: &Vec<u32>| {
+                if v.is_empty() {
+                    return Err(ValidationError::new("list must not be empty"));
+                }
+                Ok(())
+            })
+            .build();
+
+        let result = Movetex::write_all(&[&a, &b], |[a, b]| {
+            a.push(10);
+            b.clear();
+        });
+        assert!(matches!(result, Err(WriteError::Rejected(_))));
+        assert_eq!(
+            (a.read().as_slice(), b.read().as_slice()),
+            (&[1][..], &[2][..])
+        );
+        assert_eq!((a.version(), b.version()), (0, 0));
+
+        // Both writer copies were restored.
+        assert!(a.write(|a| a.push(3)));
+        assert!(b.write(|b| b.push(4)));
+        assert_eq!(
+            (a.read().as_slice(), b.read().as_slice()),
+            (&[1, 3][..], &[2, 4][..])
+        );
+    }
+
+    #[test]
+    fn test_t_2() {
+        let a = Movetex::new(1u32);
+        let b = Movetex::new(2u32);
+        assert_eq!(a.swap(5), Ok(1));
+
+        // The second instance is busy, so the first one is released again without poisoning.
+        let tx = b.begin_write().unwrap();
+        assert_eq!(
+            Movetex::write_all(&[&a, &b], |_| unreachable!()),
+            Err(WriteError::Busy)
+        );
+        drop(tx);
+        assert!(!a.is_poisoned());
+        assert!(a.is_dirty());
+        assert!(a.write(|a| *a += 1));
+        assert_eq!(*a.read(), 6);
+    }
+}
+
+mod write_pair_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let index = Movetex::new(vec![0usize]);
+        let data = Movetex::new(vec![String::from("a")]);
+
+        write_pair(&index, &data, |index, data| {
+            data.push(String::from("b"));
+            index.push(data.len() - 1);
+        })
+        .unwrap();
+        let (index, data) = read_pair(&index, &data);
+        assert_eq!(data[index[1]], "b");
+
+        let bad = Movetex::builder(0u8)
+            .validator(|v: &u8| match v {
+                0..=9 => Ok(()),
+                _ => Err(ValidationError::new("too large")),
+            })
+            .build();
+        let other = Movetex::new(String::from("kept"));
+        let result = write_pair(&other, &bad, |other, bad| {
+            other.clear();
+            *bad = 10;
+        });
+        assert!(matches!(result, Err(WriteError::Rejected(_))));
+        assert_eq!((other.read().as_str(), *bad.read()), ("kept", 0));
+        assert_eq!((other.version(), bad.version()), (0, 0));
+
+        // Both writer copies were restored.
+        assert!(other.write(|v| v.push('!')));
+        assert!(bad.write(|v| *v = 9));
+        assert_eq!((other.read().as_str(), *bad.read()), ("kept!", 9));
+    }
+}
+
+mod transaction_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let a = Movetex::new(1u32);
+        let b = Movetex::new("one");
+        let c = Movetex::new(vec![1u8]);
+
+        transaction!(&a, &b, &c => |a, b, c| {
+            *a += 1;
+            *b = "two";
+            c.push(2);
+        })
+        .unwrap();
+        let (a_val, b_val, c_val) = read_n!(&a, &b, &c);
+        assert_eq!((*a_val, *b_val, c_val.as_slice()), (2, "two", &[1, 2][..]));
+        drop((a_val, b_val, c_val));
+        assert_eq!((a.version(), b.version(), c.version()), (1, 1, 1));
+
+        // A busy instance in the middle leaves the others released and `f` uncalled.
+        assert_eq!(a.swap(5), Ok(2));
+        let tx = b.begin_write().unwrap();
+        let result = transaction!(&a, &b, &c => |_, _, _| unreachable!());
+        assert_eq!(result, Err(WriteError::Busy));
+        drop(tx);
+        assert!(!a.is_poisoned() && a.is_dirty());
+        assert!(c.write(|c| c.push(3)));
+
+        assert_eq!(
+            transaction!(&a, &c, &a => |_, _, _| unreachable!()),
+            Err(WriteError::Busy)
+        );
+        assert!(a.write(|a| *a += 1));
+        assert_eq!(*a.read(), 6);
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "tokio")]
+
+use movetex::{Movetex, WriteError};
+use std::sync::Arc;
+
+mod offload_tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Arc::new(Movetex::new(vec![0u8; 1024]));
+        block_on(movetex.write_offloaded(|v| v.push(1))).unwrap();
+        assert_eq!(movetex.read().len(), 1025);
+        assert_eq!(movetex.read()[1024], 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::<u32>::uninit());
+        let result = block_on(movetex.write_offloaded(|v| *v += 1));
+        assert_eq!(result, Err(WriteError::NotInitialized));
+    }
+}
@@ -0,0 +1,73 @@
+use movetex::{MemoryOrdering, Movetex};
+use std::sync::Arc;
+use std::thread;
+
+mod memory_ordering_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        assert_eq!(MemoryOrdering::default(), MemoryOrdering::AcquireRelease);
+
+        for ordering in [
+            MemoryOrdering::AcquireRelease,
+            MemoryOrdering::Mixed,
+            MemoryOrdering::SeqCst,
+        ] {
+            let movetex = Movetex::builder(vec![0u32])
+                .memory_ordering(ordering)
+                .build();
+            assert!(movetex.write(|v| v.push(1)));
+            assert_eq!(movetex.swap_and_publish(vec![2]), Some(vec![0, 1]));
+            assert_eq!(movetex.swap(vec![3]), Ok(vec![2]));
+            assert!(movetex.publish());
+            assert_eq!(*movetex.read(), [3]);
+            assert_eq!(movetex.version(), 3);
+        }
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(
+            Movetex::builder(0u64)
+                .memory_ordering(MemoryOrdering::SeqCst)
+                .build(),
+        );
+
+        let writer = {
+            let movetex = Arc::clone(&movetex);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    movetex.write_blocking(|v| *v += 1).unwrap();
+                }
+            })
+        };
+        let mut last = 0;
+        while last < 1000 {
+            let current = *movetex.read();
+            assert!(current >= last);
+            last = current;
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_t_2() {
+        // Writers contending for the writer slot hand it over under every profile without
+        // losing an update.
+        for ordering in [MemoryOrdering::Mixed, MemoryOrdering::SeqCst] {
+            let movetex = Movetex::builder(0u64).memory_ordering(ordering).build();
+            thread::scope(|s| {
+                for _ in 0..4 {
+                    s.spawn(|| {
+                        for _ in 0..500 {
+                            movetex.write_blocking(|v| *v += 1).unwrap();
+                        }
+                    });
+                }
+            });
+            assert_eq!(*movetex.read(), 2000);
+            assert_eq!(movetex.version(), 2000);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "json")]
+
+use movetex::Movetex;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    limits: BTreeMap<String, u32>,
+    tags: Vec<String>,
+}
+
+fn config() -> Config {
+    Config {
+        name: String::from("api"),
+        limits: BTreeMap::from([(String::from("cpu"), 2), (String::from("mem"), 512)]),
+        tags: vec![String::from("a")],
+    }
+}
+
+fn raw(json: &str) -> Box<RawValue> {
+    RawValue::from_string(json.to_string()).unwrap()
+}
+
+mod patch_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(config());
+        let patch = raw(r#"{"limits": {"cpu": 4, "mem": null, "io": 1}, "tags": ["b", "c"]}"#);
+
+        assert!(movetex.write_patch(&patch).unwrap());
+        assert_eq!(
+            movetex.read().limits,
+            BTreeMap::from([(String::from("cpu"), 4), (String::from("io"), 1)])
+        );
+        assert_eq!(movetex.read().tags, ["b", "c"]);
+        assert_eq!(movetex.read().name, "api");
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(config());
+
+        assert!(movetex.write_patch(&raw(r#"{"name": 42}"#)).is_err());
+        assert_eq!(*movetex.read(), config());
+    }
+}
@@ -0,0 +1,118 @@
+use movetex::{MockClock, MovetexPerKey};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod per_key_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let registry = MovetexPerKey::new(|key: &u32| key * 10);
+
+        assert!(registry.is_empty());
+        assert_eq!(*registry.entry(1).read(), 10);
+        assert!(registry.entry(2).write(|v| *v += 1));
+        assert_eq!(*registry.get(&2).unwrap().read(), 21);
+        assert!(registry.get(&3).is_none());
+
+        let mut keys = registry.keys();
+        keys.sort();
+        assert_eq!(keys, [1, 2]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let clock = MockClock::new();
+        let registry = MovetexPerKey::with_clock(|_: &&str| 0, Arc::new(clock.clone()));
+        let held = registry.entry("held");
+        registry.entry("idle");
+
+        clock.advance(Duration::from_millis(20));
+        assert_eq!(registry.evict_idle(Duration::from_millis(10)), 1);
+        assert_eq!(registry.keys(), ["held"]);
+
+        drop(held);
+        assert_eq!(registry.evict_idle(Duration::from_millis(10)), 1);
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_t_2() {
+        let registry = Arc::new(MovetexPerKey::new(|_: &u8| 0u64));
+
+        std::thread::scope(|s| {
+            for key in 0..4u8 {
+                let r = Arc::clone(&registry);
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        assert!(r.entry(key).write(|v| *v += 1));
+                    }
+                });
+            }
+        });
+
+        let mut total = 0;
+        registry.for_each(|_, m| total += *m.read());
+        assert_eq!(total, 400);
+    }
+}
+
+mod bulk_load_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let registry: MovetexPerKey<u32, String> = (0..10).map(|i| (i, i.to_string())).collect();
+
+        assert_eq!(registry.len(), 10);
+        assert_eq!(*registry.entry(7).read(), "7");
+        assert_eq!(*registry.entry(42).read(), "");
+    }
+
+    #[test]
+    fn test_t_1() {
+        let registry = MovetexPerKey::new(|_: &u32| 0u32);
+        let old = registry.entry(1);
+
+        assert_eq!(registry.bulk_load((0..10_000).map(|i| (i, i * 2))), 10_000);
+        assert_eq!(registry.len(), 10_000);
+        assert_eq!(*registry.entry(1).read(), 2);
+        assert_eq!(*registry.entry(9_999).read(), 19_998);
+        assert_eq!(*old.read(), 0);
+    }
+}
+
+mod snapshot_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_t_0() {
+        let registry: MovetexPerKey<u8, u32> = (0..4).map(|i| (i, 0)).collect();
+        registry.entry(2).write(|v| *v = 5);
+
+        assert_eq!(
+            registry.snapshot_all(),
+            HashMap::from([(0, 0), (1, 0), (2, 5), (3, 0)])
+        );
+        let (values, report) = registry.snapshot_all_reported();
+        assert_eq!(values.len(), 4);
+        assert_eq!((report.retries, report.max_skew), (0, 0));
+    }
+}
+
+mod hasher_tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    #[test]
+    fn test_t_0() {
+        let hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let registry = MovetexPerKey::with_hasher(|key: &u32| key + 1, hasher);
+        assert!(registry.entry(1).write(|v| *v *= 10));
+        assert_eq!(*registry.get(&1).unwrap().read(), 20);
+        assert_eq!(*registry.entry(2).read(), 3);
+        assert_eq!(registry.len(), 2);
+    }
+}
@@ -0,0 +1,27 @@
+use movetex::{MovetexBuilderPhase, WriteError};
+
+mod phase_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let mut phase = MovetexBuilderPhase::new(vec![1]);
+        phase.push(2);
+        assert_eq!(phase.len(), 2);
+
+        let frozen = phase.freeze();
+        assert_eq!(*frozen.read(), vec![1, 2]);
+        assert_eq!(frozen.try_write(|v| v.clear()), Err(WriteError::Sealed));
+        assert_eq!(frozen.swap(Vec::new()), Err(Vec::new()));
+        assert_eq!(*frozen.read(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let mut phase = MovetexBuilderPhase::new(1);
+        *phase += 1;
+        let movetex = phase.into_movetex();
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(*movetex.read(), 3);
+    }
+}
@@ -0,0 +1,54 @@
+use movetex::{Movetex, ReadGuard, StateSink, StateSource, WriteError, WriteThrough};
+use std::sync::{Arc, Mutex};
+
+/// Publishes every value to all of its sinks.
+struct Replicate<A, B>(A, B);
+
+impl<T: Clone, A: StateSink<T>, B: StateSink<T, Error = A::Error>> StateSink<T>
+    for Replicate<A, B>
+{
+    type Error = A::Error;
+
+    fn publish(&self, value: T) -> Result<(), A::Error> {
+        self.0.publish(value.clone())?;
+        self.1.publish(value)
+    }
+}
+
+impl<T, A: StateSource<T>, B> StateSource<T> for Replicate<A, B> {
+    fn read(&self) -> ReadGuard<'_, T> {
+        self.0.read()
+    }
+}
+
+mod pipeline_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let primary = Movetex::new(1);
+        let replica = Arc::new(Movetex::new(1));
+        let pipeline = Replicate(&primary, Arc::clone(&replica));
+
+        pipeline.publish(2).unwrap();
+        assert_eq!((*pipeline.read(), *replica.read()), (2, 2));
+
+        primary.seal();
+        assert_eq!(pipeline.publish(3), Err(WriteError::Sealed));
+        assert_eq!(*replica.read(), 2);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let persisted = Mutex::new(Vec::new());
+        let sink = WriteThrough::new(Movetex::new(0), |value: &i32| {
+            persisted.lock().unwrap().push(*value);
+            Ok::<_, std::convert::Infallible>(())
+        });
+
+        let boxed: &dyn StateSource<i32> = &sink;
+        sink.publish(5).unwrap();
+        assert_eq!(*boxed.read(), 5);
+        assert_eq!(*persisted.lock().unwrap(), [5]);
+    }
+}
@@ -106,7 +106,7 @@ mod swap_tests {
     fn test_t_0() {
         let movetex = Movetex::new(42);
 
-        assert_eq!(movetex.swap(43), Some(42));
+        assert_eq!(movetex.swap(43), Ok(42));
     }
 
     #[test]
@@ -118,13 +118,204 @@ mod swap_tests {
             let m2 = movetex.clone();
 
             s.spawn(move || {
-                assert_eq!(m1.swap(43), Some(42));
+                assert_eq!(m1.swap(43), Ok(42));
             });
 
             s.spawn(move || {
                 std::thread::sleep(time::Duration::from_secs(1));
-                assert_eq!(m2.swap(44), Some(43));
+                assert_eq!(m2.swap(44), Ok(43));
             });
         });
     }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Arc::new(Movetex::new(42));
+
+        std::thread::scope(|s| {
+            let m1 = movetex.clone();
+            let m2 = movetex.clone();
+
+            s.spawn(move || {
+                assert!(m1.write(|value| {
+                    std::thread::sleep(time::Duration::from_millis(300));
+                    *value = 43;
+                }));
+            });
+
+            s.spawn(move || {
+                std::thread::sleep(time::Duration::from_millis(100));
+                assert_eq!(m2.swap(44), Err(44));
+                assert!(!m2.write(|_| {}));
+            });
+        });
+
+        assert_eq!(*movetex.read(), 43);
+        assert_eq!(movetex.swap(45), Ok(43));
+    }
+}
+
+mod read_fresh_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1);
+        assert_eq!(*movetex.read_fresh(), 1);
+        movetex.write(|value| *value = 2);
+        assert_eq!(*movetex.read_fresh(), 2);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(1);
+        movetex.write(|value| {
+            *value = 2;
+            // The write in progress is not published yet, so the bounded spin gives up.
+            assert_eq!(*movetex.read_fresh(), 1);
+        });
+    }
+}
+
+mod read_batch_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1);
+        let mut seen = Vec::new();
+        let calls = movetex.read_batch(5, |v| {
+            seen.push(*v);
+            if seen.len() == 2 {
+                assert!(movetex.write(|v| *v = 2));
+            }
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(seen, [1, 1]);
+
+        assert_eq!(movetex.read_batch(3, |v| seen.push(*v)), 3);
+        assert_eq!(seen, [1, 1, 2, 2, 2]);
+        assert_eq!(movetex.read_batch(0, |_| unreachable!()), 0);
+    }
+}
+
+mod clone_ahead_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1]).clone_ahead().build();
+        for i in 2..10 {
+            assert!(movetex.write(|value| value.push(i)));
+            assert_eq!(movetex.read().len(), i);
+        }
+        assert_eq!(movetex.swap(vec![0]), Ok((1..10).collect()));
+        assert!(movetex.write(|value| value.push(1)));
+        assert_eq!(*movetex.read(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(0u64)
+            .clone_ahead()
+            .validator(|value: &u64| {
+                if value.is_multiple_of(2) {
+                    Ok(())
+                } else {
+                    Err(movetex::ValidationError::new("odd"))
+                }
+            })
+            .build();
+        assert!(movetex.write(|value| *value += 2));
+        assert!(!movetex.write(|value| *value += 1));
+        assert!(movetex.write(|value| *value += 2));
+        assert_eq!(*movetex.read(), 4);
+        assert!(movetex.seal());
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Fragile(u32);
+
+    impl Clone for Fragile {
+        fn clone(&self) -> Self {
+            assert_ne!(self.0, 13, "unlucky clone");
+            Fragile(self.0)
+        }
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::builder(Fragile(1)).clone_ahead().build();
+        assert!(movetex.write(|value| value.0 = 13));
+
+        // The clone panicked on the worker; the swap collecting it panics and poisons.
+        let swap =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| movetex.swap(Fragile(2))));
+        assert!(swap.is_err());
+        assert!(movetex.is_poisoned());
+        assert_eq!(movetex.swap(Fragile(3)), Err(Fragile(3)));
+        assert_eq!(*movetex.read(), Fragile(13));
+
+        // A clone panicking when nothing collects it is dropped with the instance.
+        let other = Movetex::builder(Fragile(1)).clone_ahead().build();
+        assert!(other.write(|value| value.0 = 13));
+        drop(other);
+    }
+}
+
+mod pinned_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Arc::new(Movetex::new(vec![1u32; 64]));
+        movetex.with_pinned(|pinned| {
+            let writer = {
+                let movetex = Arc::clone(&movetex);
+                std::thread::spawn(move || movetex.write(|value| value.fill(2)))
+            };
+            // The writer publishes without waiting, but the old value outlives this scope.
+            assert!(writer.join().unwrap());
+            assert!(movetex.read().iter().all(|&v| v == 2));
+            assert!(unsafe { &*pinned }.iter().all(|&v| v == 1));
+        });
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(1);
+        assert_eq!(movetex.active_readers(), 0);
+        movetex.with_pinned(|_| {
+            assert_eq!(movetex.active_readers(), 1);
+            movetex.with_pinned(|_| assert_eq!(movetex.active_readers(), 2));
+        });
+        let guard = movetex.read();
+        assert_eq!(movetex.active_readers(), 1);
+        drop(guard);
+        assert_eq!(movetex.active_readers(), 0);
+    }
+}
+
+mod write_into_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(String::from("a"));
+        let mut scratch = String::with_capacity(32);
+        for next in ["b", "c"] {
+            scratch.clear();
+            assert!(movetex.write_into(&mut scratch, |buf| buf.push_str(next)));
+            assert_eq!(*movetex.read(), next);
+        }
+        assert_eq!(scratch, "b");
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit();
+        let mut scratch = 7;
+        assert!(!movetex.write_into(&mut scratch, |buf| *buf += 1));
+        assert_eq!(scratch, 7);
+    }
 }
@@ -0,0 +1,68 @@
+use movetex::{InvariantViolation, Movetex, ValidationError};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+static SALT: AtomicU64 = AtomicU64::new(0);
+static LIMIT: AtomicU32 = AtomicU32::new(10);
+static MISMATCHES: AtomicU32 = AtomicU32::new(0);
+
+mod repair_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        movetex::set_invariant_handler(|violation| {
+            assert_eq!(violation, InvariantViolation::ChecksumMismatch);
+            MISMATCHES.fetch_add(1, Ordering::SeqCst);
+        });
+        let movetex = Movetex::builder(7u64)
+            .checksum(|value| value ^ SALT.load(Ordering::SeqCst))
+            .repair(|_| Some(0))
+            .build();
+        assert_eq!(*movetex.read(), 7);
+
+        // Changing the salt makes the published checksum stale, as corruption would. Reads
+        // keep serving the bad value and leave the repair to the next write.
+        SALT.store(0xff, Ordering::SeqCst);
+        assert_eq!(*movetex.read(), 7);
+        assert_eq!(MISMATCHES.load(Ordering::SeqCst), 1);
+        movetex.write(|value| *value += 1);
+        assert_eq!(*movetex.read(), 1);
+        assert_eq!(MISMATCHES.load(Ordering::SeqCst), 1);
+        assert!(movetex.verify_published());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(8u32)
+            .validator(|value: &u32| {
+                if *value <= LIMIT.load(Ordering::SeqCst) {
+                    Ok(())
+                } else {
+                    Err(ValidationError::new("over limit"))
+                }
+            })
+            .repair(|value| Some((*value).min(LIMIT.load(Ordering::SeqCst))))
+            .build();
+        assert!(movetex.verify_published());
+
+        LIMIT.store(5, Ordering::SeqCst);
+        assert_eq!(*movetex.read(), 8);
+        assert!(movetex.verify_published());
+        assert_eq!(*movetex.read(), 5);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::builder(1u32)
+            .validator(|value: &u32| {
+                if *value < 100 {
+                    Ok(())
+                } else {
+                    Err(ValidationError::new("too large"))
+                }
+            })
+            .build();
+        assert!(movetex.verify_published());
+        assert!(Movetex::<u32>::uninit().verify_published());
+    }
+}
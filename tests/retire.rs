@@ -0,0 +1,162 @@
+use movetex::{Movetex, SpillPolicy};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static RETIRED: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+static SPILLED: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+
+mod retire_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1])
+            .on_retire(|value| RETIRED.lock().unwrap().push(value))
+            .build();
+        movetex.write(|v| v.push(2));
+        movetex.write(|v| v.push(3));
+        assert_eq!(*RETIRED.lock().unwrap(), vec![vec![1], vec![1, 2]]);
+
+        movetex.swap(vec![9]).unwrap();
+        drop(movetex);
+        assert_eq!(RETIRED.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(vec![1])
+            .spill(SpillPolicy::always())
+            .on_retire(|value| SPILLED.lock().unwrap().push(value))
+            .build();
+        // The retired snapshot shares its allocation with the writer copy until the write
+        // modifies it, so the modification clones and the old value is handed over.
+        movetex.write(|v| v.push(2));
+        assert_eq!(*SPILLED.lock().unwrap(), vec![vec![1]]);
+    }
+}
+
+mod recycle_tests {
+    use super::*;
+
+    /// Without a retire hook, publishes cycle through the same few buffers instead of
+    /// allocating new ones.
+    #[cfg(not(feature = "single-copy"))]
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![0u64; 1024]);
+        let mut buffers = std::collections::HashSet::new();
+        for i in 0..32 {
+            assert!(movetex.write(|v| v[0] = i));
+            if i >= 2 {
+                buffers.insert(movetex.read().as_ptr() as usize);
+            }
+        }
+        assert!(buffers.len() <= 3, "{} buffers", buffers.len());
+        assert_eq!(movetex.read()[0], 31);
+    }
+
+    /// A recycled buffer is refreshed to the published value before the next write starts.
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(String::from("a"));
+        for c in ['b', 'c', 'd', 'e'] {
+            let guard = movetex.read();
+            assert!(movetex.write(|v| v.push(c)));
+            assert!(guard.len() < movetex.read().len());
+        }
+        assert!(movetex.write(|v| v.push('f')));
+        assert_eq!(*movetex.read(), "abcdef");
+    }
+
+    static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted(Vec<u8>);
+
+    impl Counted {
+        fn new() -> Self {
+            LIVE.fetch_add(1, Ordering::SeqCst);
+            Counted(Vec::new())
+        }
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            LIVE.fetch_add(1, Ordering::SeqCst);
+            Counted(self.0.clone())
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            LIVE.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Between writes, an instance holds the published value and the writer copy, or only
+    /// the published value in `single-copy` builds.
+    #[test]
+    fn test_t_2() {
+        let copies = if cfg!(feature = "single-copy") { 1 } else { 2 };
+        let live = || LIVE.load(Ordering::SeqCst);
+
+        let movetex = Movetex::new(Counted::new());
+        assert_eq!(live(), copies);
+        for i in 0..4 {
+            assert!(movetex.write(|v| v.0.push(i)));
+            assert_eq!(live(), copies);
+        }
+
+        // A snapshot a reader holds during the publish stays until the next publish, which
+        // turns it into the writer copy, or drops it in `single-copy` builds.
+        let guard = movetex.read();
+        assert!(movetex.write(|v| v.0.push(4)));
+        drop(guard);
+        assert_eq!(live(), copies + 1);
+        assert!(movetex.write(|v| v.0.push(5)));
+        assert_eq!(live(), copies);
+
+        assert_eq!(movetex.read().0, [0, 1, 2, 3, 4, 5]);
+        drop(movetex);
+        assert_eq!(live(), 0);
+    }
+}
+
+mod background_drop_tests {
+    use super::*;
+
+    static DROPPED_ON: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
+
+    #[derive(Clone)]
+    struct Tracked(u32);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            let thread = thread::current().name().map(str::to_owned);
+            DROPPED_ON.lock().unwrap().push(thread);
+        }
+    }
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(Tracked(0))
+            .spill(SpillPolicy::always())
+            .drop_in_background()
+            .build();
+        for i in 1..=4 {
+            assert!(movetex.write(|v| v.0 = i));
+        }
+        assert_eq!(movetex.read().0, 4);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while DROPPED_ON.lock().unwrap().len() < 4 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let dropped_on = DROPPED_ON.lock().unwrap();
+        assert_eq!(dropped_on.len(), 4);
+        assert!(dropped_on
+            .iter()
+            .all(|thread| thread.as_deref() == Some("movetex-dropper")));
+    }
+}
@@ -0,0 +1,101 @@
+use movetex::{MockClock, Movetex, RetryPolicy, ValidationError, WriteError};
+use std::time::Duration;
+
+fn small(v: &u32) -> Result<(), ValidationError> {
+    match v {
+        0..=10 => Ok(()),
+        _ => Err(ValidationError::new("too large")),
+    }
+}
+
+mod write_retrying_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(0u32).validator(small).build();
+        let mut attempts = Vec::new();
+        let result = movetex.write_retrying(RetryPolicy::new(3), |v, attempt| {
+            attempts.push(attempt.number);
+            *v = 100;
+        });
+        assert!(matches!(result, Err(WriteError::Rejected(_))));
+        assert_eq!(attempts, [1, 2, 3]);
+        assert_eq!(*movetex.read(), 0);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0u32);
+        let policy = RetryPolicy::new(u32::MAX)
+            .backoff(Duration::from_millis(1), Duration::from_millis(4))
+            .timeout(Duration::from_millis(20));
+        let mut nested = None;
+        assert!(movetex.write(|_| {
+            // The outer write holds the writer copy for the whole retry loop.
+            nested = Some(movetex.write_retrying(policy, |v, _| *v = 1));
+        }));
+        assert_eq!(nested, Some(Err(WriteError::Busy)));
+
+        assert!(movetex.seal());
+        let mut calls = 0;
+        assert_eq!(
+            movetex.write_retrying(RetryPolicy::new(5), |_, _| calls += 1),
+            Err(WriteError::Sealed)
+        );
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let clock = MockClock::new();
+        let movetex = Movetex::builder(0u32)
+            .clock(clock.clone())
+            .validator(small)
+            .build();
+        let mut elapsed = Vec::new();
+        let policy = RetryPolicy::new(u32::MAX).timeout(Duration::from_secs(25));
+        let result = movetex.write_retrying(policy, |v, attempt| {
+            elapsed.push(attempt.elapsed.as_secs());
+            clock.advance(Duration::from_secs(10));
+            *v = 100;
+        });
+        assert!(matches!(result, Err(WriteError::Rejected(_))));
+        assert_eq!(elapsed, [0, 10, 20]);
+    }
+}
+
+mod backoff_tests {
+    use super::*;
+    use movetex::Backoff;
+    use std::thread;
+
+    #[test]
+    fn test_t_0() {
+        let counter = Movetex::new(0);
+        let backoff = Backoff::new()
+            .spins(4)
+            .yields(4)
+            .sleep(Duration::from_micros(1), Duration::from_micros(50));
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        counter.write_with_backoff(|c| *c += 1, backoff).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(*counter.read(), 800);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        movetex.seal();
+        assert_eq!(
+            movetex.write_with_backoff(|_| unreachable!(), Backoff::default()),
+            Err(WriteError::Sealed)
+        );
+    }
+}
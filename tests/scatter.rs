@@ -0,0 +1,98 @@
+use movetex::ScatterGather;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+mod scatter_gather_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let sum: ScatterGather<u64, u64> = ScatterGather::new(0);
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1_000 {
+                        sum.scatter(|partial| *partial += 1);
+                    }
+                });
+            }
+        });
+        assert_eq!(*sum.read(), 0);
+
+        sum.merge(|total, partial| *total += partial).unwrap();
+        assert_eq!(*sum.read(), 8_000);
+        assert_eq!(sum.movetex().version(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let log: ScatterGather<Vec<u32>, Vec<u32>> = ScatterGather::new(Vec::new());
+        log.scatter(|partial| partial.push(1));
+
+        let mut merged = 0;
+        log.merge(|total, partial| {
+            merged += 1;
+            total.extend(partial);
+        })
+        .unwrap();
+        // Only the written shard is merged, and a second merge finds nothing new.
+        log.merge(|_, _| merged += 1).unwrap();
+        assert_eq!(merged, 1);
+        assert_eq!(*log.read(), [1]);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let sum: ScatterGather<u64, u64> = ScatterGather::new(0);
+        sum.scatter(|partial| *partial += 5);
+        sum.movetex().seal();
+        assert!(sum.merge(|total, partial| *total += partial).is_err());
+        assert_eq!(*sum.read(), 0);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let sum: ScatterGather<u64, u64> = ScatterGather::new(0);
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            let scatterers: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        for _ in 0..10_000 {
+                            sum.scatter(|partial| *partial += 1);
+                        }
+                    })
+                })
+                .collect();
+            s.spawn(|| {
+                while !done.load(Ordering::SeqCst) {
+                    sum.merge(|total, partial| *total += partial).unwrap();
+                }
+            });
+            for scatterer in scatterers {
+                scatterer.join().unwrap();
+            }
+            done.store(true, Ordering::SeqCst);
+        });
+
+        // Merges racing the updates left nothing behind.
+        sum.merge(|total, partial| *total += partial).unwrap();
+        assert_eq!(*sum.read(), 80_000);
+    }
+
+    #[test]
+    fn test_t_4() {
+        // Threads that exit leave their partials to the next merge, and later threads reuse
+        // their slots.
+        let log: ScatterGather<Vec<u32>, Vec<u32>> = ScatterGather::new(Vec::new());
+        for i in 0..4 {
+            thread::scope(|s| {
+                s.spawn(|| log.scatter(|partial| partial.push(i)));
+            });
+        }
+        log.merge(|total, partial| total.extend(partial)).unwrap();
+        let mut merged = log.read().clone();
+        merged.sort();
+        assert_eq!(merged, [0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,102 @@
+//! The documented guarantees of `Movetex`, checked as assertions.
+//!
+//! Each test states one guarantee that users may rely on across releases. A change that breaks
+//! one of them is a breaking change and needs a changelog entry saying so.
+
+use movetex::{Movetex, WriteError};
+use std::thread;
+
+mod writer_copy_semantics {
+    use super::*;
+
+    /// `swap` replaces the writer copy only; readers see it once a `write` publishes, and the
+    /// write applies its closure to the swapped value.
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1);
+        assert_eq!(movetex.swap(10), Ok(1));
+        assert_eq!(*movetex.read(), 1);
+        assert!(movetex.is_dirty());
+
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(*movetex.read(), 11);
+        assert!(!movetex.is_dirty());
+    }
+
+    /// Every write starts from the published value, including after a rejected write.
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(vec![1]);
+        assert!(movetex.write(|v| v.push(2)));
+        assert!(movetex.write(|v| v.push(3)));
+        assert_eq!(*movetex.read(), [1, 2, 3]);
+    }
+
+    /// A write started while another one owns the writer copy fails without running its
+    /// closure.
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0);
+        let mut nested = None;
+        assert!(movetex.write(|v| {
+            *v = 1;
+            nested = Some(movetex.try_write(|_| unreachable!()));
+        }));
+        assert_eq!(nested, Some(Err(WriteError::Busy)));
+        assert_eq!(*movetex.read(), 1);
+    }
+}
+
+mod visibility_semantics {
+    use super::*;
+
+    /// A guard keeps the value it was created with; publishes are seen by new reads only.
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(String::from("old"));
+        let guard = movetex.read();
+        assert!(movetex.write(|v| *v = String::from("new")));
+        assert_eq!(*guard, "old");
+        assert_eq!(*movetex.read(), "new");
+    }
+
+    /// Every publish bumps the version by one, `swap` does not, and a value read together with
+    /// its version was published under that version.
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0);
+        movetex.swap(5).unwrap();
+        assert_eq!(movetex.version(), 0);
+        assert!(movetex.write(|v| *v += 1));
+        assert!(movetex.write(|v| *v += 1));
+        let (version, value) = movetex.read_versioned();
+        assert_eq!((version, *value), (2, 7));
+    }
+
+    /// A reader never observes publishes out of order: successive reads on one thread see
+    /// values from non-decreasing versions.
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0u64);
+        thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..1_000 {
+                    while !movetex.write(|v| *v += 1) {
+                        thread::yield_now();
+                    }
+                }
+            });
+            for _ in 0..2 {
+                s.spawn(|| {
+                    let mut last = 0;
+                    for _ in 0..10_000 {
+                        let current = *movetex.read();
+                        assert!(current >= last);
+                        last = current;
+                    }
+                });
+            }
+        });
+        assert_eq!(*movetex.read(), 1_000);
+    }
+}
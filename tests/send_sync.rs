@@ -0,0 +1,23 @@
+use movetex::{Movetex, MovetexArc, OwnedReadGuard};
+use std::cell::Cell;
+use std::sync::Mutex;
+
+fn assert_send<S: Send>() {}
+fn assert_sync<S: Sync>() {}
+
+mod send_sync_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        assert_send::<Movetex<String>>();
+        assert_sync::<Movetex<String>>();
+        assert_send::<OwnedReadGuard<Vec<u8>>>();
+
+        // `Cell` is `Send` but not `Sync`: the instance can move, but not be shared.
+        assert_send::<Movetex<Cell<u32>>>();
+
+        // A value that is only `Send + Sync` through a lock can be shared without `Clone`.
+        assert_sync::<MovetexArc<Mutex<u32>>>();
+    }
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "json")]
+
+use movetex::Movetex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    name: String,
+    limit: Movetex<u32>,
+}
+
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let settings: Settings = serde_json::from_str(r#"{"name":"api","limit":10}"#).unwrap();
+        assert_eq!(*settings.limit.read(), 10);
+
+        settings.limit.write(|v| *v = 20);
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap(),
+            r#"{"name":"api","limit":20}"#
+        );
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit();
+        assert!(serde_json::to_string(&movetex).is_err());
+        movetex.initialize(1).unwrap();
+        assert_eq!(serde_json::to_string(&movetex).unwrap(), "1");
+    }
+}
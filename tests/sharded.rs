@@ -0,0 +1,142 @@
+use movetex::{ShardCount, ShardedMap};
+use std::collections::hash_map::RandomState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+mod sharded_map_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let map = ShardedMap::with_shards(4);
+        assert!(map.is_empty());
+
+        for key in 0..100u32 {
+            assert_eq!(map.insert(key, key * 2), Ok(None));
+        }
+        assert_eq!(map.insert(7, 0), Ok(Some(14)));
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get(&7).as_deref(), Some(&0));
+        assert!(map.get(&100).is_none());
+
+        assert_eq!(map.remove(&7), Ok(Some(0)));
+        assert_eq!(map.remove(&7), Ok(None));
+        assert!(!map.contains_key(&7));
+
+        // Every shard holds part of the keys, and only those routed to it.
+        let sizes: Vec<usize> = (0..100u32)
+            .map(|key| map.shard(&key).read().len())
+            .collect();
+        assert!(sizes.iter().all(|&len| len < 99));
+        for key in (0..100u32).filter(|&key| key != 7) {
+            assert!(map.shard(&key).read().contains_key(&key));
+        }
+    }
+
+    #[test]
+    fn test_t_1() {
+        let map: ShardedMap<String, usize> = ShardedMap::new();
+        assert_eq!(ShardedMap::<u8, u8>::with_shards(0).shards(), 1);
+
+        thread::scope(|s| {
+            for t in 0..8 {
+                let map = &map;
+                s.spawn(move || {
+                    for i in 0..250 {
+                        map.insert(format!("{t}-{i}"), i).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(map.len(), 2000);
+        assert_eq!(map.get("3-249").as_deref(), Some(&249));
+    }
+
+    #[test]
+    fn test_t_2() {
+        let cores = thread::available_parallelism().map_or(1, |n| n.get());
+        assert_eq!(ShardCount::from(3).resolve(), 3);
+        assert_eq!(ShardCount::PowerOfTwo(0).resolve(), 1);
+        assert_eq!(ShardCount::PowerOfTwo(5).resolve(), 8);
+        assert_eq!(ShardCount::PerCpu(2).resolve(), 2 * cores);
+        assert_eq!(ShardCount::PerCpu(0).resolve(), 1);
+
+        let mut map = ShardedMap::with_shards(ShardCount::PowerOfTwo(3));
+        assert_eq!(map.shards(), 4);
+        for key in 0..1000u32 {
+            map.insert(key, key + 1).unwrap();
+        }
+        assert_eq!(map.shard_sizes().iter().sum::<usize>(), 1000);
+
+        // Fewer shards with a new seed: every entry moves to the shard its new hash picks.
+        map.rebalance(3, RandomState::new());
+        assert_eq!(map.shards(), 3);
+        assert_eq!(map.shard_sizes().iter().sum::<usize>(), 1000);
+        for key in 0..1000u32 {
+            assert_eq!(map.get(&key).as_deref(), Some(&(key + 1)));
+            assert!(map.shard(&key).read().contains_key(&key));
+        }
+        assert_eq!(map.insert(5, 0), Ok(Some(6)));
+        assert_eq!(map.len(), 1000);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let map = ShardedMap::with_shards(2);
+        for key in 0..500u32 {
+            map.insert(key, key).unwrap();
+        }
+
+        // Reshards grow and shrink the map while writers update and remove keys and readers
+        // look up keys nobody writes to.
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            for t in 0..4u32 {
+                let map = &map;
+                s.spawn(move || {
+                    for round in 0..50u32 {
+                        for key in (1000 + t * 100)..(1000 + t * 100 + 100) {
+                            assert_eq!(map.insert(key, round), Ok(round.checked_sub(1)));
+                        }
+                    }
+                    for key in (1000 + t * 100)..(1000 + t * 100 + 50) {
+                        assert_eq!(map.remove(&key), Ok(Some(49)));
+                    }
+                });
+            }
+            for _ in 0..2 {
+                s.spawn(|| {
+                    while !done.load(Ordering::Relaxed) {
+                        for key in 0..500u32 {
+                            assert_eq!(map.get(&key).as_deref(), Some(&key));
+                        }
+                    }
+                });
+            }
+            for shards in [ShardCount::PowerOfTwo(16), ShardCount::Fixed(3), 7.into()] {
+                map.reshard(shards, RandomState::new()).unwrap();
+            }
+            s.spawn(|| {
+                while map.len() != 700 {
+                    thread::yield_now();
+                }
+                done.store(true, Ordering::Relaxed);
+            });
+        });
+
+        assert_eq!(map.shards(), 7);
+        assert_eq!(map.len(), 700);
+        for t in 0..4u32 {
+            for key in (1000 + t * 100)..(1000 + t * 100 + 100) {
+                let expected = (key % 100 >= 50).then_some(49);
+                assert_eq!(map.get(&key).as_deref().copied(), expected);
+                assert_eq!(map.contains_key(&key), expected.is_some());
+            }
+        }
+        // Every entry lives in the shard its key routes to.
+        assert_eq!(map.shard_sizes().iter().sum::<usize>(), 700);
+        for key in 0..500u32 {
+            assert!(map.shard(&key).read().contains_key(&key));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use movetex::MovetexSlab;
+
+mod slab_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let slab = MovetexSlab::new();
+        let a = slab.insert(1).unwrap();
+        let b = slab.insert(2).unwrap();
+        assert_eq!(slab.len(), 2);
+
+        assert_eq!(slab.remove(a).unwrap(), Some(1));
+        assert_eq!(slab.remove(a).unwrap(), None);
+        assert_eq!(slab.get(b).as_deref(), Some(&2));
+
+        let c = slab.insert(3).unwrap();
+        assert_eq!(c, a);
+        assert_eq!(slab.get(c).as_deref(), Some(&3));
+        assert_eq!(slab.len(), 2);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let slab = MovetexSlab::new();
+        let key = slab.insert(10).unwrap();
+        slab.batch(|batch| {
+            *batch.get_mut(key).unwrap() += 1;
+            batch.insert(20);
+            // Not published until the batch completes.
+            assert_eq!(slab.get(key).as_deref(), Some(&10));
+            assert_eq!(slab.len(), 1);
+        })
+        .unwrap();
+        assert_eq!(slab.get(key).as_deref(), Some(&11));
+        assert_eq!(slab.len(), 2);
+        assert!(!slab.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use movetex::{Movetex, SpillPolicy};
+
+fn policy() -> SpillPolicy<Vec<u8>> {
+    SpillPolicy::new(64, |v: &Vec<u8>| v.len())
+}
+
+mod spill_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![0u8; 1024]);
+        assert!(!movetex.is_spilled());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(vec![0u8; 8]).spill(policy()).build();
+        assert!(!movetex.is_spilled());
+
+        assert!(movetex.write(|v| v.resize(128, 1)));
+        assert!(movetex.is_spilled());
+        assert_eq!(movetex.read().len(), 128);
+
+        assert!(movetex.write(|v| v.push(2)));
+        assert_eq!(movetex.read().len(), 129);
+        assert_eq!(movetex.read()[128], 2);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::builder(vec![0u8; 128]).spill(policy()).build();
+        assert!(movetex.is_spilled());
+
+        assert!(movetex.write(|v| v.truncate(4)));
+        assert!(!movetex.is_spilled());
+        assert_eq!(*movetex.read(), vec![0u8; 4]);
+        assert_eq!(movetex.swap(vec![1]), Ok(vec![0u8; 4]));
+    }
+}
@@ -0,0 +1,134 @@
+use movetex::{InvariantViolation, Movetex, WriteError};
+
+mod state_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(1);
+        assert!(!movetex.is_dirty());
+        assert_eq!(movetex.swap(2), Ok(1));
+        assert!(movetex.is_dirty());
+        assert_eq!(*movetex.read(), 1);
+
+        assert!(movetex.write(|value| *value += 1));
+        assert!(!movetex.is_dirty());
+        assert_eq!(*movetex.read(), 3);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(1);
+        movetex.swap(5).unwrap();
+        assert!(movetex.seal());
+        assert!(movetex.is_sealed());
+        assert!(movetex.seal());
+
+        assert_eq!(
+            movetex.try_write(|value| *value += 1),
+            Err(WriteError::Sealed)
+        );
+        assert_eq!(movetex.swap(7), Err(7));
+        assert_eq!(*movetex.read(), 1);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::<u32>::uninit();
+        assert!(!movetex.seal());
+        assert_eq!(movetex.swap(1), Err(1));
+        assert!(movetex.initialize(2).is_ok());
+        assert!(movetex.initialize(3).is_err());
+        assert!(movetex.write(|value| *value *= 2));
+        assert_eq!(*movetex.read(), 4);
+    }
+}
+
+mod invariant_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        movetex::set_invariant_handler(|violation| panic!("unexpected violation: {violation}"));
+        let movetex = Movetex::new(1);
+        movetex.swap(2).unwrap();
+        assert!(movetex.write(|value| *value += 1));
+        assert_eq!(*movetex.read(), 3);
+        assert_eq!(
+            InvariantViolation::MissingWriterCopy.to_string(),
+            "writer copy is missing"
+        );
+    }
+}
+
+mod publish_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1]);
+        assert!(!movetex.publish());
+        assert_eq!(movetex.version(), 0);
+
+        assert_eq!(movetex.swap(vec![2]), Ok(vec![1]));
+        assert!(movetex.is_dirty());
+        assert!(movetex.publish());
+        assert!(!movetex.is_dirty());
+        assert_eq!(*movetex.read(), [2]);
+        assert_eq!(movetex.version(), 1);
+
+        // The writer copy matches the published value again.
+        assert!(movetex.write(|v| v.push(3)));
+        assert_eq!(*movetex.read(), [2, 3]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(1);
+        movetex.swap(5).unwrap();
+        assert_eq!(movetex.swap_and_publish(2), Some(5));
+        assert_eq!(*movetex.read(), 2);
+        assert!(!movetex.is_dirty());
+
+        assert!(movetex.seal());
+        assert_eq!(movetex.swap_and_publish(3), None);
+        assert_eq!(*movetex.read(), 2);
+    }
+}
+
+mod poison_tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn panic_in<R>(f: impl FnOnce() -> R) {
+        assert!(panic::catch_unwind(AssertUnwindSafe(f)).is_err());
+    }
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(String::from("a"));
+        assert!(!movetex.clear_poison());
+
+        panic_in(|| movetex.write(|_| panic!("closure")));
+        assert!(movetex.is_poisoned());
+        assert_eq!(movetex.swap(String::from("b")), Err(String::from("b")));
+        assert!(!movetex.seal());
+        assert_eq!(*movetex.read(), "a");
+
+        assert!(movetex.clear_poison());
+        assert!(!movetex.is_poisoned());
+        assert!(movetex.write(|v| v.push('c')));
+        assert_eq!(*movetex.read(), "ac");
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(0u32)
+            .validator(|_: &u32| panic!("validator"))
+            .build();
+        panic_in(|| movetex.write(|v| *v = 1));
+        assert_eq!(movetex.try_write(|v| *v = 2), Err(WriteError::Poisoned));
+        assert!(movetex.clear_poison());
+        assert_eq!(*movetex.read(), 0);
+    }
+}
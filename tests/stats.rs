@@ -0,0 +1,52 @@
+#![cfg(feature = "stats")]
+
+use movetex::{Movetex, MovetexStats, ValidationError};
+
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1u32]);
+        assert_eq!(movetex.stats(), MovetexStats::default());
+
+        assert!(movetex.write(|v| v.push(2)));
+        assert_eq!(movetex.read().len(), 2);
+        assert_eq!(movetex.try_read().unwrap().len(), 2);
+        assert_eq!(movetex.swap(vec![3]), Ok(vec![1, 2]));
+        assert!(movetex.publish());
+        // Printing the instance does not count as a read.
+        assert_eq!(format!("{movetex:?}"), "Movetex { value: [3], version: 2 }");
+
+        let stats = movetex.stats();
+        assert_eq!(
+            (stats.reads, stats.writes, stats.failed_writes, stats.swaps),
+            (2, 2, 0, 1)
+        );
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(vec![1u32])
+            .validator(|v: &Vec<u32>| {
+                if v.is_empty() {
+                    return Err(ValidationError::new("list must not be empty"));
+                }
+                Ok(())
+            })
+            .build();
+
+        assert!(!movetex.write(|v| v.clear()));
+        let transaction = movetex.begin_write().unwrap();
+        assert!(!movetex.write(|v| v.push(2)));
+        assert_eq!(movetex.swap(vec![]), Err(vec![]));
+        transaction.abort();
+
+        let uninit = Movetex::<u32>::uninit();
+        assert!(!uninit.write(|v| *v += 1));
+
+        let stats = movetex.stats();
+        assert_eq!((stats.writes, stats.failed_writes, stats.swaps), (0, 3, 0));
+        assert_eq!(uninit.stats().failed_writes, 1);
+    }
+}
@@ -0,0 +1,61 @@
+use movetex::Movetex;
+
+#[derive(Default, Debug)]
+struct Service {
+    name: String,
+    limits: Movetex<Vec<u32>>,
+}
+
+mod std_traits_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let service = Service::default();
+        assert!(service.name.is_empty());
+        assert!(service.limits.read().is_empty());
+        assert!(service.limits.write(|v| v.push(1)));
+        assert_eq!(
+            format!("{service:?}"),
+            r#"Service { name: "", limits: Movetex { value: [1], version: 1 } }"#
+        );
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex: Movetex<String> = String::from("a").into();
+        assert_eq!(*movetex.read(), "a");
+        assert_eq!(
+            format!("{:?}", Movetex::<u8>::uninit()),
+            "Movetex { value: <uninitialized>, version: 0 }"
+        );
+    }
+}
+
+mod clone_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let original = Movetex::new(vec![1]);
+        original.write(|v| v.push(2));
+        original.swap(vec![9]).unwrap();
+
+        let copy = original.clone();
+        assert_eq!(*copy.read(), [1, 2]);
+        assert_eq!(copy.version(), 0);
+        assert!(!copy.is_dirty());
+
+        copy.write(|v| v.push(3));
+        assert_eq!(*original.read(), [1, 2]);
+        assert_eq!(*copy.read(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let copy = Movetex::<u8>::uninit().clone();
+        assert!(!copy.is_initialized());
+        copy.initialize(1).unwrap();
+        assert_eq!(*copy.read(), 1);
+    }
+}
@@ -0,0 +1,52 @@
+use movetex::{MockClock, Movetex, WriteError};
+use std::thread;
+use std::time::Duration;
+
+mod max_write_duration_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1])
+            .max_write_duration(Duration::from_millis(5))
+            .build();
+        assert_eq!(
+            movetex.try_write(|v| {
+                v.push(2);
+                thread::sleep(Duration::from_millis(20));
+            }),
+            Err(WriteError::TimedOut)
+        );
+        assert_eq!(*movetex.read(), [1]);
+        assert_eq!(movetex.version(), 0);
+
+        // The discarded modification does not leak into the next write.
+        assert_eq!(movetex.try_write(|v| v.push(3)), Ok(()));
+        assert_eq!(*movetex.read(), [1, 3]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let clock = MockClock::new();
+        let movetex = Movetex::builder(1u32)
+            .clock(clock.clone())
+            .max_write_duration(Duration::from_secs(5))
+            .build();
+        // The limit is measured on the clock of the instance, not in real time.
+        assert_eq!(
+            movetex.try_write(|v| {
+                *v = 2;
+                clock.advance(Duration::from_secs(6));
+            }),
+            Err(WriteError::TimedOut)
+        );
+        assert_eq!(
+            movetex.try_write(|v| {
+                *v = 3;
+                thread::sleep(Duration::from_millis(10));
+            }),
+            Ok(())
+        );
+        assert_eq!(*movetex.read(), 3);
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "tracing")]
+
+use movetex::Movetex;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the message and fields of every event and span as one line each.
+#[derive(Clone, Default)]
+struct Recorder {
+    lines: Arc<Mutex<Vec<String>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+#[derive(Default)]
+struct Line(String);
+
+impl Visit for Line {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.0.insert_str(0, &format!("{value:?}")),
+            name => self.0.push_str(&format!(" {name}={value:?}")),
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "movetex"
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut line = Line(format!("span {}", span.metadata().name()));
+        span.record(&mut line);
+        self.lines.lock().unwrap().push(line.0);
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line::default();
+        event.record(&mut line);
+        self.lines.lock().unwrap().push(line.0);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+mod tracing_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let movetex = Movetex::new(1u32);
+            assert!(movetex.write(|v| *v = 2));
+            assert_eq!(movetex.swap(3), Ok(2));
+            assert!(movetex.seal());
+            assert!(!movetex.write(|v| *v = 4));
+        });
+        assert_eq!(
+            *recorder.lines.lock().unwrap(),
+            [
+                "span write type_name=\"u32\" version=0",
+                "write acquired",
+                "published type_name=\"u32\" version=1",
+                "swapped type_name=\"u32\" version=1",
+                "write refused type_name=\"u32\" version=1 error=movetex is sealed",
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use movetex::{begin_write_all, commit_all, read_pair, Movetex, ValidationError, WriteError};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+#[derive(Default)]
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+mod transaction_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1]);
+        let mut tx = movetex.begin_write().unwrap();
+        tx.push(2);
+        assert_eq!(*movetex.read(), [1]);
+        assert_eq!(movetex.try_write(|_| unreachable!()), Err(WriteError::Busy));
+        assert!(movetex.begin_write().is_none());
+
+        tx.commit().unwrap();
+        assert_eq!(*movetex.read(), [1, 2]);
+        assert_eq!(movetex.version(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(vec![1]);
+        let mut tx = movetex.begin_write().unwrap();
+        tx.push(2);
+        drop(tx);
+        assert_eq!(movetex.version(), 0);
+
+        assert!(movetex.write(|v| v.push(3)));
+        assert_eq!(*movetex.read(), [1, 3]);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::builder(0)
+            .validator(|v: &i32| match *v {
+                0..=9 => Ok(()),
+                _ => Err(ValidationError::new("too large")),
+            })
+            .build();
+        let mut tx = movetex.begin_write().unwrap();
+        *tx = 10;
+        assert!(matches!(tx.commit(), Err(WriteError::Rejected(_))));
+        assert_eq!(*movetex.read(), 0);
+
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(*movetex.read(), 1);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let movetex = Movetex::new(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut tx = movetex.begin_write().unwrap();
+            *tx = 1;
+            panic!("failed halfway");
+        }));
+        assert!(result.is_err());
+        assert!(movetex.is_poisoned());
+        assert!(movetex.begin_write().is_none());
+
+        assert!(movetex.clear_poison());
+        let tx = movetex.begin_write().unwrap();
+        assert_eq!(*tx, 0);
+        tx.abort();
+    }
+}
+
+mod write_guard_tests {
+    use super::*;
+
+    fn append(list: &mut Vec<u32>, item: &str) -> Result<(), std::num::ParseIntError> {
+        list.push(item.parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(vec![1]);
+        let result = (|| {
+            let mut guard = movetex.try_write_guard().unwrap();
+            append(&mut guard, "2")?;
+            assert!(movetex.try_write_guard().is_none());
+            append(&mut guard, "x")
+        })();
+        assert!(result.is_err());
+        // The guard publishes on the early return as well.
+        assert_eq!(*movetex.read(), [1, 2]);
+        assert_eq!(movetex.version(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(0)
+            .validator(|v: &i32| match *v {
+                0..=9 => Ok(()),
+                _ => Err(ValidationError::new("too large")),
+            })
+            .build();
+        let mut guard = movetex.try_write_guard().unwrap();
+        *guard = 10;
+        assert!(matches!(guard.publish(), Err(WriteError::Rejected(_))));
+
+        let mut guard = movetex.try_write_guard().unwrap();
+        *guard += 1;
+        guard.publish().unwrap();
+        assert_eq!(*movetex.read(), 1);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut guard = movetex.try_write_guard().unwrap();
+            *guard = 1;
+            panic!("failed halfway");
+        }));
+        assert!(result.is_err());
+        assert!(movetex.is_poisoned());
+        assert_eq!(*movetex.read(), 0);
+    }
+}
+
+mod async_transaction_tests {
+    use super::*;
+
+    fn assert_send<F: Future + Send>(future: F) -> F {
+        future
+    }
+
+    /// Starting writes waits for a write owning one of the instances, without holding the
+    /// other one meanwhile, and resumes once it finishes.
+    #[test]
+    fn test_t_0() {
+        let a = Movetex::new(1u32);
+        let b = Movetex::new(String::from("x"));
+        let flag = Arc::new(Flag::default());
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let busy = b.begin_write().unwrap();
+        let mut begin = pin!(assert_send(begin_write_all!(&a, &b)));
+        assert!(begin.as_mut().poll(&mut cx).is_pending());
+        assert!(a.write(|v| *v = 2));
+
+        drop(busy);
+        assert!(flag.0.load(Ordering::SeqCst));
+        let Poll::Ready(Ok((mut a_tx, mut b_tx))) = begin.as_mut().poll(&mut cx) else {
+            panic!("both writer copies are free");
+        };
+        assert_eq!(a.try_write(|_| {}), Err(WriteError::Busy));
+        *a_tx += 1;
+        b_tx.push('y');
+        assert_eq!(commit_all!(a_tx, b_tx), Ok(()));
+
+        let (a, b) = read_pair(&a, &b);
+        assert_eq!((*a, b.as_str()), (3, "xy"));
+    }
+
+    /// A value the validator rejects discards every modification.
+    #[test]
+    fn test_t_1() {
+        let a = Movetex::builder(0u32)
+            .validator(|value: &u32| {
+                if *value < 10 {
+                    Ok(())
+                } else {
+                    Err(ValidationError::new("too large"))
+                }
+            })
+            .build();
+        let b = Movetex::new(0u32);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let Poll::Ready(Ok((mut a_tx, mut b_tx))) = pin!(begin_write_all!(&a, &b)).poll(&mut cx)
+        else {
+            panic!("both writer copies are free");
+        };
+        *a_tx = 10;
+        *b_tx = 10;
+        assert!(matches!(
+            commit_all!(a_tx, b_tx),
+            Err(WriteError::Rejected(_))
+        ));
+        assert_eq!((*a.read(), *b.read()), (0, 0));
+        assert!(a.write(|v| *v = 1) && b.write(|v| *v = 1));
+    }
+
+    #[test]
+    fn test_t_2() {
+        let a = Movetex::new(0u32);
+        let sealed = Movetex::new(0u32);
+        sealed.seal();
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let duplicate = pin!(begin_write_all!(&a, &a)).poll(&mut cx);
+        assert!(matches!(duplicate, Poll::Ready(Err(WriteError::Busy))));
+        let refused = pin!(begin_write_all!(&a, &sealed)).poll(&mut cx);
+        assert!(matches!(refused, Poll::Ready(Err(WriteError::Sealed))));
+        assert!(a.write(|v| *v = 1));
+
+        let single = pin!(a.begin_write_async()).poll(&mut cx);
+        let Poll::Ready(Ok(mut tx)) = single else {
+            panic!("the writer copy is free");
+        };
+        *tx += 1;
+        tx.commit().unwrap();
+        assert_eq!(*a.read(), 2);
+    }
+}
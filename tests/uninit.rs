@@ -0,0 +1,108 @@
+use movetex::{AlreadyInitialized, AlreadyPublished, Movetex, NotInitialized, WriteError};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod uninit_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::<u32>::uninit();
+
+        assert!(!movetex.is_initialized());
+        assert_eq!(movetex.try_read(), Err(NotInitialized));
+        assert_eq!(
+            movetex.try_write(|v| *v = 1),
+            Err(WriteError::NotInitialized)
+        );
+        assert_eq!(movetex.swap(1), Err(1));
+
+        assert_eq!(movetex.initialize(42), Ok(()));
+        assert_eq!(movetex.initialize(43), Err(AlreadyInitialized(43)));
+        assert_eq!(*movetex.read(), 42);
+        assert!(movetex.write(|v| *v += 1));
+        assert_eq!(movetex.try_read().as_deref(), Ok(&43));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Arc::new(Movetex::<String>::uninit());
+
+        std::thread::scope(|s| {
+            let m = Arc::clone(&movetex);
+            s.spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                m.initialize(String::from("ready")).unwrap();
+            });
+
+            assert_eq!(
+                movetex
+                    .read_timeout(Duration::from_secs(10))
+                    .as_deref()
+                    .map(String::as_str),
+                Ok("ready")
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "movetex has not been initialized")]
+    fn test_t_2() {
+        let movetex = Movetex::<u32>::uninit();
+        movetex.read();
+    }
+}
+
+mod fallback_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::<&str>::uninit();
+        assert_eq!(*movetex.read_or(&"fallback"), "fallback");
+
+        movetex.initialize("value").unwrap();
+        assert_eq!(*movetex.read_or(&"fallback"), "value");
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::uninit_builder()
+            .fallback(String::from("default"))
+            .build();
+
+        assert_eq!(*movetex.read(), "default");
+        assert_eq!(movetex.try_read(), Err(NotInitialized));
+
+        movetex.initialize(String::from("loaded")).unwrap();
+        assert_eq!(*movetex.read(), "loaded");
+    }
+}
+
+mod publish_once_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::<u32>::uninit();
+        assert_eq!(movetex.publish_once(1), Ok(()));
+        assert_eq!(movetex.publish_once(2), Err(AlreadyPublished(2)));
+        assert_eq!(movetex.initialize(3), Err(AlreadyInitialized(3)));
+        assert_eq!(movetex.try_write(|v| *v = 4), Err(WriteError::Sealed));
+        assert_eq!(movetex.swap(5), Err(5));
+        assert_eq!(*movetex.read(), 1);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit_builder().write_once().build();
+        assert!(!movetex.is_sealed());
+        movetex.initialize(1).unwrap();
+        assert!(movetex.is_sealed());
+        assert!(!movetex.write(|v| *v = 2));
+
+        let movetex = Movetex::builder(1).write_once().build();
+        assert!(movetex.is_sealed());
+        assert_eq!(*movetex.read(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+use movetex::{Movetex, ValidationError, WriteError};
+
+fn non_empty() -> impl Fn(&Vec<u32>) -> Result<(), ValidationError> + Send + Sync {
+    |v| {
+        if v.is_empty() {
+            return Err(ValidationError::new("list must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+mod validator_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1]).validator(non_empty()).build();
+
+        assert_eq!(movetex.try_write(|v| v.push(2)), Ok(()));
+        assert_eq!(*movetex.read(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::builder(vec![1]).validator(non_empty()).build();
+
+        assert_eq!(
+            movetex.try_write(|v| v.clear()),
+            Err(WriteError::Rejected(ValidationError::new(
+                "list must not be empty"
+            )))
+        );
+        assert_eq!(*movetex.read(), vec![1]);
+
+        // The rejected modification must not leak into the next write.
+        assert!(movetex.write(|v| v.push(3)));
+        assert_eq!(*movetex.read(), vec![1, 3]);
+    }
+}
+
+mod try_write_with_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::builder(vec![1, 2]).validator(non_empty()).build();
+
+        assert_eq!(movetex.try_write_with(|v| v.pop()), Ok(Some(2)));
+        assert!(matches!(
+            movetex.try_write_with(|v| v.pop()),
+            Err(WriteError::Rejected(_))
+        ));
+        assert_eq!(*movetex.read(), vec![1]);
+    }
+}
+
+#[cfg(feature = "jsonschema")]
+mod json_schema_tests {
+    use super::*;
+    use movetex::JsonSchemaValidator;
+    use serde_json::json;
+
+    #[test]
+    fn test_t_0() {
+        let schema = json!({
+            "type": "array",
+            "items": { "type": "integer", "maximum": 10 },
+            "minItems": 1
+        });
+        let movetex = Movetex::builder(vec![1u32])
+            .validator(JsonSchemaValidator::new(&schema).unwrap())
+            .build();
+
+        assert!(movetex.write(|v| v.push(10)));
+        assert!(matches!(
+            movetex.try_write(|v| v.push(11)),
+            Err(WriteError::Rejected(_))
+        ));
+        assert_eq!(*movetex.read(), vec![1, 10]);
+    }
+}
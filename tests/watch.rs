@@ -0,0 +1,272 @@
+use movetex::{Backpressure, Delivery, Diff, MapDiff, Movetex, NotInitialized, WriteError};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+mod subscription_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(0);
+        let mut subscription = movetex.subscribe();
+        assert!(!subscription.has_changed());
+        assert!(subscription
+            .wait_timeout(Duration::from_millis(10))
+            .is_none());
+
+        movetex.write(|v| *v = 1);
+        movetex.write(|v| *v = 2);
+        assert!(subscription.has_changed());
+        assert_eq!(*subscription.borrow_and_update(), 2);
+        assert!(!subscription.has_changed());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::<u32>::uninit();
+        let mut subscription = movetex.subscribe();
+        thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(20));
+                movetex.initialize(5).unwrap();
+            });
+            assert_eq!(*subscription.wait(), 5);
+        });
+    }
+
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(0);
+        let mut subscription = movetex.subscribe();
+        let flag = Arc::new(Flag::default());
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        {
+            let mut changed = pin!(subscription.changed());
+            assert_eq!(changed.as_mut().poll(&mut cx), Poll::Pending);
+            movetex.write(|v| *v = 1);
+            assert!(flag.0.load(Ordering::SeqCst));
+            assert_eq!(changed.as_mut().poll(&mut cx), Poll::Ready(()));
+        }
+        assert_eq!(*subscription.borrow_and_update(), 1);
+    }
+
+    #[test]
+    fn test_t_3() {
+        let movetex = Movetex::new(0u32);
+        thread::scope(|s| {
+            let mut subscription = movetex.subscribe();
+            s.spawn(|| {
+                for i in 1..=1_000 {
+                    movetex.write_blocking(|v| *v = i).unwrap();
+                }
+            });
+            let mut last = 0;
+            while last != 1_000 {
+                let value = *subscription.wait();
+                assert!(value > last);
+                last = value;
+            }
+        });
+    }
+}
+
+mod backpressure_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(0);
+        let fail = |v| movetex.publish_with_backpressure(move |x| *x = v, Backpressure::Fail);
+        assert_eq!(fail(1), Ok(()));
+
+        let mut subscription = movetex.subscribe();
+        assert_eq!(fail(2), Ok(()));
+        assert_eq!(fail(3), Err(WriteError::Lagging));
+        assert_eq!(
+            movetex.publish_with_backpressure(
+                |x| *x = 3,
+                Backpressure::WaitTimeout(Duration::from_millis(5))
+            ),
+            Err(WriteError::Lagging)
+        );
+        assert_eq!(*subscription.borrow_and_update(), 2);
+        assert_eq!(fail(3), Ok(()));
+
+        assert_eq!(
+            movetex.publish_with_backpressure(|x| *x = 4, Backpressure::Skip),
+            Ok(())
+        );
+        drop(subscription);
+        assert_eq!(fail(5), Ok(()));
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0u32);
+        let mut subscription = movetex.subscribe();
+        let mut received = Vec::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..=50 {
+                    movetex
+                        .publish_with_backpressure(|v| *v = i, Backpressure::Wait)
+                        .unwrap();
+                }
+            });
+            while received.last() != Some(&50) {
+                received.push(*subscription.wait());
+            }
+        });
+        // Waiting for the subscriber delivers every value.
+        assert_eq!(received, (1..=50).collect::<Vec<_>>());
+    }
+}
+
+mod diff_subscription_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(HashMap::from([(1, "a"), (2, "b")]));
+        let mut subscription = movetex.subscribe_diffs().unwrap();
+        let mut replica = movetex.read().clone();
+        assert!(subscription.diff_and_update().is_none());
+
+        // Publishes made in between arrive as one diff to the latest value.
+        movetex.write(|map| {
+            map.insert(3, "c");
+        });
+        movetex.write(|map| {
+            map.remove(&1);
+            map.insert(2, "B");
+        });
+        let mut diff = subscription.diff_and_update().unwrap();
+        diff.changed.sort();
+        assert_eq!(
+            diff,
+            MapDiff {
+                changed: vec![(2, "B"), (3, "c")],
+                removed: vec![1],
+            }
+        );
+        replica.apply_diff(diff);
+        assert_eq!(replica, *movetex.read());
+        assert!(!subscription.has_changed());
+
+        // A publish of an equal value is a change with an empty diff.
+        movetex.write(|_| {});
+        assert!(subscription.diff_and_update().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_t_1() {
+        assert_eq!(
+            Movetex::<HashMap<u8, u8>>::uninit().subscribe_diffs().err(),
+            Some(NotInitialized)
+        );
+
+        let movetex = Movetex::new(HashMap::new());
+        let mut subscription = movetex.subscribe_diffs().unwrap();
+        assert!(subscription
+            .wait_timeout(Duration::from_millis(10))
+            .is_none());
+        let mut replica = HashMap::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 0..20u32 {
+                    movetex.write(|map| {
+                        map.insert(i % 5, i);
+                    });
+                }
+            });
+            while replica.get(&4) != Some(&19) {
+                replica.apply_diff(subscription.wait());
+            }
+        });
+        assert_eq!(replica, *movetex.read());
+    }
+}
+
+mod delivery_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        let movetex = Movetex::new(0u32);
+        let mut latest = movetex.subscribe();
+        let mut queued = movetex.subscribe_with(Delivery::DropOldest(3));
+        for i in 1..=5 {
+            movetex.write(|v| *v = i);
+        }
+        assert_eq!((latest.lag(), latest.dropped()), (5, 0));
+        assert_eq!((queued.lag(), queued.dropped()), (5, 2));
+
+        assert_eq!(*latest.borrow_and_update(), 5);
+        assert_eq!((latest.seen_version(), latest.lag()), (5, 0));
+
+        // The queue delivers the values it kept in publish order, then the published one.
+        let first = queued.borrow_and_update();
+        assert_eq!((*first, queued.seen_version(), queued.lag()), (3, 3, 2));
+        assert_eq!(*queued.borrow_and_update(), 4);
+        movetex.write(|v| *v = 6);
+        assert_eq!(*first, 3);
+        let rest: Vec<_> = (0..3).map(|_| *queued.borrow_and_update()).collect();
+        assert_eq!(rest, [5, 6, 6]);
+        assert!(!queued.has_changed());
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(0u32);
+        let mut subscription = movetex.subscribe_with(Delivery::Block);
+        let mut received = Vec::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..=50 {
+                    while !movetex.write(|v| *v = i) {
+                        thread::yield_now();
+                    }
+                }
+            });
+            while received.last() != Some(&50) {
+                received.push(*subscription.wait());
+            }
+        });
+        // Plain writes wait for a blocking subscription, so it sees every value.
+        assert_eq!(received, (1..=50).collect::<Vec<_>>());
+        drop(subscription);
+        assert!(movetex.write(|v| *v = 51));
+        assert!(movetex.write(|v| *v = 52));
+    }
+
+    /// Queued values stay readable until taken, and dropping the subscription releases them.
+    #[test]
+    fn test_t_2() {
+        let movetex = Movetex::new(String::from("a"));
+        let mut queued = movetex.subscribe_with(Delivery::DropOldest(10));
+        for c in ['b', 'c', 'd'] {
+            movetex.write(|v| v.push(c));
+        }
+        assert_eq!(*queued.borrow_and_update(), "ab");
+        drop(queued);
+        movetex.write(|v| v.push('e'));
+        assert_eq!(*movetex.read(), "abcde");
+    }
+}
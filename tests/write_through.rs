@@ -0,0 +1,99 @@
+use movetex::{Movetex, PersistFailure, PersistOrder, Store, WriteThrough, WriteThroughError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+mod write_through_tests {
+    use super::*;
+
+    struct Recording {
+        stored: Arc<Mutex<Vec<u32>>>,
+        fail: bool,
+    }
+
+    impl Store<u32> for Recording {
+        type Error = &'static str;
+
+        fn persist(&mut self, value: &u32) -> Result<(), &'static str> {
+            if self.fail {
+                return Err("disk full");
+            }
+            self.stored.lock().unwrap().push(*value);
+            Ok(())
+        }
+    }
+
+    fn recording(fail: bool) -> (Arc<Mutex<Vec<u32>>>, Recording) {
+        let stored = Arc::new(Mutex::new(Vec::new()));
+        let store = Recording {
+            stored: Arc::clone(&stored),
+            fail,
+        };
+        (stored, store)
+    }
+
+    #[test]
+    fn test_t_0() {
+        let (stored, store) = recording(false);
+        let through = WriteThrough::new(Movetex::new(1), store);
+        through.write(|v| *v = 2).unwrap();
+        through.write(|v| *v = 3).unwrap();
+        assert_eq!(*stored.lock().unwrap(), vec![2, 3]);
+        assert_eq!(*through.read(), 3);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let (_, store) = recording(true);
+        let through = WriteThrough::new(Movetex::new(1), store);
+        assert!(matches!(
+            through.write(|v| *v = 2),
+            Err(WriteThroughError::Persist("disk full"))
+        ));
+        assert_eq!(*through.read(), 1);
+        // The rejected value was discarded from the writer copy as well.
+        through.movetex().write(|v| *v += 10);
+        assert_eq!(*through.read(), 11);
+    }
+
+    #[test]
+    fn test_t_2() {
+        let (_, store) = recording(true);
+        let through = WriteThrough::new(Movetex::new(1), store).on_failure(PersistFailure::Publish);
+        assert!(through.write(|v| *v = 2).is_err());
+        assert_eq!(*through.read(), 2);
+
+        let (stored, store) = recording(false);
+        let through =
+            WriteThrough::new(Movetex::new(1), store).order(PersistOrder::PublishThenPersist);
+        through.write(|v| *v = 5).unwrap();
+        let (movetex, _) = through.into_inner();
+        assert_eq!(*movetex.read(), 5);
+        assert_eq!(*stored.lock().unwrap(), vec![5]);
+    }
+
+    #[test]
+    fn test_t_3() {
+        // Direct writes publishing between a publish and its persist are not persisted in
+        // place of the values written through.
+        let (stored, store) = recording(false);
+        let through =
+            WriteThrough::new(Movetex::new(0), store).order(PersistOrder::PublishThenPersist);
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    through.movetex().write(|v| *v = 0);
+                    thread::yield_now();
+                }
+            });
+            for i in 1..=200 {
+                while let Err(WriteThroughError::Write(_)) = through.write(|v| *v = i) {
+                    thread::yield_now();
+                }
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(*stored.lock().unwrap(), (1..=200).collect::<Vec<_>>());
+    }
+}
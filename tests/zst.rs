@@ -0,0 +1,34 @@
+use movetex::{Movetex, SpillPolicy};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Tick;
+
+mod zst_tests {
+    use super::*;
+
+    #[test]
+    fn test_t_0() {
+        assert_eq!(Movetex::<Tick>::LAYOUT.writer_copy_size, 0);
+
+        let movetex = Movetex::builder(Tick).spill(SpillPolicy::always()).build();
+        let mut subscription = movetex.subscribe();
+        for _ in 0..3 {
+            assert!(movetex.write(|_| {}));
+        }
+        assert!(!movetex.is_spilled());
+        assert!(subscription.has_changed());
+        let (_, meta) = movetex.read_meta();
+        assert_eq!(meta.version, 3);
+        assert_eq!(*subscription.borrow_and_update(), Tick);
+    }
+
+    #[test]
+    fn test_t_1() {
+        let movetex = Movetex::new(());
+        assert_eq!(movetex.swap(()), Ok(()));
+        assert!(movetex.is_dirty());
+        assert!(movetex.write(|_| {}));
+        assert!(!movetex.is_dirty());
+        assert_eq!(movetex.swap(()), Ok(()));
+    }
+}